@@ -156,12 +156,12 @@ pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
             match cmd {
                 #[cfg(feature = "codegen")]
                 ChainCommandOption::Codegen(c) => c.run(current, &discovery, &storage),
-                ChainCommandOption::Process(c) => c.run(current, &storage).inspect(|_| {
+                ChainCommandOption::Process(c) => c.run(current, &storage, client).inspect(|_| {
                     storage
                         .schemas
                         .insert(current.get_url().clone(), current.clone());
                 }),
-                ChainCommandOption::Validate(v) => v.run(current),
+                ChainCommandOption::Validate(v) => v.run(current, &storage, client),
                 ChainCommandOption::Output(o) => {
                     o.output.show(current.get_body());
                     Ok(())
@@ -1,4 +1,6 @@
 use schematools::codegen::jsonschema::JsonSchemaExtractOptions;
+use schematools::process::name::word::Dictionary;
+use schematools::scope::SchemaNamingStrategy;
 use schematools::Client;
 use serde_json::Value;
 use std::{fmt::Display, time::Instant};
@@ -64,6 +66,10 @@ pub struct JsonSchemaOpts {
     #[clap(long)]
     pub base_name: Option<String>,
 
+    /// Naming strategy: default, operation, path or template like {operation}{form}{property}
+    #[clap(long, default_value = "default")]
+    naming_strategy: SchemaNamingStrategy,
+
     /// Directory with templates, name:: prefix if pointing to registry
     #[clap(long, required = true)]
     template: Vec<String>,
@@ -76,6 +82,10 @@ pub struct JsonSchemaOpts {
     #[clap(long)]
     pub format: Option<String>,
 
+    /// Path to json/yaml dictionary of irregular and uncountable words used by plural filter
+    #[clap(long)]
+    dictionary: Option<String>,
+
     #[clap(short = 'o', value_parser = super::get_options::<String>, number_of_values = 1)]
     options: Vec<(String, Value)>,
 
@@ -104,6 +114,10 @@ pub struct OpenapiOpts {
     #[clap(long, required = false)]
     keep_schema: Vec<String>,
 
+    /// Naming strategy: default, operation, path or template like {operation}{form}{property}
+    #[clap(long, default_value = "default")]
+    naming_strategy: SchemaNamingStrategy,
+
     /// Directory with templates, name:: prefix if pointing to registry
     #[clap(long, required = true)]
     template: Vec<String>,
@@ -116,6 +130,10 @@ pub struct OpenapiOpts {
     #[clap(long)]
     pub format: Option<String>,
 
+    /// Path to json/yaml dictionary of irregular and uncountable words used by plural filter
+    #[clap(long)]
+    dictionary: Option<String>,
+
     #[clap(short = 'o', value_parser = super::get_options::<String>, number_of_values = 1)]
     options: Vec<(String, Value)>,
 
@@ -166,6 +184,7 @@ impl Opts {
                         base_name: opts.base_name.clone(),
                         allow_list: true,
                         keep_schema: schematools::tools::Filter::new(&opts.keep_schema)?,
+                        naming_strategy: opts.naming_strategy.clone(),
                     },
                 )?;
 
@@ -180,6 +199,7 @@ impl Opts {
                     discovery.resolve(&opts.template)?,
                     &[codegen::templates::TemplateType::Models],
                     codegen::create_container(&opts.options),
+                    &load_dictionary(&opts.dictionary)?,
                 )?;
 
                 renderer
@@ -204,6 +224,7 @@ impl Opts {
                         optional_and_nullable_as_models: opts.optional_and_nullable_as_models,
                         nested_arrays_as_models: opts.nested_arrays_as_models,
                         keep_schema: schematools::tools::Filter::new(&opts.keep_schema)?,
+                        naming_strategy: opts.naming_strategy.clone(),
                     },
                 )?;
 
@@ -223,6 +244,7 @@ impl Opts {
                         codegen::templates::TemplateType::Endpoints,
                     ],
                     codegen::create_container(&opts.options),
+                    &load_dictionary(&opts.dictionary)?,
                 )?;
 
                 renderer
@@ -258,3 +280,11 @@ pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
         }
     }
 }
+
+fn load_dictionary(path: &Option<String>) -> Result<Dictionary, Error> {
+    path.as_deref()
+        .map(Dictionary::load)
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(Error::Schematools)
+}
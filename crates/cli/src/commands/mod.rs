@@ -4,6 +4,7 @@ use std::io::prelude::*;
 
 use clap::{Parser, ValueEnum};
 use env_logger::Builder as LoggerBuilder;
+use schematools::canonical;
 use schematools::Client;
 use serde_json::Value;
 
@@ -48,6 +49,15 @@ where
     }
 }
 
+/// Parse a single key-value pair of plain strings
+fn get_pair(s: &str) -> Result<(String, String), Box<dyn StdError + Send + Sync + 'static>> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("invalid KEY=value: no `=` found in `{s}`"))?;
+
+    Ok((key.to_string(), value.to_string()))
+}
+
 #[derive(Clone, Debug, Parser)]
 pub struct Verbosity {
     /// Verbosity level, increase by multiplying v occurrences (warning, info, debug, trace)
@@ -82,7 +92,43 @@ impl Verbosity {
     }
 }
 
-#[derive(Clone, Debug, Parser)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+enum SortValues {
+    #[default]
+    Alphabetical,
+    CaseInsensitive,
+    Preserve,
+}
+
+impl From<SortValues> for canonical::SortOrder {
+    fn from(value: SortValues) -> Self {
+        match value {
+            SortValues::Alphabetical => Self::Alphabetical,
+            SortValues::CaseInsensitive => Self::CaseInsensitive,
+            SortValues::Preserve => Self::Preserve,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+enum QuoteValues {
+    #[default]
+    Auto,
+    Single,
+    Double,
+}
+
+impl From<QuoteValues> for canonical::Quote {
+    fn from(value: QuoteValues) -> Self {
+        match value {
+            QuoteValues::Auto => Self::Auto,
+            QuoteValues::Single => Self::Single,
+            QuoteValues::Double => Self::Double,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Parser)]
 pub(crate) struct Output {
     /// Returned format
     #[arg(value_enum, short, long, default_value = "json")]
@@ -90,16 +136,52 @@ pub(crate) struct Output {
     /// Path of output file, default output to stdout
     #[clap(long)]
     to_file: Option<String>,
+    /// Writes keys in conventional order and sorts paths, components and properties
+    #[clap(long)]
+    canonical: bool,
+    /// Order of paths, components and properties in canonical output
+    #[arg(value_enum, long, default_value = "alphabetical")]
+    sort: SortValues,
+    /// Indentation of yaml output
+    #[clap(long, default_value = "2")]
+    yaml_indent: usize,
+    /// Writes yaml sequences of scalars in flow style, ex. [a, b]
+    #[clap(long)]
+    yaml_flow_sequences: bool,
+    /// Quoting of yaml strings
+    #[arg(value_enum, long, default_value = "auto")]
+    yaml_quote: QuoteValues,
 }
 
 impl Output {
     pub fn show(self: &Output, value: &Value) {
+        let canonical;
+        let value = if self.canonical {
+            canonical = canonical::canonicalize(value, self.sort.into());
+            &canonical
+        } else {
+            value
+        };
+
+        let style = canonical::YamlStyle {
+            indent: self.yaml_indent,
+            flow_sequences: self.yaml_flow_sequences,
+            quote: self.yaml_quote.into(),
+        };
+
         let result = match self.output.as_str() {
             "json" => serde_json::to_string_pretty(value).unwrap(),
+            "yaml" if self.canonical || style != canonical::YamlStyle::default() => {
+                canonical::to_yaml(value, &style)
+            }
             "yaml" => serde_yaml::to_string(value).unwrap(),
             _ => panic!("Output format not supported"),
         };
 
+        self.write(&result);
+    }
+
+    pub fn write(self: &Output, result: &str) {
         match &self.to_file {
             Some(filename) => {
                 let mut file = File::create(filename).unwrap();
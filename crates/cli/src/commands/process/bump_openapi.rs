@@ -12,6 +12,7 @@ pub struct BumperOptions {
 pub enum BumpKind {
     #[value(alias = "x-version")]
     Xversion,
+    Semantic,
     Undefined,
 }
 
@@ -19,6 +20,7 @@ impl From<BumpKind> for schematools::process::bump_openapi::BumpKind {
     fn from(value: BumpKind) -> Self {
         match value {
             BumpKind::Xversion => Self::Xversion,
+            BumpKind::Semantic => Self::Semantic,
             BumpKind::Undefined => Self::Undefined,
         }
     }
@@ -8,11 +8,19 @@ use schematools::tools;
 use schematools::Client;
 
 use crate::error::Error;
-use schematools::process::{dereference, merge_allof, merge_openapi, name};
+use schematools::error::Error as SchematoolsError;
+use schematools::process::name::word::Dictionary;
+use schematools::process::{
+    changelog, convert_swagger, dedupe, dereference, diff, downgrade_openapi, extract_inline,
+    filter, merge_allof, merge_openapi, name, normalize_parameters, operations, prefix_paths,
+    publish, remove_unused, rename_schema, upgrade_jsonschema, upgrade_openapi,
+};
 use schematools::schema::{path_to_url, Schema};
+use schematools::scope::SchemaNamingStrategy;
 
 #[cfg(feature = "semver")]
 pub mod bump_openapi;
+pub mod overlay;
 pub mod patch;
 
 #[derive(Clone, Debug, Parser)]
@@ -25,6 +33,21 @@ impl Display for Opts {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.command {
             Command::MergeOpenapi(_) => write!(f, "merge_openapi"),
+            Command::PrefixPaths(_) => write!(f, "prefix_paths"),
+            Command::Diff(_) => write!(f, "diff"),
+            Command::Changelog(_) => write!(f, "changelog"),
+            Command::UpgradeOpenapi(_) => write!(f, "upgrade_openapi"),
+            Command::DowngradeOpenapi(_) => write!(f, "downgrade_openapi"),
+            Command::ConvertSwagger(_) => write!(f, "convert_swagger"),
+            Command::UpgradeJsonschema(_) => write!(f, "upgrade_jsonschema"),
+            Command::Filter(_) => write!(f, "filter"),
+            Command::RemoveUnused(_) => write!(f, "remove_unused"),
+            Command::RenameSchema(_) => write!(f, "rename_schema"),
+            Command::ExtractInline(_) => write!(f, "extract_inline"),
+            Command::Dedupe(_) => write!(f, "dedupe"),
+            Command::Publish(_) => write!(f, "publish"),
+            Command::Operations(_) => write!(f, "operations"),
+            Command::NormalizeParameters(_) => write!(f, "normalize_parameters"),
             #[cfg(feature = "semver")]
             Command::BumpOpenapi(_) => write!(f, "bump_openapi"),
             Command::MergeAllOf(_) => write!(f, "merge_allof"),
@@ -33,6 +56,7 @@ impl Display for Opts {
             Command::Name(_) => write!(f, "name"),
             #[cfg(feature = "json-patch")]
             Command::Patch(_) => write!(f, "patch"),
+            Command::Overlay(_) => write!(f, "overlay"),
         }
     }
 }
@@ -42,6 +66,51 @@ pub enum Command {
     /// Merges openapi specifications
     MergeOpenapi(MergeOpenapiOpts),
 
+    /// Prefixes all paths of openapi specification
+    PrefixPaths(PrefixPathsOpts),
+
+    /// Compares two versions of openapi specification and classifies changes
+    Diff(DiffOpts),
+
+    /// Renders markdown changelog between two versions of openapi specification
+    Changelog(ChangelogOpts),
+
+    /// Converts openapi 3.0 specification to 3.1
+    UpgradeOpenapi(UpgradeOpenapiOpts),
+
+    /// Converts openapi 3.1 specification to 3.0, reports lossy constructs
+    DowngradeOpenapi(DowngradeOpenapiOpts),
+
+    /// Converts swagger 2.0 specification to openapi 3.0
+    ConvertSwagger(ConvertSwaggerOpts),
+
+    /// Converts json schema draft-04/06/07 to 2020-12
+    UpgradeJsonschema(UpgradeJsonschemaOpts),
+
+    /// Keeps only selected operations and removes components, tags and security schemes no longer used
+    Filter(FilterOpts),
+
+    /// Removes components not reachable from paths, webhooks and security
+    RemoveUnused(RemoveUnusedOpts),
+
+    /// Renames components and rewrites all references to them, including external files
+    RenameSchema(RenameSchemaOpts),
+
+    /// Moves inline object, enum and oneOf schemas to components and replaces them with references
+    ExtractInline(ExtractInlineOpts),
+
+    /// Replaces structurally equal schemas with one canonical component
+    Dedupe(DedupeOpts),
+
+    /// Removes elements not visible to given audience from openapi specification
+    Publish(PublishOpts),
+
+    /// Edits operations selected by path, method, tag or filter
+    Operations(OperationsOpts),
+
+    /// Moves repeated parameters to components and shared ones to path items
+    NormalizeParameters(NormalizeParametersOpts),
+
     /// Bumps version of openapi specifications
     #[cfg(feature = "semver")]
     BumpOpenapi(BumpOpenapiOpts),
@@ -61,6 +130,9 @@ pub enum Command {
     // Apply json patch to schema
     #[cfg(feature = "json-patch")]
     Patch(PatchOpts),
+
+    /// Apply openapi overlay to schema
+    Overlay(OverlayOpts),
 }
 
 #[derive(Clone, Debug, Parser)]
@@ -80,6 +152,351 @@ pub struct MergeOpenapiOpts {
     #[clap(long)]
     add_version: Option<String>,
 
+    /// Prefix added to all paths of merged openapi
+    #[clap(long)]
+    prefix: Option<String>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct PrefixPathsOpts {
+    /// Path to json/yaml file
+    pub file: String,
+
+    /// Prefix added to all paths, ex. /billing
+    #[clap(long)]
+    prefix: String,
+
+    /// Strips prefix from the end of servers urls
+    #[clap(long)]
+    strip_servers: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct DiffOpts {
+    /// Path to json/yaml file with new version of openapi specification
+    pub file: String,
+
+    /// Path to previous version of openapi specification
+    #[clap(long)]
+    original: String,
+
+    /// Prints human readable report instead of json/yaml
+    #[clap(long)]
+    human: bool,
+
+    /// Exits with error when breaking changes were found
+    #[clap(long)]
+    fail_on_breaking: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct ChangelogOpts {
+    /// Path to json/yaml file with new version of openapi specification
+    pub file: String,
+
+    /// Path to previous version of openapi specification
+    #[clap(long)]
+    original: String,
+
+    /// Title of changelog
+    #[clap(long, default_value = "Changelog")]
+    title: String,
+
+    /// Path to tera template used instead of built-in markdown
+    #[clap(long)]
+    template: Option<String>,
+
+    /// Path of output file, default output to stdout
+    #[clap(long)]
+    to_file: Option<String>,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct UpgradeOpenapiOpts {
+    /// Path to json/yaml file with openapi 3.0 specification
+    pub file: String,
+
+    /// Value of jsonSchemaDialect, default is openapi 3.1 base dialect
+    #[clap(long)]
+    dialect: Option<String>,
+
+    /// Writes upgraded external files referenced by specification in place
+    #[clap(long)]
+    write: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct DowngradeOpenapiOpts {
+    /// Path to json/yaml file with openapi 3.1 specification
+    pub file: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct ConvertSwaggerOpts {
+    /// Path to json/yaml file with swagger 2.0 specification
+    pub file: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct UpgradeJsonschemaOpts {
+    /// Path to json/yaml file with json schema
+    pub file: String,
+
+    /// Writes upgraded external files referenced by schema in place
+    #[clap(long)]
+    write: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct FilterOpts {
+    /// Path to json/yaml file with openapi specification
+    pub file: String,
+
+    /// Keeps operations with given tag
+    #[clap(long, required = false)]
+    tag: Vec<String>,
+
+    /// Keeps operations of paths matching glob, ex. /users/**
+    #[clap(long, required = false)]
+    path: Vec<String>,
+
+    /// Keeps operations with given http method
+    #[clap(long, required = false)]
+    method: Vec<String>,
+
+    /// Keeps operations with given operationId
+    #[clap(long, required = false)]
+    operation_id: Vec<String>,
+
+    /// Keeps operations matching filter, ex. x-audience="partner"
+    #[clap(long, required = false)]
+    filter: Vec<String>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct RemoveUnusedOpts {
+    /// Path to json/yaml file with openapi specification
+    pub file: String,
+
+    /// Glob pattern of components which are never removed, ex. schemas/Error*
+    #[clap(long, required = false)]
+    keep: Vec<String>,
+
+    /// Prints list of unused components instead of removing them
+    #[clap(long)]
+    report: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct RenameSchemaOpts {
+    /// Path to json/yaml file with openapi specification
+    pub file: String,
+
+    /// Component to rename, ex. Pet=Animal or responses/Error=Problem (may be repeated)
+    #[clap(long, value_parser = crate::commands::get_pair, number_of_values = 1, required = true)]
+    rename: Vec<(String, String)>,
+
+    /// Writes schema and modified external files referenced by it in place
+    #[clap(long)]
+    write: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct ExtractInlineOpts {
+    /// Path to json/yaml file with openapi specification
+    pub file: String,
+
+    /// Naming strategy: default, operation, path or template like {operation}{form}{property}
+    #[clap(long, default_value = "default")]
+    naming_strategy: SchemaNamingStrategy,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct DedupeOpts {
+    /// Path to json/yaml file with openapi specification
+    pub file: String,
+
+    /// Keyword skipped when schemas are compared, ex. title or description (may be repeated)
+    #[clap(long)]
+    ignore: Vec<String>,
+
+    /// Prints groups of duplicated schemas instead of merging them
+    #[clap(long)]
+    report: bool,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct PublishOpts {
+    /// Path to json/yaml file with openapi specification
+    pub file: String,
+
+    /// Audience specification is published for, ex. partner
+    #[clap(long)]
+    audience: String,
+
+    /// Extension marking elements hidden from every audience
+    #[clap(long, default_value = "x-internal")]
+    internal_key: String,
+
+    /// Extension listing audiences element is visible to
+    #[clap(long, default_value = "x-audience")]
+    audience_key: String,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct OperationsOpts {
+    /// Path to json/yaml file with openapi specification
+    pub file: String,
+
+    /// Edits operations with given tag
+    #[clap(long, required = false)]
+    tag: Vec<String>,
+
+    /// Edits operations of paths matching glob, ex. /users/**
+    #[clap(long, required = false)]
+    path: Vec<String>,
+
+    /// Edits operations with given http method
+    #[clap(long, required = false)]
+    method: Vec<String>,
+
+    /// Edits operations with given operationId
+    #[clap(long, required = false)]
+    operation_id: Vec<String>,
+
+    /// Edits operations matching filter, ex. x-audience="partner"
+    #[clap(long, required = false)]
+    filter: Vec<String>,
+
+    /// Replaces tags of operation (may be repeated)
+    #[clap(long, required = false)]
+    set_tag: Vec<String>,
+
+    /// Adds tag to operation (may be repeated)
+    #[clap(long, required = false)]
+    add_tag: Vec<String>,
+
+    /// Adds security requirement, ex. oauth:read,write
+    #[clap(long, required = false)]
+    security: Vec<String>,
+
+    /// Adds parameter, name of components/parameters entry or reference
+    #[clap(long, required = false)]
+    parameter: Vec<String>,
+
+    /// Adds header to responses, name of components/headers entry or reference
+    #[clap(long, required = false)]
+    header: Vec<String>,
+
+    /// Marks operations as deprecated
+    #[clap(long)]
+    deprecated: bool,
+
+    /// Sets extension, ex. x-stability=beta or x-internal=~true (json value)
+    #[clap(long, value_parser = crate::commands::get_options::<String>, number_of_values = 1)]
+    extension: Vec<(String, serde_json::Value)>,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct NormalizeParametersOpts {
+    /// Path to json/yaml file with openapi specification
+    pub file: String,
+
+    /// Number of identical copies needed to move parameter to components
+    #[clap(long, default_value = "2")]
+    min_occurrences: usize,
+
+    /// Keeps parameters shared by every operation of path in operations
+    #[clap(long)]
+    skip_hoist: bool,
+
     #[clap(flatten)]
     output: crate::commands::Output,
 
@@ -121,6 +538,10 @@ pub struct MergeAllOfOpts {
     #[clap(long, required = false)]
     filter: Vec<String>,
 
+    /// Intersects constraints of allOf members and fails on unsatisfiable ones (merge-all-of only)
+    #[clap(long)]
+    semantic: bool,
+
     #[clap(flatten)]
     output: crate::commands::Output,
 
@@ -174,6 +595,22 @@ pub struct NameOpts {
     #[clap(long)]
     base_name: Option<String>,
 
+    /// Naming strategy: default, operation, path or template like {operation}{form}{property}
+    #[clap(long, default_value = "default")]
+    naming_strategy: SchemaNamingStrategy,
+
+    /// Template of operationId, ex. {verb}{Resource}{Subresource}By{Param}
+    #[clap(long)]
+    operation_id_template: Option<String>,
+
+    /// Verb used in operationId, ex. get:collection=list, patch=update
+    #[clap(long = "verb", value_parser = crate::commands::get_pair, number_of_values = 1)]
+    verbs: Vec<(String, String)>,
+
+    /// Path to json/yaml dictionary of irregular and uncountable words
+    #[clap(long)]
+    dictionary: Option<String>,
+
     #[clap(flatten)]
     output: crate::commands::Output,
 
@@ -196,6 +633,112 @@ pub struct PatchOpts {
     verbose: crate::commands::Verbosity,
 }
 
+#[derive(Clone, Debug, Parser)]
+pub struct OverlayOpts {
+    /// Path to json/yaml file with schema
+    file: String,
+
+    #[clap(subcommand)]
+    pub action: overlay::Action,
+
+    #[clap(flatten)]
+    output: crate::commands::Output,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+impl DiffOpts {
+    fn options(&self, client: &Client) -> Result<diff::DifferOptions, Error> {
+        let original = Schema::load_url_with_client(path_to_url(self.original.clone())?, client)?;
+
+        Ok(diff::Differ::options(original))
+    }
+
+    fn check(&self, report: &diff::Report) -> Result<(), Error> {
+        if self.fail_on_breaking && report.is_breaking() {
+            return Err(Error::Schematools(SchematoolsError::DiffBreakingChanges(
+                report.count(diff::Severity::Breaking),
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl RemoveUnusedOpts {
+    fn options(&self) -> remove_unused::CleanerOptions {
+        let mut options = remove_unused::Cleaner::options();
+        options
+            .with_keep(self.keep.clone())
+            .with_report(self.report);
+
+        options
+    }
+}
+
+impl DedupeOpts {
+    fn options(&self) -> dedupe::DeduplicatorOptions {
+        let mut options = dedupe::Deduplicator::options();
+        options
+            .with_ignore(self.ignore.clone())
+            .with_report(self.report);
+
+        options
+    }
+}
+
+impl OperationsOpts {
+    fn options(&self) -> Result<operations::EditorOptions, Error> {
+        let mut selector = filter::Selector::options();
+        selector
+            .with_tags(self.tag.clone())
+            .with_paths(self.path.clone())
+            .with_methods(self.method.clone())
+            .with_operation_ids(self.operation_id.clone())
+            .with_filter(tools::Filter::new(&self.filter)?);
+
+        let mut options = operations::Editor::options();
+        options
+            .with_selector(selector)
+            .with_tags(if self.set_tag.is_empty() {
+                None
+            } else {
+                Some(self.set_tag.clone())
+            })
+            .with_add_tags(self.add_tag.clone())
+            .with_security(self.security.clone())
+            .with_parameters(self.parameter.clone())
+            .with_headers(self.header.clone())
+            .with_deprecated(self.deprecated)
+            .with_extensions(self.extension.clone());
+
+        Ok(options)
+    }
+}
+
+impl ChangelogOpts {
+    fn options(&self, client: &Client) -> Result<changelog::ChangelogOptions, Error> {
+        let original = Schema::load_url_with_client(path_to_url(self.original.clone())?, client)?;
+        let template = self
+            .template
+            .as_ref()
+            .map(|path| {
+                std::fs::read_to_string(path).map_err(|_| {
+                    Error::Schematools(SchematoolsError::SchemaInvalidPath { path: path.clone() })
+                })
+            })
+            .transpose()?;
+
+        let mut options = changelog::Changelog::options(original);
+        options
+            .with_title(self.title.clone())
+            .with_template(template);
+
+        Ok(options)
+    }
+}
+
 impl GetSchemaCommand for Opts {
     fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
         match &self.command {
@@ -222,6 +765,81 @@ impl GetSchemaCommand for Opts {
                 client,
             )
             .map_err(Error::Schematools),
+            Command::PrefixPaths(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::Diff(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::Changelog(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::UpgradeOpenapi(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::DowngradeOpenapi(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::ConvertSwagger(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::UpgradeJsonschema(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::Filter(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::RemoveUnused(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::RenameSchema(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::ExtractInline(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::Dedupe(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::Publish(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::Operations(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
+            Command::NormalizeParameters(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
             #[cfg(feature = "semver")]
             Command::BumpOpenapi(opts) => Schema::load_url_with_client(
                 path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
@@ -249,19 +867,37 @@ impl GetSchemaCommand for Opts {
                 client,
             )
             .map_err(Error::Schematools),
+            Command::Overlay(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
         }
     }
 }
 
 impl Opts {
-    pub fn run(&self, schema: &mut Schema, storage: &SchemaStorage) -> Result<(), Error> {
+    pub fn run(
+        &self,
+        schema: &mut Schema,
+        storage: &SchemaStorage,
+        client: &Client,
+    ) -> Result<(), Error> {
         match &self.command {
             Command::MergeAllOf(opts) => {
-                merge_allof::Merger::options()
+                let conflicts = merge_allof::Merger::options()
                     .with_leave_invalid_properties(opts.leave_invalid_properties)
                     .with_filter(tools::Filter::new(&opts.filter)?)
+                    .with_semantic(opts.semantic)
                     .process(schema, storage);
-                Ok(())
+
+                if conflicts.is_empty() {
+                    Ok(())
+                } else {
+                    Err(Error::Schematools(SchematoolsError::AllOfConflicts(
+                        conflicts.iter().map(|c| c.to_string()).collect(),
+                    )))
+                }
             }
             Command::FlattenAllOf(opts) => {
                 flatten_allof::Merger::options()
@@ -276,16 +912,107 @@ impl Opts {
                 merge_openapi::Merger::options(merge)
                     .with_retag(opts.retag.clone())
                     .with_add_version(opts.add_version.clone())
+                    .with_prefix(opts.prefix.clone())
                     .process(schema)
                     .map_err(Error::Schematools)
             }
+            Command::PrefixPaths(opts) => prefix_paths::Prefixer::options(opts.prefix.clone())
+                .with_strip_servers(opts.strip_servers)
+                .process(schema)
+                .map_err(Error::Schematools),
+            Command::Diff(opts) => {
+                opts.options(client)?
+                    .process(schema, storage, client)
+                    .map_err(Error::Schematools)?;
+
+                let report = serde_json::from_value(schema.get_body().clone())
+                    .map_err(|e| Error::Schematools(SchematoolsError::SerdeJsonError(e)))?;
+
+                opts.check(&report)
+            }
+            Command::Changelog(opts) => opts
+                .options(client)?
+                .process(schema, storage, client)
+                .map_err(Error::Schematools),
+            Command::UpgradeOpenapi(opts) => {
+                let mut upgrader = upgrade_openapi::Upgrader::options();
+                upgrader.with_dialect(opts.dialect.clone());
+
+                let externals = upgrader.process_external(schema, storage)?;
+                upgrader.process(schema)?;
+
+                save_externals(externals, opts.write)
+            }
+            Command::DowngradeOpenapi(_) => downgrade_openapi::Downgrader::options()
+                .process(schema)
+                .map(|_| ())
+                .map_err(Error::Schematools),
+            Command::ConvertSwagger(_) => convert_swagger::Converter::options()
+                .process(schema)
+                .map_err(Error::Schematools),
+            Command::UpgradeJsonschema(opts) => {
+                let upgrader = upgrade_jsonschema::Upgrader::options();
+
+                let externals = upgrader.process_external(schema, storage)?;
+                upgrader.process(schema, storage)?;
+
+                save_externals(externals, opts.write)
+            }
+            Command::Filter(opts) => filter::Selector::options()
+                .with_tags(opts.tag.clone())
+                .with_paths(opts.path.clone())
+                .with_methods(opts.method.clone())
+                .with_operation_ids(opts.operation_id.clone())
+                .with_filter(tools::Filter::new(&opts.filter)?)
+                .process(schema)
+                .map_err(Error::Schematools),
+            Command::RemoveUnused(opts) => opts
+                .options()
+                .process(schema, storage)
+                .map(|_| ())
+                .map_err(Error::Schematools),
+            Command::RenameSchema(opts) => {
+                let externals = rename_schema::Renamer::options()
+                    .with_renames(opts.rename.clone())
+                    .process(schema, storage)?;
+
+                save_externals(externals, opts.write)
+            }
+            Command::ExtractInline(opts) => extract_inline::Extractor::options()
+                .with_naming_strategy(opts.naming_strategy.clone())
+                .process(schema)
+                .map(|_| ())
+                .map_err(Error::Schematools),
+            Command::Dedupe(opts) => opts
+                .options()
+                .process(schema)
+                .map(|_| ())
+                .map_err(Error::Schematools),
+            Command::Publish(opts) => publish::Publisher::options()
+                .with_audience(opts.audience.clone())
+                .with_internal_key(opts.internal_key.clone())
+                .with_audience_key(opts.audience_key.clone())
+                .process(schema)
+                .map_err(Error::Schematools),
+            Command::Operations(opts) => opts
+                .options()?
+                .process(schema)
+                .map(|_| ())
+                .map_err(Error::Schematools),
+            Command::NormalizeParameters(opts) => normalize_parameters::Normalizer::options()
+                .with_min_occurrences(opts.min_occurrences)
+                .with_hoist(!opts.skip_hoist)
+                .process(schema)
+                .map(|_| ())
+                .map_err(Error::Schematools),
             #[cfg(feature = "semver")]
             Command::BumpOpenapi(opts) => {
-                let original = Schema::load_url(path_to_url(opts.original.clone())?)?;
+                let original =
+                    Schema::load_url_with_client(path_to_url(opts.original.clone())?, client)?;
 
                 ::schematools::process::bump_openapi::Bumper::options(original)
                     .with_kind(opts.kind.into())
-                    .process(schema)
+                    .process(schema, storage, client)
                     .map_err(Error::Schematools)
             }
             Command::Dereference(opts) => {
@@ -306,6 +1033,16 @@ impl Opts {
                     .with_resource_method_version(opts.resource_method_version)
                     .with_overwrite(opts.overwrite)
                     .with_overwrite_ambiguous(opts.overwrite_ambiguous)
+                    .with_naming_strategy(opts.naming_strategy.clone())
+                    .with_operation_id_template(opts.operation_id_template.clone())
+                    .with_dictionary(
+                        opts.dictionary
+                            .as_deref()
+                            .map(Dictionary::load)
+                            .transpose()?
+                            .unwrap_or_default(),
+                    )
+                    .with_verbs(opts.verbs.iter().cloned().collect())
                     .process(schema)
                     .map_err(Error::Schematools)
             }
@@ -314,10 +1051,32 @@ impl Opts {
                 let action = opts.action.clone().into();
                 ::schematools::process::patch::execute(schema, &action).map_err(Error::Schematools)
             }
+            Command::Overlay(opts) => {
+                let action = opts.action.clone().into();
+                ::schematools::process::overlay::execute(schema, &action)
+                    .map(|_| ())
+                    .map_err(Error::Schematools)
+            }
         }
     }
 }
 
+fn save_externals(externals: Vec<Schema>, write: bool) -> Result<(), Error> {
+    for external in externals {
+        if write {
+            log::info!("{}: saving", external.get_url());
+            external.save()?;
+        } else {
+            log::warn!(
+                "{}: external file modified, use --write to save it",
+                external.get_url()
+            );
+        }
+    }
+
+    Ok(())
+}
+
 pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
     let mut schema = opts.get_schema(client)?;
     let storage = &SchemaStorage::new(&schema, client);
@@ -326,43 +1085,184 @@ pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
     match &opts.command {
         Command::MergeAllOf(o) => {
             o.verbose.start()?;
-            opts.run(&mut schema, storage)?; // todo: ...
+            opts.run(&mut schema, storage, client)?; // todo: ...
             o.output.show(schema.get_body());
 
             Ok(())
         }
         Command::FlattenAllOf(o) => {
             o.verbose.start()?;
-            opts.run(&mut schema, storage)?; // todo: ...
+            opts.run(&mut schema, storage, client)?; // todo: ...
             o.output.show(schema.get_body());
 
             Ok(())
         }
         Command::MergeOpenapi(o) => {
             o.verbose.start()?;
-            opts.run(&mut schema, storage)?;
+            opts.run(&mut schema, storage, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::PrefixPaths(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Diff(o) => {
+            o.verbose.start()?;
+            let report = o.options(client)?.compare(&schema, storage, client)?;
+
+            if o.human {
+                o.output.write(report.to_string().trim_end());
+            } else {
+                o.output.show(
+                    &serde_json::to_value(&report)
+                        .map_err(|e| Error::Schematools(SchematoolsError::SerdeJsonError(e)))?,
+                );
+            }
+
+            o.check(&report)
+        }
+        Command::UpgradeOpenapi(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::DowngradeOpenapi(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::ConvertSwagger(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
             o.output.show(schema.get_body());
 
             Ok(())
         }
+        Command::UpgradeJsonschema(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Filter(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::RemoveUnused(o) => {
+            o.verbose.start()?;
+            let removed = o.options().process(&mut schema, storage)?;
+
+            if o.report {
+                o.output.show(
+                    &serde_json::to_value(&removed)
+                        .map_err(|e| Error::Schematools(SchematoolsError::SerdeJsonError(e)))?,
+                );
+            } else {
+                o.output.show(schema.get_body());
+            }
+
+            Ok(())
+        }
+        Command::RenameSchema(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
+
+            if o.write {
+                log::info!("{}: saving", schema.get_url());
+                schema.save()?;
+            } else {
+                o.output.show(schema.get_body());
+            }
+
+            Ok(())
+        }
+        Command::ExtractInline(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Dedupe(o) => {
+            o.verbose.start()?;
+            let groups = o.options().process(&mut schema)?;
+
+            if o.report {
+                o.output.show(
+                    &serde_json::to_value(&groups)
+                        .map_err(|e| Error::Schematools(SchematoolsError::SerdeJsonError(e)))?,
+                );
+            } else {
+                o.output.show(schema.get_body());
+            }
+
+            Ok(())
+        }
+        Command::Publish(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Operations(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::NormalizeParameters(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Changelog(o) => {
+            o.verbose.start()?;
+            let changelog = o.options(client)?.render(&schema, storage, client)?;
+
+            crate::commands::Output {
+                output: "markdown".to_string(),
+                to_file: o.to_file.clone(),
+                ..Default::default()
+            }
+            .write(&changelog);
+
+            Ok(())
+        }
         #[cfg(feature = "semver")]
         Command::BumpOpenapi(o) => {
             o.verbose.start()?;
-            opts.run(&mut schema, storage)?;
+            opts.run(&mut schema, storage, client)?;
             o.output.show(schema.get_body());
 
             Ok(())
         }
         Command::Dereference(o) => {
             o.verbose.start()?;
-            opts.run(&mut schema, storage)?;
+            opts.run(&mut schema, storage, client)?;
             o.output.show(schema.get_body());
 
             Ok(())
         }
         Command::Name(o) => {
             o.verbose.start()?;
-            opts.run(&mut schema, storage)?;
+            opts.run(&mut schema, storage, client)?;
             o.output.show(schema.get_body());
 
             Ok(())
@@ -370,7 +1270,14 @@ pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
         #[cfg(feature = "json-patch")]
         Command::Patch(o) => {
             o.verbose.start()?;
-            opts.run(&mut schema, storage)?;
+            opts.run(&mut schema, storage, client)?;
+            o.output.show(schema.get_body());
+
+            Ok(())
+        }
+        Command::Overlay(o) => {
+            o.verbose.start()?;
+            opts.run(&mut schema, storage, client)?;
             o.output.show(schema.get_body());
 
             Ok(())
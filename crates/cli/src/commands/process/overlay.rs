@@ -0,0 +1,29 @@
+use clap::Parser;
+
+#[derive(Clone, Debug, Parser)]
+pub enum Action {
+    /// Apply overlay file
+    Apply(OverlayApplyOpts),
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct OverlayApplyOpts {
+    /// Path to overlay file
+    overlay: String,
+}
+
+impl From<Action> for schematools::process::overlay::Action {
+    fn from(value: Action) -> Self {
+        match value {
+            Action::Apply(a) => Self::Apply(a.into()),
+        }
+    }
+}
+
+impl From<OverlayApplyOpts> for schematools::process::overlay::OverlayApplyOpts {
+    fn from(value: OverlayApplyOpts) -> Self {
+        Self {
+            overlay: value.overlay,
+        }
+    }
+}
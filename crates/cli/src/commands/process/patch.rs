@@ -19,6 +19,15 @@ pub enum Action {
 
     /// Apply inline patch
     Inline(PatchInlineOpts),
+
+    /// Create json merge patch file (RFC 7396)
+    MergeCreate(PatchCreateOpts),
+
+    /// Apply json merge patch file (RFC 7396)
+    MergeApply(PatchApplyOpts),
+
+    /// Rebase json patch file onto new version of original schema
+    Rebase(PatchRebaseOpts),
 }
 
 #[derive(Clone, Debug, Parser)]
@@ -33,6 +42,25 @@ pub struct PatchApplyOpts {
     patch: String,
 }
 
+#[derive(Clone, Debug, Parser)]
+pub struct PatchRebaseOpts {
+    /// Path to schema file patch was created against
+    #[clap(long)]
+    old_base: String,
+
+    /// Path to new version of schema file, processed schema is used when missing
+    #[clap(long)]
+    new_base: Option<String>,
+
+    /// Path to json patch file
+    #[clap(long)]
+    patch: String,
+
+    /// Output status of every operation instead of rebased patch
+    #[clap(long)]
+    report: bool,
+}
+
 #[derive(Clone, Debug, Parser, Serialize)]
 pub struct PatchInlineOpts {
     /// Operation add/remove/replace
@@ -52,6 +80,9 @@ impl From<Action> for schematools::process::patch::Action {
             Action::Create(c) => Self::Create(c.into()),
             Action::Apply(a) => Self::Apply(a.into()),
             Action::Inline(i) => Self::Inline(i.into()),
+            Action::MergeCreate(c) => Self::MergeCreate(c.into()),
+            Action::MergeApply(a) => Self::MergeApply(a.into()),
+            Action::Rebase(r) => Self::Rebase(r.into()),
         }
     }
 }
@@ -70,6 +101,17 @@ impl From<PatchApplyOpts> for schematools::process::patch::PatchApplyOpts {
     }
 }
 
+impl From<PatchRebaseOpts> for schematools::process::patch::PatchRebaseOpts {
+    fn from(value: PatchRebaseOpts) -> Self {
+        Self {
+            old_base: value.old_base,
+            new_base: value.new_base,
+            patch: value.patch,
+            report: value.report,
+        }
+    }
+}
+
 impl From<PatchInlineOpts> for schematools::process::patch::PatchInlineOpts {
     fn from(value: PatchInlineOpts) -> Self {
         Self {
@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use schematools::storage::SchemaStorage;
 use schematools::Client;
 
 use crate::error::Error;
@@ -20,6 +21,7 @@ impl Display for Opts {
         match &self.command {
             Command::Openapi(_) => write!(f, "openapi"),
             Command::JsonSchema(_) => write!(f, "jsonschema"),
+            Command::Compatibility(_) => write!(f, "compatibility"),
         }
     }
 }
@@ -31,6 +33,9 @@ enum Command {
 
     /// Performs json-schema specification validation
     JsonSchema(JsonSchemaOpts),
+
+    /// Checks compatibility of json-schema with its previous versions
+    Compatibility(CompatibilityOpts),
 }
 
 #[derive(Clone, Debug, Parser)]
@@ -59,6 +64,50 @@ struct JsonSchemaOpts {
     verbose: crate::commands::Verbosity,
 }
 
+#[derive(Clone, Debug, Parser)]
+struct CompatibilityOpts {
+    /// Path to json/yaml file representing new version of json-schema
+    file: String,
+
+    /// Path to previous version of json-schema, may be repeated from the oldest to the most recent
+    #[clap(long, required = true)]
+    previous: Vec<String>,
+
+    /// Compatibility mode
+    #[clap(long, value_enum, default_value = "backward")]
+    mode: CompatibilityMode,
+
+    /// Should continue on error
+    #[clap(long)]
+    pub continue_on_error: bool,
+
+    #[clap(flatten)]
+    verbose: crate::commands::Verbosity,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum CompatibilityMode {
+    /// Documents valid under previous version are valid under new one
+    Backward,
+    /// Documents valid under new version are valid under previous one
+    Forward,
+    /// Backward and forward compatibility with the most recent previous version
+    Full,
+    /// Backward and forward compatibility with all previous versions
+    Transitive,
+}
+
+impl From<CompatibilityMode> for validate::compatibility::CompatibilityMode {
+    fn from(value: CompatibilityMode) -> Self {
+        match value {
+            CompatibilityMode::Backward => Self::Backward,
+            CompatibilityMode::Forward => Self::Forward,
+            CompatibilityMode::Full => Self::Full,
+            CompatibilityMode::Transitive => Self::Transitive,
+        }
+    }
+}
+
 impl GetSchemaCommand for Opts {
     fn get_schema(&self, client: &Client) -> Result<Schema, Error> {
         match &self.command {
@@ -72,17 +121,47 @@ impl GetSchemaCommand for Opts {
                 client,
             )
             .map_err(Error::Schematools),
+            Command::Compatibility(opts) => Schema::load_url_with_client(
+                path_to_url(opts.file.clone()).map_err(Error::Schematools)?,
+                client,
+            )
+            .map_err(Error::Schematools),
         }
     }
 }
 
 impl Opts {
-    pub fn run(&self, schema: &Schema) -> Result<(), Error> {
+    pub fn run(
+        &self,
+        schema: &Schema,
+        storage: &SchemaStorage,
+        client: &Client,
+    ) -> Result<(), Error> {
         match &self.command {
             Command::Openapi(_) => validate::validate_openapi(schema).map_err(Error::Schematools),
             Command::JsonSchema(_) => {
                 validate::validate_jsonschema(schema).map_err(Error::Schematools)
             }
+            Command::Compatibility(opts) => opts
+                .previous
+                .iter()
+                .map(|p| {
+                    Ok(Schema::load_url_with_client(
+                        path_to_url(p.clone())?,
+                        client,
+                    )?)
+                })
+                .collect::<Result<Vec<_>, Error>>()
+                .and_then(|previous| {
+                    validate::compatibility::validate_compatibility(
+                        schema,
+                        storage,
+                        &previous,
+                        opts.mode.into(),
+                        client,
+                    )
+                    .map_err(Error::Schematools)
+                }),
         }
         .inspect(|_| log::info!("\x1b[0;32mSuccessful validation!\x1b[0m"))
         .or_else(|e| {
@@ -100,21 +179,27 @@ impl Opts {
         match &self.command {
             Command::Openapi(o) => o.continue_on_error,
             Command::JsonSchema(o) => o.continue_on_error,
+            Command::Compatibility(o) => o.continue_on_error,
         }
     }
 }
 
 pub fn execute(opts: Opts, client: &Client) -> Result<(), Error> {
     let schema = opts.get_schema(client)?;
+    let storage = &SchemaStorage::new(&schema, client);
 
     match &opts.command {
         Command::Openapi(o) => {
             o.verbose.start()?;
-            opts.run(&schema)
+            opts.run(&schema, storage, client)
         }
         Command::JsonSchema(o) => {
             o.verbose.start()?;
-            opts.run(&schema)
+            opts.run(&schema, storage, client)
+        }
+        Command::Compatibility(o) => {
+            o.verbose.start()?;
+            opts.run(&schema, storage, client)
         }
     }
 }
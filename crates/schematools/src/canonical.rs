@@ -0,0 +1,579 @@
+use std::cmp::Ordering;
+
+use serde_json::{Map, Value};
+
+const ROOT: [&str; 10] = [
+    "openapi",
+    "info",
+    "jsonSchemaDialect",
+    "servers",
+    "security",
+    "tags",
+    "externalDocs",
+    "paths",
+    "webhooks",
+    "components",
+];
+const INFO: [&str; 7] = [
+    "title",
+    "summary",
+    "description",
+    "termsOfService",
+    "contact",
+    "license",
+    "version",
+];
+const PATH_ITEM: [&str; 13] = [
+    "$ref",
+    "summary",
+    "description",
+    "servers",
+    "parameters",
+    "get",
+    "put",
+    "post",
+    "delete",
+    "options",
+    "head",
+    "patch",
+    "trace",
+];
+const OPERATION: [&str; 12] = [
+    "tags",
+    "summary",
+    "description",
+    "externalDocs",
+    "operationId",
+    "parameters",
+    "requestBody",
+    "responses",
+    "callbacks",
+    "deprecated",
+    "security",
+    "servers",
+];
+const PARAMETER: [&str; 14] = [
+    "$ref",
+    "name",
+    "in",
+    "description",
+    "required",
+    "deprecated",
+    "allowEmptyValue",
+    "style",
+    "explode",
+    "allowReserved",
+    "schema",
+    "example",
+    "examples",
+    "content",
+];
+const REQUEST_BODY: [&str; 4] = ["$ref", "description", "required", "content"];
+const RESPONSE: [&str; 5] = ["$ref", "description", "headers", "content", "links"];
+const MEDIA_TYPE: [&str; 4] = ["schema", "example", "examples", "encoding"];
+const COMPONENTS: [&str; 10] = [
+    "schemas",
+    "responses",
+    "parameters",
+    "examples",
+    "requestBodies",
+    "headers",
+    "securitySchemes",
+    "links",
+    "callbacks",
+    "pathItems",
+];
+const SCHEMA: [&str; 20] = [
+    "$schema",
+    "$id",
+    "$ref",
+    "title",
+    "description",
+    "type",
+    "format",
+    "nullable",
+    "enum",
+    "const",
+    "default",
+    "allOf",
+    "oneOf",
+    "anyOf",
+    "not",
+    "discriminator",
+    "required",
+    "properties",
+    "additionalProperties",
+    "items",
+];
+
+/// Order of names in paths, components, properties and other maps keyed by user defined names
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Alphabetical,
+    CaseInsensitive,
+    Preserve,
+}
+
+impl SortOrder {
+    fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            Self::Alphabetical => a.cmp(b),
+            Self::CaseInsensitive => a.to_lowercase().cmp(&b.to_lowercase()).then(a.cmp(b)),
+            Self::Preserve => Ordering::Equal,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Node {
+    Root,
+    Info,
+    Paths,
+    PathItem,
+    Operation,
+    Parameter,
+    RequestBody,
+    Responses,
+    Response,
+    Content,
+    MediaType,
+    Components,
+    Schema,
+    Other,
+}
+
+/// Reorders keys of openapi (or json schema) document to conventional order
+pub fn canonicalize(value: &Value, sort: SortOrder) -> Value {
+    let node = match value {
+        Value::Object(map) if map.contains_key("openapi") || map.contains_key("swagger") => {
+            Node::Root
+        }
+        _ => Node::Schema,
+    };
+
+    Canonical { sort }.node(value, node)
+}
+
+struct Canonical {
+    sort: SortOrder,
+}
+
+impl Canonical {
+    fn node(&self, value: &Value, node: Node) -> Value {
+        let Value::Object(map) = value else {
+            return value.clone();
+        };
+
+        match node {
+            Node::Root => self.ordered(map, &ROOT, |key| match key {
+                "info" => Node::Info,
+                "paths" | "webhooks" => Node::Paths,
+                "components" => Node::Components,
+                _ => Node::Other,
+            }),
+            Node::Info => self.ordered(map, &INFO, |_| Node::Other),
+            Node::Paths => self.sorted(map, Node::PathItem),
+            Node::PathItem => self.ordered(map, &PATH_ITEM, |key| match key {
+                "parameters" => Node::Parameter,
+                "summary" | "description" | "servers" | "$ref" => Node::Other,
+                _ if key.starts_with("x-") => Node::Other,
+                _ => Node::Operation,
+            }),
+            Node::Operation => self.ordered(map, &OPERATION, |key| match key {
+                "parameters" => Node::Parameter,
+                "requestBody" => Node::RequestBody,
+                "responses" => Node::Responses,
+                _ => Node::Other,
+            }),
+            Node::Parameter => self.ordered(map, &PARAMETER, |key| match key {
+                "schema" => Node::Schema,
+                "content" => Node::Content,
+                _ => Node::Other,
+            }),
+            Node::RequestBody => self.ordered(map, &REQUEST_BODY, |key| match key {
+                "content" => Node::Content,
+                _ => Node::Other,
+            }),
+            Node::Responses => self.sorted(map, Node::Response),
+            Node::Response => {
+                let mut result = self.ordered(map, &RESPONSE, |key| match key {
+                    "content" => Node::Content,
+                    _ => Node::Other,
+                });
+                if let Some(Value::Object(headers)) = map.get("headers") {
+                    result["headers"] = self.sorted(headers, Node::Parameter);
+                }
+                result
+            }
+            Node::Content => self.sorted(map, Node::MediaType),
+            Node::MediaType => self.ordered(map, &MEDIA_TYPE, |key| match key {
+                "schema" => Node::Schema,
+                _ => Node::Other,
+            }),
+            Node::Components => {
+                let mut result = self.ordered(map, &COMPONENTS, |_| Node::Other);
+
+                for (kind, node) in [
+                    ("schemas", Node::Schema),
+                    ("responses", Node::Response),
+                    ("parameters", Node::Parameter),
+                    ("headers", Node::Parameter),
+                    ("requestBodies", Node::RequestBody),
+                    ("pathItems", Node::PathItem),
+                    ("examples", Node::Other),
+                    ("securitySchemes", Node::Other),
+                    ("links", Node::Other),
+                    ("callbacks", Node::Other),
+                ] {
+                    if let Some(Value::Object(entries)) = map.get(kind) {
+                        result[kind] = self.sorted(entries, node);
+                    }
+                }
+
+                result
+            }
+            Node::Schema => {
+                let mut result = self.ordered(map, &SCHEMA, |key| match key {
+                    "not"
+                    | "items"
+                    | "additionalProperties"
+                    | "additionalItems"
+                    | "contains"
+                    | "propertyNames"
+                    | "if"
+                    | "then"
+                    | "else" => Node::Schema,
+                    "allOf" | "oneOf" | "anyOf" | "prefixItems" => Node::Schema,
+                    _ => Node::Other,
+                });
+
+                for key in ["properties", "patternProperties", "definitions", "$defs"] {
+                    if let Some(Value::Object(entries)) = map.get(key) {
+                        result[key] = self.sorted(entries, Node::Schema);
+                    }
+                }
+
+                result
+            }
+            Node::Other => value.clone(),
+        }
+    }
+
+    /// Array values are processed item by item with the same node kind
+    fn child(&self, value: &Value, node: Node) -> Value {
+        match value {
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|item| self.node(item, node)).collect())
+            }
+            _ => self.node(value, node),
+        }
+    }
+
+    /// Known keys go first in given order, the rest is sorted
+    fn ordered<F>(&self, map: &Map<String, Value>, keys: &[&str], node: F) -> Value
+    where
+        F: Fn(&str) -> Node,
+    {
+        let mut rest = map
+            .keys()
+            .filter(|k| !keys.contains(&k.as_str()))
+            .collect::<Vec<_>>();
+        rest.sort_by(|a, b| self.sort.compare(a, b));
+
+        Value::Object(
+            keys.iter()
+                .filter_map(|k| map.get_key_value(*k))
+                .chain(rest.into_iter().filter_map(|k| map.get_key_value(k)))
+                .map(|(k, v)| (k.clone(), self.child(v, node(k))))
+                .collect(),
+        )
+    }
+
+    /// Map keyed by user defined names
+    fn sorted(&self, map: &Map<String, Value>, node: Node) -> Value {
+        let mut keys = map.keys().collect::<Vec<_>>();
+        keys.sort_by(|a, b| self.sort.compare(a, b));
+
+        Value::Object(
+            keys.into_iter()
+                .map(|k| (k.clone(), self.node(&map[k], node)))
+                .collect(),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Quote {
+    /// Quotes only strings which would be read back as other types
+    #[default]
+    Auto,
+    Single,
+    Double,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct YamlStyle {
+    pub indent: usize,
+    /// Sequences of scalars are written inline, ex. `required: [id, name]`
+    pub flow_sequences: bool,
+    pub quote: Quote,
+}
+
+impl Default for YamlStyle {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            flow_sequences: false,
+            quote: Quote::Auto,
+        }
+    }
+}
+
+/// Serializes value to yaml with given style
+pub fn to_yaml(value: &Value, style: &YamlStyle) -> String {
+    let mut result = String::new();
+
+    match value {
+        Value::Object(map) if !map.is_empty() => write_map(map, 0, style, &mut result),
+        Value::Array(items) if !items.is_empty() && !is_flow(items, style) => {
+            write_sequence(items, 0, style, &mut result)
+        }
+        _ => {
+            result.push_str(&inline(value, style));
+            result.push('\n');
+        }
+    }
+
+    result
+}
+
+fn is_flow(items: &[Value], style: &YamlStyle) -> bool {
+    style.flow_sequences && items.iter().all(|i| !i.is_object() && !i.is_array())
+}
+
+/// Values written in the same line as their key
+fn inline(value: &Value, style: &YamlStyle) -> String {
+    match value {
+        Value::String(s) => string(s, style.quote),
+        Value::Object(map) if map.is_empty() => "{}".to_string(),
+        Value::Array(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(|i| inline(i, style))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Null => "null".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+fn is_inline(value: &Value, style: &YamlStyle) -> bool {
+    match value {
+        Value::Object(map) => map.is_empty(),
+        Value::Array(items) => items.is_empty() || is_flow(items, style),
+        _ => true,
+    }
+}
+
+fn string(value: &str, quote: Quote) -> String {
+    let multiline = value.contains(['\n', '\r', '\t']);
+
+    match quote {
+        Quote::Double => Value::String(value.to_string()).to_string(),
+        Quote::Single if !multiline => format!("'{}'", value.replace('\'', "''")),
+        Quote::Auto if !multiline => serde_yaml::to_string(value)
+            .map(|s| s.trim_end_matches('\n').to_string())
+            .unwrap_or_else(|_| Value::String(value.to_string()).to_string()),
+        // escaped characters are supported only by double quoted style
+        _ => Value::String(value.to_string()).to_string(),
+    }
+}
+
+fn write_map(map: &Map<String, Value>, indent: usize, style: &YamlStyle, result: &mut String) {
+    for (key, value) in map {
+        result.push_str(&" ".repeat(indent));
+        result.push_str(&string(key, style.quote));
+        result.push(':');
+
+        if is_inline(value, style) {
+            result.push(' ');
+            result.push_str(&inline(value, style));
+            result.push('\n');
+        } else {
+            result.push('\n');
+
+            match value {
+                Value::Object(child) => write_map(child, indent + style.indent, style, result),
+                // block sequences are not indented, same as serde_yaml does
+                Value::Array(items) => write_sequence(items, indent, style, result),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn write_sequence(items: &[Value], indent: usize, style: &YamlStyle, result: &mut String) {
+    for item in items {
+        if is_inline(item, style) {
+            result.push_str(&" ".repeat(indent));
+            result.push_str("- ");
+            result.push_str(&inline(item, style));
+            result.push('\n');
+            continue;
+        }
+
+        // nested block is written with item indentation, then its first line gets the dash
+        let mut nested = String::new();
+        match item {
+            Value::Object(child) => write_map(child, indent + 2, style, &mut nested),
+            Value::Array(child) => write_sequence(child, indent + 2, style, &mut nested),
+            _ => {}
+        }
+
+        result.push_str(&" ".repeat(indent));
+        result.push_str("- ");
+        result.push_str(&nested[indent + 2..]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonicalize_openapi() {
+        let value = json!({
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "properties": { "name": { "type": "string" }, "id": { "type": "integer" } },
+                        "required": ["name", "id"],
+                        "type": "object"
+                    },
+                    "Error": { "type": "string" }
+                }
+            },
+            "paths": {
+                "/pets/{id}": {
+                    "get": {
+                        "responses": { "200": { "description": "Pet" } },
+                        "operationId": "getPet"
+                    }
+                },
+                "/pets": {
+                    "post": { "x-internal": true, "responses": { "201": { "description": "Created" } } },
+                    "get": { "responses": { "200": { "description": "Pets" } } }
+                }
+            },
+            "info": { "version": "1.0.0", "title": "Pets" },
+            "openapi": "3.0.3"
+        });
+
+        let expected = json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Pets", "version": "1.0.0" },
+            "paths": {
+                "/pets": {
+                    "get": { "responses": { "200": { "description": "Pets" } } },
+                    "post": { "responses": { "201": { "description": "Created" } }, "x-internal": true }
+                },
+                "/pets/{id}": {
+                    "get": {
+                        "operationId": "getPet",
+                        "responses": { "200": { "description": "Pet" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Error": { "type": "string" },
+                    "Pet": {
+                        "type": "object",
+                        "required": ["name", "id"],
+                        "properties": { "id": { "type": "integer" }, "name": { "type": "string" } }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(
+            canonicalize(&value, SortOrder::Alphabetical).to_string(),
+            expected.to_string()
+        );
+    }
+
+    #[test]
+    fn test_sort_order() {
+        let value = json!({"properties": {"b": {}, "A": {}, "a": {}}});
+
+        let keys = |sort| {
+            canonicalize(&value, sort)["properties"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(keys(SortOrder::Alphabetical), vec!["A", "a", "b"]);
+        assert_eq!(keys(SortOrder::CaseInsensitive), vec!["A", "a", "b"]);
+        assert_eq!(keys(SortOrder::Preserve), vec!["b", "A", "a"]);
+    }
+
+    #[test]
+    fn test_yaml_default_matches_serde_yaml() {
+        let value = json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "tags": ["pets"],
+                        "parameters": [{ "name": "limit", "in": "query", "required": false }],
+                        "responses": { "200": { "description": "yes\nno" } }
+                    }
+                }
+            },
+            "empty": {},
+            "nested": [[1, 2], []]
+        });
+
+        assert_eq!(
+            to_yaml(&value, &YamlStyle::default()),
+            serde_yaml::to_string(&value).unwrap().replace(
+                "description: |-\n            yes\n            no",
+                "description: \"yes\\nno\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_yaml_style() {
+        let value = json!({
+            "required": ["id", "name"],
+            "properties": { "id": { "type": "integer", "enum": [1, 2] } },
+            "allOf": [{ "$ref": "#/definitions/Base" }]
+        });
+
+        let style = YamlStyle {
+            indent: 4,
+            flow_sequences: true,
+            quote: Quote::Single,
+        };
+
+        assert_eq!(
+            to_yaml(&value, &style),
+            r#"'required': ['id', 'name']
+'properties':
+    'id':
+        'type': 'integer'
+        'enum': [1, 2]
+'allOf':
+- '$ref': '#/definitions/Base'
+"#
+        );
+    }
+}
@@ -3,12 +3,13 @@ use regex::Regex;
 use std::collections::HashMap;
 use tera::to_value;
 
+use crate::process::name::word::Dictionary;
 use inflector::Inflector;
 use serde_json::Value;
 use tera::Tera;
 use tera::{try_get_value, Result as TeraResult};
 
-pub fn register(tera: &mut Tera) {
+pub fn register(tera: &mut Tera, dictionary: &Dictionary) {
     tera.register_filter("camelcase", camelcase);
     tera.register_filter("pascalcase", pascalcase);
     tera.register_filter("snakecase", snakecase);
@@ -26,7 +27,12 @@ pub fn register(tera: &mut Tera) {
     tera.register_filter("filter_startswith", filter_startswith);
     tera.register_filter("filter_inarray", filter_inarray);
     tera.register_filter("filter_not_inarray", filter_not_inarray);
-    tera.register_filter("plural", plural);
+
+    let dictionary = dictionary.clone();
+    tera.register_filter(
+        "plural",
+        move |value: &Value, _: &HashMap<String, Value>| plural(value, &dictionary),
+    );
 }
 
 pub fn pascalcase(value: &Value, _: &HashMap<String, Value>) -> TeraResult<Value> {
@@ -271,9 +277,11 @@ pub fn filter_not_inarray(value: &Value, args: &HashMap<String, Value>) -> TeraR
     }
 }
 
-pub fn plural(value: &Value, _: &HashMap<String, Value>) -> TeraResult<Value> {
+pub fn plural(value: &Value, dictionary: &Dictionary) -> TeraResult<Value> {
     let s = try_get_value!("plural", "value", String, value);
-    let plural = pluralize(&s, 2, false);
+    let plural = dictionary
+        .plural(&s)
+        .unwrap_or_else(|| pluralize(&s, 2, false));
 
     Ok(to_value(plural).unwrap())
 }
@@ -18,8 +18,8 @@ pub mod title;
 pub mod types;
 
 use crate::{
-    error::Error, resolver::SchemaResolver, schema::Schema, scope::SchemaScope, scope::Space,
-    storage::SchemaStorage, tools,
+    error::Error, resolver::SchemaResolver, schema::Schema, scope::SchemaNamingStrategy,
+    scope::SchemaScope, scope::Space, storage::SchemaStorage, tools,
 };
 
 #[derive(Clone)]
@@ -165,6 +165,7 @@ pub struct JsonSchemaExtractOptions {
     pub base_name: Option<String>,
     pub allow_list: bool,
     pub keep_schema: tools::Filter,
+    pub naming_strategy: SchemaNamingStrategy,
 }
 
 pub fn extract(
@@ -176,7 +177,7 @@ pub fn extract(
 
     if options.allow_list && schema.get_body().is_array() {
         let list = schema.get_body().as_array().unwrap();
-        let scope = &mut SchemaScope::default();
+        let scope = &mut SchemaScope::new(options.naming_strategy.clone());
 
         // todo: ... check resolve in multi
         for (i, body) in list.iter().enumerate() {
@@ -196,7 +197,7 @@ pub fn extract(
         add_types(
             schema.get_body(),
             &mut mcontainer,
-            &mut SchemaScope::default(),
+            &mut SchemaScope::new(options.naming_strategy.clone()),
             &SchemaResolver::new(schema, storage),
             &options,
         )?;
@@ -146,7 +146,7 @@ fn new_endpoint(
                 })
                 .collect::<std::collections::HashMap<String, Value>>();
 
-            scope.glue(&operation);
+            scope.operation(&operation, method, path);
             scope.add_spaces(&mut tags.clone().into_iter().map(Space::Tag).collect());
             scope.add_spaces(&mut vec![Space::Operation(operation.clone())]);
 
@@ -1,5 +1,6 @@
+use crate::scope::{SchemaNamingStrategy, SchemaScope};
 use crate::storage::SchemaStorage;
-use crate::{error::Error, resolver::SchemaResolver, schema::Schema, scope::SchemaScope, tools};
+use crate::{error::Error, resolver::SchemaResolver, schema::Schema, tools};
 use serde::ser::SerializeMap;
 use serde::Serialize;
 use serde_json::Map;
@@ -18,6 +19,7 @@ pub struct OpenapiExtractOptions {
     pub nested_arrays_as_models: bool,
     pub optional_and_nullable_as_models: bool,
     pub keep_schema: tools::Filter,
+    pub naming_strategy: SchemaNamingStrategy,
 }
 #[derive(Default)]
 pub struct EndpointContainer {
@@ -113,7 +115,7 @@ pub fn extract(
     storage: &SchemaStorage,
     options: OpenapiExtractOptions,
 ) -> Result<Openapi, Error> {
-    let mut scope = SchemaScope::default();
+    let mut scope = SchemaScope::new(options.naming_strategy.clone());
     let mut mcontainer = ModelContainer::default();
     let mut econtainer = EndpointContainer::new();
     let mut scontainer = security::SecuritySchemes::new();
@@ -124,6 +126,7 @@ pub fn extract(
     let options = &JsonSchemaExtractOptions {
         optional_and_nullable_as_models: options.optional_and_nullable_as_models,
         keep_schema: options.keep_schema,
+        naming_strategy: options.naming_strategy.clone(),
         ..Default::default()
     };
 
@@ -61,12 +61,12 @@ pub fn extract_requestbody(
                     .unwrap()
             });
 
-            scope.glue("request").glue("body");
+            scope.request();
 
             let model = super::get_content(data, scope, mcontainer, resolver, options)
                 .map_or(Ok(None), |v| v.map(Some));
 
-            scope.reduce(2);
+            scope.pop();
 
             Ok(Some(RequestBody {
                 models: model?,
@@ -163,7 +163,7 @@ pub fn extract_response(
                 })?
             };
 
-            scope.glue(&status_code.to_string());
+            scope.response(code);
 
             let model = super::get_content(data, scope, mcontainer, resolver, options)
                 .map_or(Ok(None), |v| v.map(Some));
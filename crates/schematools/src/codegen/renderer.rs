@@ -1,3 +1,4 @@
+use crate::process::name::word::Dictionary;
 use crate::{discovery::Discovered, error::Error};
 use tera::Tera;
 
@@ -12,6 +13,7 @@ pub fn create(
     discovered: Discovered,
     required: &[super::templates::TemplateType],
     container: super::CodegenContainer,
+    dictionary: &Dictionary,
 ) -> Result<Renderer, Error> {
     let mut tera = Tera::default();
 
@@ -19,7 +21,7 @@ pub fn create(
     tera.add_raw_templates(discovered.templates.clone())
         .map_err(Error::CodegenTemplatesParseError)?;
 
-    super::filters::register(&mut tera);
+    super::filters::register(&mut tera, dictionary);
 
     let templates = super::templates::get(discovered)?;
     if !templates.includes(required) {
@@ -143,6 +143,18 @@ pub enum Error {
     #[error("Not implemented")]
     NotImplemented,
 
+    #[error("Invalid naming strategy: {0}")]
+    NamingStrategyInvalid(String),
+
+    #[error("Invalid operationId template: {0}")]
+    OperationIdTemplateInvalid(String),
+
+    #[error("Cannot rename component: {0}")]
+    ComponentRenameInvalid(String),
+
+    #[error("Cannot edit operations: {0}")]
+    OperationEditInvalid(String),
+
     #[error("Cannot guess base name of schema")]
     NamingBaseNameNotFound,
 
@@ -152,12 +164,20 @@ pub enum Error {
     #[error("Cannot validate schema {0}")]
     SchemaValidation(String),
 
+    #[error(
+        "Schema {url} is not compatible with previous versions, found {count} incompatibilities"
+    )]
+    SchemaIncompatible { url: String, count: usize },
+
     #[error("Schema compilation error occurred {url}, reason: {reason}")]
     SchemaCompilation { url: String, reason: String },
 
     #[error("Cannot load schema: {url}, {path}")]
     SchemaLoad { url: String, path: String },
 
+    #[error("Cannot save schema: {url}, reason: {reason}")]
+    SchemaSave { url: String, reason: String },
+
     #[error("Cannot get remote schema: {url}, reason: {reason}")]
     SchemaHttpLoad { url: String, reason: String },
 
@@ -190,4 +210,20 @@ pub enum Error {
 
     #[error("De/serialization error: {0}")]
     SerdeJsonError(serde_json::Error),
+
+    #[error("Found {0} breaking changes")]
+    DiffBreakingChanges(usize),
+
+    #[error("Found {} unsatisfiable allOf combinations: {}", .0.len(), .0.join("; "))]
+    AllOfConflicts(Vec<String>),
+
+    #[error("Invalid jsonpath {path} at position {position}: {reason}")]
+    JsonPathInvalid {
+        path: String,
+        position: usize,
+        reason: String,
+    },
+
+    #[error("Overlay is invalid: {0}")]
+    OverlayInvalid(String),
 }
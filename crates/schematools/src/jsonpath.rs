@@ -0,0 +1,658 @@
+//! JSONPath (RFC 9535) evaluator, function extensions are not supported
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use crate::error::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Filter(Expr),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Exists(Query),
+    Compare(Operand, Comparison, Operand),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Operand {
+    Literal(Value),
+    Query(Query),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Query {
+    absolute: bool,
+    segments: Vec<Segment>,
+}
+
+/// Node selected by jsonpath with its location
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node<'a> {
+    pub path: Vec<String>,
+    pub value: &'a Value,
+}
+
+impl<'a> Node<'a> {
+    /// Json pointer of node, ex. /paths/~1users/get
+    pub fn pointer(&self) -> String {
+        pointer(&self.path)
+    }
+}
+
+/// Json pointer built from unescaped path segments
+pub fn pointer(path: &[String]) -> String {
+    path.iter()
+        .map(|p| format!("/{}", p.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self, Error> {
+        let mut parser = Parser {
+            path,
+            chars: path.chars().collect(),
+            position: 0,
+        };
+
+        parser.expect('$')?;
+        let segments = parser.segments()?;
+
+        if parser.position < parser.chars.len() {
+            return Err(parser.error("unexpected character"));
+        }
+
+        Ok(Self { segments })
+    }
+
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<Node<'a>> {
+        select(&self.segments, root, root)
+    }
+
+    /// Json pointers of selected nodes
+    pub fn pointers(&self, root: &Value) -> Vec<String> {
+        self.select(root).iter().map(Node::pointer).collect()
+    }
+}
+
+fn select<'a>(segments: &[Segment], current: &'a Value, root: &'a Value) -> Vec<Node<'a>> {
+    let mut nodes = vec![Node {
+        path: vec![],
+        value: current,
+    }];
+
+    for segment in segments {
+        nodes = match segment {
+            Segment::Child(selectors) => nodes
+                .iter()
+                .flat_map(|node| apply(selectors, node, root))
+                .collect(),
+            Segment::Descendant(selectors) => nodes
+                .iter()
+                .flat_map(descendants)
+                .flat_map(|node| apply(selectors, &node, root))
+                .collect(),
+        };
+    }
+
+    nodes
+}
+
+/// Node itself and all its descendants in document order
+fn descendants<'a>(node: &Node<'a>) -> Vec<Node<'a>> {
+    let mut result = vec![node.clone()];
+
+    for child in children(node) {
+        result.extend(descendants(&child));
+    }
+
+    result
+}
+
+fn children<'a>(node: &Node<'a>) -> Vec<Node<'a>> {
+    match node.value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| child(node, key.clone(), value))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, value)| child(node, i.to_string(), value))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn child<'a>(node: &Node<'a>, key: String, value: &'a Value) -> Node<'a> {
+    let mut path = node.path.clone();
+    path.push(key);
+
+    Node { path, value }
+}
+
+fn apply<'a>(selectors: &[Selector], node: &Node<'a>, root: &'a Value) -> Vec<Node<'a>> {
+    let mut result = vec![];
+
+    for selector in selectors {
+        match (selector, node.value) {
+            (Selector::Name(name), Value::Object(map)) => {
+                if let Some(value) = map.get(name) {
+                    result.push(child(node, name.clone(), value));
+                }
+            }
+            (Selector::Wildcard, _) => result.extend(children(node)),
+            (Selector::Index(index), Value::Array(items)) => {
+                let index = if *index < 0 {
+                    items.len() as i64 + index
+                } else {
+                    *index
+                };
+
+                if let Some(value) = usize::try_from(index).ok().and_then(|i| items.get(i)) {
+                    result.push(child(node, index.to_string(), value));
+                }
+            }
+            (Selector::Slice(start, end, step), Value::Array(items)) => {
+                for i in slice(items.len() as i64, *start, *end, step.unwrap_or(1)) {
+                    result.push(child(node, i.to_string(), &items[i]));
+                }
+            }
+            (Selector::Filter(expr), _) => result.extend(
+                children(node)
+                    .into_iter()
+                    .filter(|child| evaluate(expr, child.value, root)),
+            ),
+            _ => {}
+        }
+    }
+
+    result
+}
+
+fn slice(len: i64, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    let normalize = |i: i64| if i < 0 { len + i } else { i };
+
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, len);
+        let upper = normalize(end.unwrap_or(len)).clamp(0, len);
+
+        (lower..upper)
+            .step_by(step as usize)
+            .map(|i| i as usize)
+            .collect()
+    } else if step < 0 {
+        let upper = normalize(start.unwrap_or(len - 1)).clamp(-1, len - 1);
+        let lower = end.map(normalize).unwrap_or(-len - 1).clamp(-1, len - 1);
+
+        let mut result = vec![];
+        let mut i = upper;
+        while i > lower {
+            result.push(i as usize);
+            i += step;
+        }
+        result
+    } else {
+        vec![]
+    }
+}
+
+fn evaluate(expr: &Expr, current: &Value, root: &Value) -> bool {
+    match expr {
+        Expr::Or(a, b) => evaluate(a, current, root) || evaluate(b, current, root),
+        Expr::And(a, b) => evaluate(a, current, root) && evaluate(b, current, root),
+        Expr::Not(a) => !evaluate(a, current, root),
+        Expr::Exists(query) => !query_nodes(query, current, root).is_empty(),
+        Expr::Compare(a, comparison, b) => {
+            let a = operand(a, current, root);
+            let b = operand(b, current, root);
+
+            match comparison {
+                Comparison::Eq => equal(a, b),
+                Comparison::Ne => !equal(a, b),
+                Comparison::Lt => less(a, b),
+                Comparison::Le => less(a, b) || equal(a, b),
+                Comparison::Gt => less(b, a),
+                Comparison::Ge => less(b, a) || equal(a, b),
+            }
+        }
+    }
+}
+
+fn query_nodes<'a>(query: &Query, current: &'a Value, root: &'a Value) -> Vec<Node<'a>> {
+    select(
+        &query.segments,
+        if query.absolute { root } else { current },
+        root,
+    )
+}
+
+/// Value of comparable, queries selecting other than exactly one node are `Nothing`
+fn operand<'a>(operand: &'a Operand, current: &'a Value, root: &'a Value) -> Option<&'a Value> {
+    match operand {
+        Operand::Literal(value) => Some(value),
+        Operand::Query(query) => match &query_nodes(query, current, root)[..] {
+            [node] => Some(node.value),
+            _ => None,
+        },
+    }
+}
+
+fn equal(a: Option<&Value>, b: Option<&Value>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a.as_f64() == b.as_f64(),
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn less(a: Option<&Value>, b: Option<&Value>) -> bool {
+    match (a, b) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => {
+            a.as_f64().partial_cmp(&b.as_f64()) == Some(Ordering::Less)
+        }
+        (Some(Value::String(a)), Some(Value::String(b))) => a < b,
+        _ => false,
+    }
+}
+
+struct Parser<'a> {
+    path: &'a str,
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, reason: &str) -> Error {
+        Error::JsonPathInvalid {
+            path: self.path.to_string(),
+            position: self.position,
+            reason: reason.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn starts_with(&self, value: &str) -> bool {
+        value
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.position + i) == Some(&c))
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), Error> {
+        if self.peek() == Some(c) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {c}")))
+        }
+    }
+
+    fn whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn segments(&mut self) -> Result<Vec<Segment>, Error> {
+        let mut segments = vec![];
+
+        loop {
+            if self.starts_with("..") {
+                self.position += 2;
+
+                let selectors = match self.peek() {
+                    Some('[') => self.bracket()?,
+                    Some('*') => {
+                        self.position += 1;
+                        vec![Selector::Wildcard]
+                    }
+                    _ => vec![Selector::Name(self.name()?)],
+                };
+                segments.push(Segment::Descendant(selectors));
+            } else if self.peek() == Some('.') {
+                self.position += 1;
+
+                let selector = match self.peek() {
+                    Some('*') => {
+                        self.position += 1;
+                        Selector::Wildcard
+                    }
+                    _ => Selector::Name(self.name()?),
+                };
+                segments.push(Segment::Child(vec![selector]));
+            } else if self.peek() == Some('[') {
+                segments.push(Segment::Child(self.bracket()?));
+            } else {
+                return Ok(segments);
+            }
+        }
+    }
+
+    /// Member name shorthand, `-` is accepted for convenience of `x-` extensions
+    fn name(&mut self) -> Result<String, Error> {
+        let start = self.position;
+
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || c == '_' || c == '-' || c == '$' || !c.is_ascii() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+
+        if start == self.position {
+            return Err(self.error("expected member name"));
+        }
+
+        Ok(self.chars[start..self.position].iter().collect())
+    }
+
+    fn bracket(&mut self) -> Result<Vec<Selector>, Error> {
+        self.expect('[')?;
+        let mut selectors = vec![];
+
+        loop {
+            self.whitespace();
+            selectors.push(self.selector()?);
+            self.whitespace();
+
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(selectors);
+                }
+                _ => return Err(self.error("expected , or ]")),
+            }
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector, Error> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Selector::Name(self.string()?)),
+            Some('*') => {
+                self.position += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.position += 1;
+                self.whitespace();
+                Ok(Selector::Filter(self.or()?))
+            }
+            _ => {
+                let start = self.integer()?;
+                self.whitespace();
+
+                if self.peek() != Some(':') {
+                    return start
+                        .map(Selector::Index)
+                        .ok_or_else(|| self.error("expected selector"));
+                }
+
+                self.position += 1;
+                self.whitespace();
+                let end = self.integer()?;
+                self.whitespace();
+
+                let step = if self.peek() == Some(':') {
+                    self.position += 1;
+                    self.whitespace();
+                    self.integer()?
+                } else {
+                    None
+                };
+
+                Ok(Selector::Slice(start, end, step))
+            }
+        }
+    }
+
+    fn integer(&mut self) -> Result<Option<i64>, Error> {
+        let start = self.position;
+
+        if self.peek() == Some('-') {
+            self.position += 1;
+        }
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.position += 1;
+        }
+
+        if start == self.position {
+            return Ok(None);
+        }
+
+        self.chars[start..self.position]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .map(Some)
+            .map_err(|_| self.error("invalid integer"))
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        let quote = self.peek().ok_or_else(|| self.error("expected string"))?;
+        self.position += 1;
+
+        let mut result = String::new();
+
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(c) if c == quote => {
+                    self.position += 1;
+                    return Ok(result);
+                }
+                Some('\\') => {
+                    self.position += 1;
+                    let escaped = match self.peek() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some(c @ ('\\' | '/' | '\'' | '"')) => c,
+                        _ => return Err(self.error("invalid escape sequence")),
+                    };
+                    result.push(escaped);
+                    self.position += 1;
+                }
+                Some(c) => {
+                    result.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.and()?;
+
+        loop {
+            self.whitespace();
+            if !self.starts_with("||") {
+                return Ok(expr);
+            }
+            self.position += 2;
+            self.whitespace();
+
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+    }
+
+    fn and(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.unary()?;
+
+        loop {
+            self.whitespace();
+            if !self.starts_with("&&") {
+                return Ok(expr);
+            }
+            self.position += 2;
+            self.whitespace();
+
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, Error> {
+        match self.peek() {
+            Some('!') if !self.starts_with("!=") => {
+                self.position += 1;
+                self.whitespace();
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some('(') => {
+                self.position += 1;
+                self.whitespace();
+                let expr = self.or()?;
+                self.whitespace();
+                self.expect(')')?;
+                Ok(expr)
+            }
+            _ => self.comparison(),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, Error> {
+        let left = self.operand()?;
+        self.whitespace();
+
+        let comparison = [
+            ("==", Comparison::Eq),
+            ("!=", Comparison::Ne),
+            ("<=", Comparison::Le),
+            (">=", Comparison::Ge),
+            ("<", Comparison::Lt),
+            (">", Comparison::Gt),
+        ]
+        .into_iter()
+        .find(|(token, _)| self.starts_with(token));
+
+        match (comparison, left) {
+            (Some((token, comparison)), left) => {
+                self.position += token.len();
+                self.whitespace();
+                Ok(Expr::Compare(left, comparison, self.operand()?))
+            }
+            (None, Operand::Query(query)) => Ok(Expr::Exists(query)),
+            (None, Operand::Literal(_)) => Err(self.error("expected comparison")),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, Error> {
+        match self.peek() {
+            Some(c @ ('@' | '$')) => {
+                self.position += 1;
+                Ok(Operand::Query(Query {
+                    absolute: c == '$',
+                    segments: self.segments()?,
+                }))
+            }
+            Some('\'' | '"') => Ok(Operand::Literal(Value::String(self.string()?))),
+            Some(_) => {
+                let start = self.position;
+                while matches!(
+                    self.peek(),
+                    Some('a'..='z' | '0'..='9' | '-' | '+' | '.' | 'E')
+                ) {
+                    self.position += 1;
+                }
+
+                let literal = self.chars[start..self.position].iter().collect::<String>();
+
+                serde_json::from_str::<Value>(&literal)
+                    .ok()
+                    .filter(|v| !v.is_object() && !v.is_array())
+                    .map(Operand::Literal)
+                    .ok_or_else(|| self.error("expected literal or query"))
+            }
+            None => Err(self.error("unexpected end")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use test_case::test_case;
+
+    fn document() -> Value {
+        json!({
+            "paths": {
+                "/users": {
+                    "get": { "tags": ["users"], "operationId": "listUsers", "x-internal": true },
+                    "post": { "tags": ["users", "admin"], "operationId": "createUser" }
+                },
+                "/invoices": {
+                    "get": { "tags": ["billing"], "operationId": "listInvoices", "x-limit": 10 }
+                }
+            },
+            "servers": [{ "url": "a" }, { "url": "b" }, { "url": "c" }]
+        })
+    }
+
+    #[test_case("$", vec![""])]
+    #[test_case("$.paths['/users'].get", vec!["/paths/~1users/get"])]
+    #[test_case("$.paths.*.get.operationId", vec!["/paths/~1users/get/operationId", "/paths/~1invoices/get/operationId"])]
+    #[test_case("$..operationId", vec!["/paths/~1users/get/operationId", "/paths/~1users/post/operationId", "/paths/~1invoices/get/operationId"])]
+    #[test_case("$.servers[-1]", vec!["/servers/2"])]
+    #[test_case("$.servers[0,2]", vec!["/servers/0", "/servers/2"])]
+    #[test_case("$.servers[1:]", vec!["/servers/1", "/servers/2"])]
+    #[test_case("$.servers[::-2]", vec!["/servers/2", "/servers/0"])]
+    #[test_case("$.paths.*[?@['x-internal'] == true]", vec!["/paths/~1users/get"])]
+    #[test_case("$.paths.*[?(@.x-limit > 5 || @.operationId == 'createUser')]", vec!["/paths/~1users/post", "/paths/~1invoices/get"])]
+    #[test_case("$.paths.*.*.tags[?@ == 'admin']", vec!["/paths/~1users/post/tags/1"])]
+    #[test_case("$.paths.*[?!@.x-internal && @.tags[0] == 'users']", vec!["/paths/~1users/post"])]
+    #[test_case("$.servers[?@.url == $.servers[1].url]", vec!["/servers/1"])]
+    #[test_case("$.missing.*", vec![])]
+    fn test_select(path: &str, expected: Vec<&str>) {
+        let path = JsonPath::parse(path).unwrap();
+
+        assert_eq!(path.pointers(&document()), expected);
+    }
+
+    #[test_case("paths" ; "missing root")]
+    #[test_case("$.paths[" ; "unclosed bracket")]
+    #[test_case("$.paths[?@.a ==]" ; "missing operand")]
+    #[test_case("$.paths['a" ; "unterminated string")]
+    #[test_case("$.." ; "missing descendant selector")]
+    fn test_invalid(path: &str) {
+        assert!(JsonPath::parse(path).is_err());
+    }
+}
@@ -1,11 +1,13 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod canonical;
 #[cfg(feature = "codegen")]
 pub mod codegen;
 pub mod discovery;
 pub mod error;
 pub mod hash;
+pub mod jsonpath;
 pub mod process;
 pub mod resolver;
 pub mod schema;
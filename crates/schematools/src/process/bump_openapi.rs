@@ -1,6 +1,7 @@
 use serde_json::{Map, Value};
 
-use crate::{error::Error, schema::Schema};
+use super::diff;
+use crate::{error::Error, schema::Schema, storage::SchemaStorage, Client};
 
 pub struct Bumper;
 
@@ -12,6 +13,7 @@ pub struct BumperOptions {
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum BumpKind {
     Xversion,
+    Semantic,
     Undefined,
 }
 
@@ -21,6 +23,7 @@ impl std::str::FromStr for BumpKind {
     fn from_str(input: &str) -> Result<BumpKind, Self::Err> {
         match input {
             "x-version" => Ok(BumpKind::Xversion),
+            "semantic" => Ok(BumpKind::Semantic),
             _ => Err(()),
         }
     }
@@ -32,7 +35,19 @@ impl BumperOptions {
         self
     }
 
-    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+    pub fn process(
+        &self,
+        schema: &mut Schema,
+        storage: &SchemaStorage,
+        client: &Client,
+    ) -> Result<(), Error> {
+        let report = match self.kind {
+            BumpKind::Semantic => Some(
+                diff::Differ::options(self.original.clone()).compare(schema, storage, client)?,
+            ),
+            _ => None,
+        };
+
         let root = schema
             .get_body_mut()
             .as_object_mut()
@@ -81,27 +96,21 @@ impl BumperOptions {
                     }
                 }
 
-                let mut original_version = extract_version(original_info, "version")?;
-                if bump.0 {
-                    original_version.major += 1;
-                    original_version.minor = 0;
-                    original_version.patch = 0;
-                } else if bump.1 {
-                    original_version.minor += 1;
-                    original_version.patch = 0;
-                } else if bump.2 {
-                    original_version.patch += 1
-                }
-
-                log::info!("bumping version to: {}", original_version);
-
-                let info = extract_info_mut(root)?;
-                info.insert(
-                    "version".to_string(),
-                    Value::String(original_version.to_string()),
+                let original_version = extract_version(original_info, "version")?;
+                set_version(root, bump_version(original_version, bump))
+            }
+            BumpKind::Semantic => {
+                let report = report.ok_or(Error::NotImplemented)?;
+                let bump = semantic_bump(&report);
+
+                log::info!(
+                    "changes: {}, bump (major, minor, patch): {:?}",
+                    report.changes.len(),
+                    bump
                 );
 
-                Ok(())
+                let original_version = extract_version(extract_info(original)?, "version")?;
+                set_version(root, bump_version(original_version, bump))
             }
             _ => Err(Error::NotImplemented),
         }
@@ -117,6 +126,44 @@ impl Bumper {
     }
 }
 
+/// Breaking or unclassified changes bump major, other api changes minor and documentation patch
+fn semantic_bump(report: &diff::Report) -> (bool, bool, bool) {
+    let major = report
+        .changes
+        .iter()
+        .any(|c| c.severity != diff::Severity::NonBreaking);
+    let minor = report.changes.iter().any(|c| !c.kind.is_documentation());
+    let patch = !report.changes.is_empty();
+
+    (major, minor, patch)
+}
+
+#[cfg(feature = "semver")]
+fn bump_version(mut version: semver::Version, bump: (bool, bool, bool)) -> semver::Version {
+    if bump.0 {
+        version.major += 1;
+        version.minor = 0;
+        version.patch = 0;
+    } else if bump.1 {
+        version.minor += 1;
+        version.patch = 0;
+    } else if bump.2 {
+        version.patch += 1
+    }
+
+    version
+}
+
+#[cfg(feature = "semver")]
+fn set_version(openapi: &mut Map<String, Value>, version: semver::Version) -> Result<(), Error> {
+    log::info!("bumping version to: {}", version);
+
+    let info = extract_info_mut(openapi)?;
+    info.insert("version".to_string(), Value::String(version.to_string()));
+
+    Ok(())
+}
+
 fn extract_info(openapi: &Map<String, Value>) -> Result<&Map<String, Value>, Error> {
     openapi
         .get("info")
@@ -176,10 +223,12 @@ mod tests {
         });
 
         let mut schema = Schema::from_json(recent);
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
 
         let _result = Bumper::options(Schema::from_json(original))
             .with_kind(BumpKind::Xversion)
-            .process(&mut schema);
+            .process(&mut schema, &storage, &client);
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
@@ -211,14 +260,100 @@ mod tests {
         });
 
         let mut schema = Schema::from_json(recent);
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
 
         let _result = Bumper::options(Schema::from_json(original))
             .with_kind(BumpKind::Xversion)
-            .process(&mut schema);
+            .process(&mut schema, &storage, &client);
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
 
+    #[test]
+    fn test_semantic_bump_major() {
+        let recent = json!({
+            "info": { "version": "1.4.2" },
+            "paths": {
+                "/users": {}
+            }
+        });
+
+        let original = json!({
+            "info": { "version": "1.4.2" },
+            "paths": {
+                "/users": { "get": {} }
+            }
+        });
+
+        let mut schema = Schema::from_json(recent);
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        Bumper::options(Schema::from_json(original))
+            .with_kind(BumpKind::Semantic)
+            .process(&mut schema, &storage, &client)
+            .unwrap();
+
+        assert_eq!(schema.get_body()["info"]["version"], json!("2.0.0"));
+    }
+
+    #[test]
+    fn test_semantic_bump_minor() {
+        let recent = json!({
+            "info": { "version": "1.4.2" },
+            "paths": {
+                "/users": { "get": { "description": "List of users" }, "post": {} }
+            }
+        });
+
+        let original = json!({
+            "info": { "version": "1.4.2" },
+            "paths": {
+                "/users": { "get": {} }
+            }
+        });
+
+        let mut schema = Schema::from_json(recent);
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        Bumper::options(Schema::from_json(original))
+            .with_kind(BumpKind::Semantic)
+            .process(&mut schema, &storage, &client)
+            .unwrap();
+
+        assert_eq!(schema.get_body()["info"]["version"], json!("1.5.0"));
+    }
+
+    #[test]
+    fn test_semantic_bump_patch() {
+        let recent = json!({
+            "info": { "version": "1.4.2" },
+            "paths": {
+                "/users": { "get": { "description": "List of users" } }
+            }
+        });
+
+        let original = json!({
+            "info": { "version": "1.4.2" },
+            "paths": {
+                "/users": { "get": {} }
+            }
+        });
+
+        let mut schema = Schema::from_json(recent);
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        Bumper::options(Schema::from_json(original))
+            .with_kind(BumpKind::Semantic)
+            .process(&mut schema, &storage, &client)
+            .unwrap();
+
+        assert_eq!(schema.get_body()["info"]["version"], json!("1.4.3"));
+    }
+
     #[test]
     fn test_xversion_bump_minor() {
         let recent = json!({
@@ -246,10 +381,12 @@ mod tests {
         });
 
         let mut schema = Schema::from_json(recent);
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
 
         let _result = Bumper::options(Schema::from_json(original))
             .with_kind(BumpKind::Xversion)
-            .process(&mut schema);
+            .process(&mut schema, &storage, &client);
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
@@ -0,0 +1,381 @@
+use std::fmt::Write;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::diff::{self, Change, ChangeKind, Operation, Report};
+use crate::{error::Error, schema::Schema, storage::SchemaStorage, Client};
+
+pub struct Changelog;
+
+pub struct ChangelogOptions {
+    pub original: Schema,
+    pub title: String,
+    pub template: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct OperationChanges {
+    pub operation: Option<Operation>,
+    pub added: Vec<Change>,
+    pub changed: Vec<Change>,
+    pub deprecated: Vec<Change>,
+    pub removed: Vec<Change>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct TagChanges {
+    pub tag: String,
+    pub operations: Vec<OperationChanges>,
+}
+
+impl ChangelogOptions {
+    pub fn with_title(&mut self, value: String) -> &mut Self {
+        self.title = value;
+        self
+    }
+
+    /// Tera template used instead of built-in markdown
+    pub fn with_template(&mut self, value: Option<String>) -> &mut Self {
+        self.template = value;
+        self
+    }
+
+    pub fn process(
+        &self,
+        schema: &mut Schema,
+        storage: &SchemaStorage,
+        client: &Client,
+    ) -> Result<(), Error> {
+        let changelog = self.render(schema, storage, client)?;
+
+        let body = schema.get_body_mut();
+        *body = Value::String(changelog);
+
+        Ok(())
+    }
+
+    pub fn render(
+        &self,
+        schema: &Schema,
+        storage: &SchemaStorage,
+        client: &Client,
+    ) -> Result<String, Error> {
+        let report =
+            diff::Differ::options(self.original.clone()).compare(schema, storage, client)?;
+        let tags = group(&report);
+
+        match &self.template {
+            Some(template) => self.render_template(template, &report, &tags, schema),
+            None => Ok(markdown(&self.title, &tags)),
+        }
+    }
+
+    #[cfg(feature = "codegen")]
+    fn render_template(
+        &self,
+        template: &str,
+        report: &Report,
+        tags: &[TagChanges],
+        schema: &Schema,
+    ) -> Result<String, Error> {
+        let mut context = tera::Context::new();
+        context.insert("title", &self.title);
+        context.insert("tags", tags);
+        context.insert("changes", &report.changes);
+        context.insert("breaking", &report.is_breaking());
+        context.insert("original", &self.original.get_body().pointer("/info"));
+        context.insert("info", &schema.get_body().pointer("/info"));
+
+        tera::Tera::one_off(template, &context, false).map_err(Error::CodegenTemplateError)
+    }
+
+    #[cfg(not(feature = "codegen"))]
+    fn render_template(
+        &self,
+        _template: &str,
+        _report: &Report,
+        _tags: &[TagChanges],
+        _schema: &Schema,
+    ) -> Result<String, Error> {
+        Err(Error::NotImplemented)
+    }
+}
+
+impl Changelog {
+    pub fn options(original: Schema) -> ChangelogOptions {
+        ChangelogOptions {
+            original,
+            title: "Changelog".to_string(),
+            template: None,
+        }
+    }
+}
+
+/// Groups changes by first tag of operation and then by operation, documentation changes are skipped
+pub fn group(report: &Report) -> Vec<TagChanges> {
+    let mut tags: Vec<TagChanges> = vec![];
+
+    for change in report.changes.iter() {
+        if change.kind.is_documentation() {
+            continue;
+        }
+
+        let tag = change
+            .operation
+            .as_ref()
+            .and_then(|o| o.tags.first().cloned())
+            .unwrap_or_else(|| "default".to_string());
+
+        let index = match tags.iter().position(|t| t.tag == tag) {
+            Some(index) => index,
+            None => {
+                tags.push(TagChanges {
+                    tag,
+                    operations: vec![],
+                });
+                tags.len() - 1
+            }
+        };
+        let operations = &mut tags[index].operations;
+
+        let index = match operations
+            .iter()
+            .position(|o| same_operation(&o.operation, &change.operation))
+        {
+            Some(index) => index,
+            None => {
+                operations.push(OperationChanges {
+                    operation: change.operation.clone(),
+                    added: vec![],
+                    changed: vec![],
+                    deprecated: vec![],
+                    removed: vec![],
+                });
+                operations.len() - 1
+            }
+        };
+        let operation = &mut operations[index];
+
+        if change.kind.is_addition() {
+            operation.added.push(change.clone());
+        } else if change.kind.is_removal() {
+            operation.removed.push(change.clone());
+        } else if change.kind.is_deprecation() {
+            operation.deprecated.push(change.clone());
+        } else {
+            operation.changed.push(change.clone());
+        }
+    }
+
+    tags
+}
+
+fn same_operation(a: &Option<Operation>, b: &Option<Operation>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.method == b.method && a.path == b.path,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn markdown(title: &str, tags: &[TagChanges]) -> String {
+    let mut result = format!("# {title}\n");
+
+    if tags.is_empty() {
+        result.push_str("\nNo changes.\n");
+    }
+
+    for tag in tags {
+        let _ = write!(result, "\n## {}\n", tag.tag);
+
+        for operation in tag.operations.iter() {
+            match &operation.operation {
+                Some(o) => {
+                    let _ = write!(
+                        result,
+                        "\n### `{} {}`{}\n",
+                        o.method.to_uppercase(),
+                        o.path,
+                        o.operation_id
+                            .as_ref()
+                            .map(|id| format!(" {id}"))
+                            .unwrap_or_default()
+                    );
+                }
+                None => result.push_str("\n### General\n"),
+            }
+
+            for (name, changes) in [
+                ("Added", &operation.added),
+                ("Changed", &operation.changed),
+                ("Deprecated", &operation.deprecated),
+                ("Removed", &operation.removed),
+            ] {
+                if changes.is_empty() {
+                    continue;
+                }
+
+                let _ = write!(result, "\n#### {name}\n\n");
+
+                for change in changes {
+                    let _ = writeln!(result, "- {}", describe(change));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+fn describe(change: &Change) -> String {
+    let message = match change.kind {
+        ChangeKind::EndpointAdded => "Endpoint added".to_string(),
+        ChangeKind::EndpointRemoved => "Endpoint removed".to_string(),
+        ChangeKind::EndpointDeprecated => "Endpoint deprecated".to_string(),
+        _ => {
+            let mut message = change.message.clone();
+            if let Some(first) = message.get(0..1) {
+                message.replace_range(0..1, &first.to_uppercase());
+            }
+
+            match location(&change.pointer) {
+                Some(location) => format!("{message} (`{location}`)"),
+                None => message,
+            }
+        }
+    };
+
+    match change.severity {
+        diff::Severity::Breaking => format!("**Breaking:** {message}"),
+        _ => message,
+    }
+}
+
+/// Location of field relative to operation, ex. requestBody/content/application~1json/schema/properties/name
+fn location(pointer: &str) -> Option<String> {
+    let parts = pointer.split('/').skip(4).collect::<Vec<_>>();
+
+    if parts.len() < 2 {
+        None
+    } else {
+        Some(parts.join("/").replace("~1", "/").replace("~0", "~"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use serde_json::json;
+
+    fn specs() -> (Value, Value) {
+        let old = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "tags": ["users"],
+                        "operationId": "listUsers",
+                        "parameters": [
+                            { "name": "page", "in": "query", "schema": { "type": "integer" } }
+                        ]
+                    },
+                    "delete": { "tags": ["users"] }
+                },
+                "/invoices": {
+                    "get": { "tags": ["billing"] }
+                }
+            }
+        });
+
+        let new = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "tags": ["users"],
+                        "operationId": "listUsers",
+                        "description": "List of users",
+                        "parameters": [
+                            { "name": "page", "in": "query", "required": true, "schema": { "type": "integer" } },
+                            { "name": "limit", "in": "query", "schema": { "type": "integer" } }
+                        ]
+                    },
+                    "post": { "tags": ["users"], "operationId": "createUser" }
+                },
+                "/invoices": {
+                    "get": { "tags": ["billing"], "deprecated": true }
+                }
+            }
+        });
+
+        (old, new)
+    }
+
+    #[test]
+    fn test_markdown() {
+        let (old, new) = specs();
+        let schema = Schema::from_json(new);
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        let result = Changelog::options(Schema::from_json(old))
+            .render(&schema, &storage, &client)
+            .unwrap();
+
+        let expected = r#"# Changelog
+
+## users
+
+### `DELETE /users`
+
+#### Removed
+
+- **Breaking:** Endpoint removed
+
+### `GET /users` listUsers
+
+#### Added
+
+- Optional query parameter limit added (`parameters/1`)
+
+#### Changed
+
+- **Breaking:** Query parameter page became required (`parameters/0/required`)
+
+### `POST /users` createUser
+
+#### Added
+
+- Endpoint added
+
+## billing
+
+### `GET /invoices`
+
+#### Deprecated
+
+- Endpoint deprecated
+"#;
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "codegen")]
+    fn test_template() {
+        let (old, new) = specs();
+        let mut schema = Schema::from_json(new);
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        Changelog::options(Schema::from_json(old))
+            .with_template(Some(
+                "{% for t in tags %}{{ t.tag }}:{{ t.operations | length }};{% endfor %}"
+                    .to_string(),
+            ))
+            .process(&mut schema, &storage, &client)
+            .unwrap();
+
+        assert_eq!(schema.get_body(), &json!("users:3;billing:1;"));
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+use url::Url;
+
+use super::diff::escape;
+use crate::{schema::Schema, storage::SchemaStorage};
+
+pub const KINDS: [&str; 10] = [
+    "schemas",
+    "responses",
+    "parameters",
+    "examples",
+    "requestBodies",
+    "headers",
+    "securitySchemes",
+    "links",
+    "callbacks",
+    "pathItems",
+];
+
+/// Entry of openapi components section, ex. schemas/Pet
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct Component {
+    pub kind: String,
+    pub name: String,
+}
+
+impl Component {
+    pub fn new(kind: &str, name: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    pub fn pointer(&self) -> String {
+        format!("/components/{}/{}", self.kind, escape(&self.name))
+    }
+
+    /// Parses local reference pointing to component (or its part)
+    pub fn from_ref(reference: &str) -> Option<Self> {
+        match reference.split_once('#')? {
+            ("", fragment) => Self::from_fragment(fragment),
+            _ => None,
+        }
+    }
+
+    fn from_fragment(fragment: &str) -> Option<Self> {
+        let mut segments = fragment.strip_prefix("/components/")?.split('/');
+
+        match (segments.next(), segments.next()) {
+            (Some(kind), Some(name)) => {
+                Some(Self::new(kind, &name.replace("~1", "/").replace("~0", "~")))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.kind, self.name)
+    }
+}
+
+/// Components transitively reachable from paths, webhooks and top-level security
+pub fn used(root: &Value) -> BTreeSet<Component> {
+    Reachability {
+        root,
+        url: None,
+        storage: None,
+    }
+    .used()
+}
+
+/// Same as [`used`], references to external files are followed using storage
+pub fn used_with_storage(schema: &Schema, storage: &SchemaStorage) -> BTreeSet<Component> {
+    Reachability {
+        root: schema.get_body(),
+        url: Some(schema.get_url()),
+        storage: Some(storage),
+    }
+    .used()
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum Target {
+    Component(Component),
+    External(Url),
+}
+
+struct Reachability<'a> {
+    root: &'a Value,
+    url: Option<&'a Url>,
+    storage: Option<&'a SchemaStorage>,
+}
+
+impl<'a> Reachability<'a> {
+    fn used(&self) -> BTreeSet<Component> {
+        let mut queue = vec![];
+
+        for key in ["paths", "webhooks"] {
+            if let Some(node) = self.root.get(key) {
+                self.collect(node, None, &mut queue);
+            }
+        }
+
+        if let Some(security) = self.root.get("security") {
+            collect_security(security, &mut queue);
+        }
+
+        let mut visited = HashSet::new();
+        let mut used = BTreeSet::new();
+
+        while let Some(target) = queue.pop() {
+            if visited.contains(&target) {
+                continue;
+            }
+
+            match &target {
+                Target::Component(component) => {
+                    if let Some(node) = self.root.pointer(&component.pointer()) {
+                        self.collect(node, None, &mut queue);
+                    }
+
+                    used.insert(component.clone());
+                }
+                Target::External(url) => {
+                    if let Some(node) = self.external(url) {
+                        self.collect(node, Some(url), &mut queue);
+                    }
+                }
+            }
+
+            visited.insert(target);
+        }
+
+        used
+    }
+
+    fn external(&self, url: &Url) -> Option<&'a Value> {
+        let mut document = url.clone();
+        document.set_fragment(None);
+
+        let body = self.storage?.schemas.get(&document)?.get_body();
+
+        match url.fragment() {
+            Some(fragment) if !fragment.is_empty() => body.pointer(fragment),
+            _ => Some(body),
+        }
+    }
+
+    /// Resolves reference found in root document (base is none) or external one
+    fn target(&self, reference: &str, base: Option<&Url>) -> Option<Target> {
+        let (path, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+
+        if path.is_empty() && base.is_none() {
+            return Component::from_fragment(fragment).map(Target::Component);
+        }
+
+        let url = base.or(self.url)?.join(reference).ok()?;
+
+        let mut document = url.clone();
+        document.set_fragment(None);
+
+        if Some(&document) == self.url {
+            Component::from_fragment(fragment).map(Target::Component)
+        } else {
+            Some(Target::External(url))
+        }
+    }
+
+    fn collect(&self, node: &Value, base: Option<&Url>, queue: &mut Vec<Target>) {
+        match node {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    queue.extend(self.target(reference, base));
+                }
+
+                if let Some(Value::Object(mapping)) = map
+                    .get("discriminator")
+                    .and_then(|discriminator| discriminator.get("mapping"))
+                {
+                    for reference in mapping.values().filter_map(Value::as_str) {
+                        if reference.contains('#') {
+                            queue.extend(self.target(reference, base));
+                        } else if base.is_none() {
+                            queue.push(Target::Component(Component::new("schemas", reference)));
+                        }
+                    }
+                }
+
+                if let Some(security) = map.get("security").filter(|_| base.is_none()) {
+                    collect_security(security, queue);
+                }
+
+                for value in map.values() {
+                    self.collect(value, base, queue);
+                }
+            }
+            Value::Array(values) => {
+                for value in values {
+                    self.collect(value, base, queue);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_security(security: &Value, queue: &mut Vec<Target>) {
+    if let Value::Array(requirements) = security {
+        for requirement in requirements.iter().filter_map(Value::as_object) {
+            for name in requirement.keys() {
+                queue.push(Target::Component(Component::new("securitySchemes", name)));
+            }
+        }
+    }
+}
+
+/// Removes components which are not used nor kept, returns removed ones
+pub fn remove<F>(root: &mut Value, used: &BTreeSet<Component>, keep: F) -> Vec<Component>
+where
+    F: Fn(&Component) -> bool,
+{
+    let mut removed = vec![];
+
+    let Some(Value::Object(components)) = root.get_mut("components") else {
+        return removed;
+    };
+
+    for kind in KINDS {
+        let Some(Value::Object(entries)) = components.get_mut(kind) else {
+            continue;
+        };
+
+        let size = entries.len();
+
+        entries.retain(|name, _| {
+            let component = Component::new(kind, name);
+            let retain = used.contains(&component) || keep(&component);
+
+            if !retain {
+                removed.push(component);
+            }
+
+            retain
+        });
+
+        if entries.is_empty() && size > 0 {
+            components.shift_remove(kind);
+        }
+    }
+
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_used_and_remove() {
+        let mut value = json!({
+            "security": [{ "apiKey": [] }],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "security": [{ "oauth": ["read"] }],
+                        "responses": {
+                            "200": { "$ref": "#/components/responses/Pets" }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "oneOf": [{ "$ref": "#/components/schemas/Cat" }],
+                        "discriminator": { "propertyName": "kind", "mapping": { "dog": "Dog" } }
+                    },
+                    "Cat": { "type": "object" },
+                    "Dog": { "type": "object" },
+                    "Unused": { "$ref": "#/components/schemas/Other" },
+                    "Other": { "type": "object" },
+                    "Kept": { "type": "object" }
+                },
+                "responses": {
+                    "Pets": {
+                        "description": "Pets",
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Pet/items" }
+                            }
+                        }
+                    }
+                },
+                "parameters": {
+                    "Limit": { "name": "limit", "in": "query" }
+                },
+                "securitySchemes": {
+                    "apiKey": { "type": "apiKey", "name": "key", "in": "header" },
+                    "oauth": { "type": "oauth2" },
+                    "basic": { "type": "http", "scheme": "basic" }
+                }
+            }
+        });
+
+        let used = used(&value);
+        let removed = remove(&mut value, &used, |c| c.name == "Kept");
+
+        assert_eq!(
+            removed.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+            vec![
+                "schemas/Unused",
+                "schemas/Other",
+                "parameters/Limit",
+                "securitySchemes/basic"
+            ]
+        );
+        assert_eq!(
+            value["components"]["schemas"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["Pet", "Cat", "Dog", "Kept"]
+        );
+        assert!(value["components"].get("parameters").is_none());
+    }
+}
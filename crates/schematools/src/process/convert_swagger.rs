@@ -0,0 +1,1020 @@
+use std::collections::HashSet;
+
+use serde_json::{json, Map, Value};
+
+use crate::{error::Error, schema::Schema};
+
+pub const VERSION: &str = "3.0.3";
+
+const DEFAULT_MEDIA_TYPE: &str = "application/json";
+const METHODS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+const FORM_MEDIA_TYPES: [&str; 2] = ["multipart/form-data", "application/x-www-form-urlencoded"];
+
+pub struct Converter;
+
+pub struct ConverterOptions {
+    pub version: String,
+}
+
+struct Context {
+    consumes: Vec<String>,
+    produces: Vec<String>,
+    parameters: Map<String, Value>,
+}
+
+impl ConverterOptions {
+    pub fn with_version(&mut self, value: String) -> &mut Self {
+        self.version = value;
+        self
+    }
+
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        let body = schema.get_body_mut();
+        let root = body
+            .as_object_mut()
+            .ok_or(Error::InvalidOpenapiSchemaError)?;
+
+        match (root.get("swagger"), root.get("openapi")) {
+            (Some(Value::String(version)), _) if version == "2.0" => {}
+            (None, Some(version)) => {
+                log::warn!("document is already openapi {}, skipping", version);
+                return Ok(());
+            }
+            _ => return Err(Error::InvalidOpenapiSchemaError),
+        }
+
+        let body_parameters = root
+            .get("parameters")
+            .and_then(Value::as_object)
+            .map(|parameters| {
+                parameters
+                    .iter()
+                    .filter(|(_, p)| location(p) == Some("body"))
+                    .map(|(name, _)| name.clone())
+                    .collect::<HashSet<_>>()
+            })
+            .unwrap_or_default();
+
+        rewrite_refs(body, &body_parameters);
+
+        let swagger = std::mem::take(body.as_object_mut().unwrap());
+        *body = Value::Object(self.convert(swagger));
+
+        Ok(())
+    }
+
+    fn convert(&self, mut swagger: Map<String, Value>) -> Map<String, Value> {
+        let context = Context {
+            consumes: media_types(swagger.get("consumes")),
+            produces: media_types(swagger.get("produces")),
+            parameters: swagger
+                .get("parameters")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default(),
+        };
+
+        let mut result = Map::new();
+        result.insert("openapi".to_string(), Value::String(self.version.clone()));
+
+        if let Some(info) = swagger.shift_remove("info") {
+            result.insert("info".to_string(), info);
+        }
+
+        let servers = servers(
+            swagger.get("host").and_then(Value::as_str),
+            swagger.get("basePath").and_then(Value::as_str),
+            &media_types(swagger.get("schemes")),
+        );
+        if !servers.is_empty() {
+            result.insert("servers".to_string(), Value::Array(servers));
+        }
+
+        let mut components = Map::new();
+
+        for (key, value) in swagger {
+            match key.as_str() {
+                "swagger" | "host" | "basePath" | "schemes" | "consumes" | "produces" => {}
+                "paths" => {
+                    result.insert(key, convert_paths(value, &context));
+                }
+                "definitions" => {
+                    components.insert("schemas".to_string(), convert_schemas(value));
+                }
+                "parameters" => {
+                    let (parameters, request_bodies) = convert_global_parameters(value, &context);
+
+                    if !parameters.is_empty() {
+                        components.insert("parameters".to_string(), Value::Object(parameters));
+                    }
+                    if !request_bodies.is_empty() {
+                        components
+                            .insert("requestBodies".to_string(), Value::Object(request_bodies));
+                    }
+                }
+                "responses" => {
+                    components.insert(
+                        "responses".to_string(),
+                        convert_responses(value, &context.produces),
+                    );
+                }
+                "securityDefinitions" => {
+                    components.insert(
+                        "securitySchemes".to_string(),
+                        convert_security_definitions(value),
+                    );
+                }
+                _ => {
+                    result.insert(key, value);
+                }
+            }
+        }
+
+        result.entry("paths").or_insert_with(|| json!({}));
+
+        if !components.is_empty() {
+            result.insert("components".to_string(), Value::Object(components));
+        }
+
+        result
+    }
+}
+
+impl Converter {
+    pub fn options() -> ConverterOptions {
+        ConverterOptions {
+            version: VERSION.to_string(),
+        }
+    }
+}
+
+/// Rewrites references of swagger sections to their components counterparts
+fn rewrite_refs(node: &mut Value, body_parameters: &HashSet<String>) {
+    match node {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                if let Some(rewritten) = rewrite_ref(reference, body_parameters) {
+                    *reference = rewritten;
+                }
+            }
+
+            for (_, value) in map.iter_mut() {
+                rewrite_refs(value, body_parameters);
+            }
+        }
+        Value::Array(values) => {
+            for value in values.iter_mut() {
+                rewrite_refs(value, body_parameters);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Rewrites local reference, external files are not converted so references to them are kept
+fn rewrite_ref(reference: &str, body_parameters: &HashSet<String>) -> Option<String> {
+    let (url, fragment) = reference.split_once('#')?;
+
+    if !url.is_empty() {
+        log::warn!(
+            "{}: reference to external file left unchanged, file has to be converted separately",
+            reference
+        );
+        return None;
+    }
+
+    let (section, name) = fragment.strip_prefix('/')?.split_once('/')?;
+
+    let target = match section {
+        "definitions" => "schemas",
+        "responses" => "responses",
+        "parameters" if body_parameters.contains(name) => "requestBodies",
+        "parameters" => "parameters",
+        _ => return None,
+    };
+
+    Some(format!("#/components/{target}/{name}"))
+}
+
+fn location(parameter: &Value) -> Option<&str> {
+    parameter.get("in").and_then(Value::as_str)
+}
+
+fn media_types(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|types| {
+            types
+                .iter()
+                .filter_map(|t| t.as_str().map(|t| t.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn servers(host: Option<&str>, base_path: Option<&str>, schemes: &[String]) -> Vec<Value> {
+    let base_path = base_path.unwrap_or_default();
+
+    match host {
+        Some(host) if schemes.is_empty() => {
+            vec![json!({ "url": format!("https://{host}{base_path}") })]
+        }
+        Some(host) => schemes
+            .iter()
+            .map(|scheme| json!({ "url": format!("{scheme}://{host}{base_path}") }))
+            .collect(),
+        None if !base_path.is_empty() => vec![json!({ "url": base_path })],
+        None => vec![],
+    }
+}
+
+fn convert_paths(paths: Value, context: &Context) -> Value {
+    let Value::Object(paths) = paths else {
+        return paths;
+    };
+
+    Value::Object(
+        paths
+            .into_iter()
+            .map(|(path, item)| (path, convert_path_item(item, context)))
+            .collect(),
+    )
+}
+
+fn convert_path_item(item: Value, context: &Context) -> Value {
+    let Value::Object(item) = item else {
+        return item;
+    };
+
+    // body and formData parameters of path item have to be moved to each operation
+    let (inherited, parameters): (Vec<_>, Vec<_>) = item
+        .get("parameters")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|p| is_request_body(p, context));
+
+    item.into_iter()
+        .filter_map(|(key, value)| match key.as_str() {
+            "parameters" if parameters.is_empty() => None,
+            "parameters" => Some((
+                key,
+                Value::Array(parameters.iter().cloned().map(convert_parameter).collect()),
+            )),
+            method if METHODS.contains(&method) => {
+                Some((key, convert_operation(value, &inherited, context)))
+            }
+            _ => Some((key, value)),
+        })
+        .collect::<Map<_, _>>()
+        .into()
+}
+
+fn resolve<'a>(parameter: &'a Value, context: &'a Context) -> &'a Value {
+    parameter
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.strip_prefix("#/components/parameters/"))
+        .and_then(|name| context.parameters.get(name))
+        .unwrap_or(parameter)
+}
+
+fn is_body_ref(parameter: &Value) -> bool {
+    parameter
+        .get("$ref")
+        .and_then(Value::as_str)
+        .map(|r| r.starts_with("#/components/requestBodies/"))
+        .unwrap_or(false)
+}
+
+fn is_request_body(parameter: &Value, context: &Context) -> bool {
+    is_body_ref(parameter)
+        || matches!(
+            location(resolve(parameter, context)),
+            Some("body" | "formData")
+        )
+}
+
+fn convert_operation(operation: Value, inherited: &[Value], context: &Context) -> Value {
+    let Value::Object(mut operation) = operation else {
+        return operation;
+    };
+
+    let consumes = operation
+        .shift_remove("consumes")
+        .map(|v| media_types(Some(&v)))
+        .unwrap_or_else(|| context.consumes.clone());
+    let produces = operation
+        .shift_remove("produces")
+        .map(|v| media_types(Some(&v)))
+        .unwrap_or_else(|| context.produces.clone());
+    operation.shift_remove("schemes");
+
+    let mut parameters = vec![];
+    let mut form = vec![];
+    let mut request_body = None;
+
+    let own = operation
+        .get("parameters")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+
+    for parameter in inherited.iter().chain(own.iter()) {
+        if is_body_ref(parameter) {
+            request_body = Some(parameter.clone());
+            continue;
+        }
+
+        match location(resolve(parameter, context)) {
+            Some("body") => {
+                request_body = Some(convert_body_parameter(parameter.clone(), &consumes));
+            }
+            Some("formData") => {
+                let resolved = resolve(parameter, context).clone();
+                form.retain(|p: &Value| p.get("name") != resolved.get("name"));
+                form.push(resolved);
+            }
+            _ => parameters.push(convert_parameter(parameter.clone())),
+        }
+    }
+
+    if !form.is_empty() {
+        request_body = Some(convert_form_parameters(form, &consumes));
+    }
+
+    let mut result = Map::new();
+
+    for (key, value) in operation {
+        match key.as_str() {
+            "parameters" => {
+                if !parameters.is_empty() {
+                    result.insert(key, Value::Array(std::mem::take(&mut parameters)));
+                }
+                if let Some(request_body) = request_body.take() {
+                    result.insert("requestBody".to_string(), request_body);
+                }
+            }
+            "responses" => {
+                if let Some(request_body) = request_body.take() {
+                    result.insert("requestBody".to_string(), request_body);
+                }
+                result.insert(key, convert_responses(value, &produces));
+            }
+            _ => {
+                result.insert(key, value);
+            }
+        }
+    }
+
+    if let Some(request_body) = request_body {
+        result.insert("requestBody".to_string(), request_body);
+    }
+
+    Value::Object(result)
+}
+
+fn convert_global_parameters(
+    parameters: Value,
+    context: &Context,
+) -> (Map<String, Value>, Map<String, Value>) {
+    let mut result = Map::new();
+    let mut request_bodies = Map::new();
+
+    if let Value::Object(parameters) = parameters {
+        for (name, parameter) in parameters {
+            match location(&parameter) {
+                Some("body") => {
+                    request_bodies
+                        .insert(name, convert_body_parameter(parameter, &context.consumes));
+                }
+                // form fields are inlined into request bodies of operations
+                Some("formData") => {}
+                _ => {
+                    result.insert(name, convert_parameter(parameter));
+                }
+            }
+        }
+    }
+
+    (result, request_bodies)
+}
+
+fn convert_parameter(parameter: Value) -> Value {
+    let Value::Object(parameter) = parameter else {
+        return parameter;
+    };
+
+    if parameter.contains_key("$ref") {
+        return Value::Object(parameter);
+    }
+
+    let location = parameter
+        .get("in")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let is_array = parameter.get("type") == Some(&json!("array"));
+
+    let mut result = Map::new();
+    let mut schema = Map::new();
+    let mut collection_format = None;
+
+    for (key, value) in parameter {
+        match key.as_str() {
+            "name" | "in" | "description" | "required" | "allowEmptyValue" => {
+                result.insert(key, value);
+            }
+            "collectionFormat" => collection_format = value.as_str().map(|v| v.to_string()),
+            _ if key.starts_with("x-") => {
+                result.insert(key, value);
+            }
+            _ => {
+                schema.insert(key, value);
+            }
+        }
+    }
+
+    if is_array {
+        let (style, explode) = match (collection_format.as_deref(), location.as_str()) {
+            (Some("multi"), _) => (None, None),
+            (Some("ssv"), _) => (Some("spaceDelimited"), Some(false)),
+            (Some("pipes"), _) => (Some("pipeDelimited"), Some(false)),
+            (Some("tsv"), _) => {
+                log::warn!("tab separated collection format is not supported in openapi 3.0");
+                (None, None)
+            }
+            (_, "query" | "formData") => (Some("form"), Some(false)),
+            _ => (None, None),
+        };
+
+        if let Some(style) = style {
+            result.insert("style".to_string(), json!(style));
+        }
+        if let Some(explode) = explode {
+            result.insert("explode".to_string(), json!(explode));
+        }
+    }
+
+    result.insert("schema".to_string(), convert_schema(Value::Object(schema)));
+
+    Value::Object(result)
+}
+
+fn convert_body_parameter(parameter: Value, consumes: &[String]) -> Value {
+    let Value::Object(parameter) = parameter else {
+        return parameter;
+    };
+
+    let mut result = Map::new();
+    let mut schema = None;
+
+    for (key, value) in parameter {
+        match key.as_str() {
+            "description" | "required" => {
+                result.insert(key, value);
+            }
+            "schema" => schema = Some(convert_schema(value)),
+            _ if key.starts_with("x-") => {
+                result.insert(key, value);
+            }
+            _ => {}
+        }
+    }
+
+    result.insert(
+        "content".to_string(),
+        content(consumes, schema.unwrap_or_else(|| json!({})), None),
+    );
+
+    Value::Object(result)
+}
+
+fn convert_form_parameters(parameters: Vec<Value>, consumes: &[String]) -> Value {
+    let has_file = parameters
+        .iter()
+        .any(|p| p.get("type") == Some(&json!("file")));
+
+    let mut media_types = consumes
+        .iter()
+        .filter(|c| FORM_MEDIA_TYPES.contains(&c.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+    if media_types.is_empty() {
+        media_types.push(FORM_MEDIA_TYPES[if has_file { 0 } else { 1 }].to_string());
+    }
+
+    let mut properties = Map::new();
+    let mut required = vec![];
+
+    for parameter in parameters {
+        let Some(name) = parameter
+            .get("name")
+            .and_then(Value::as_str)
+            .map(String::from)
+        else {
+            continue;
+        };
+
+        if parameter.get("required") == Some(&Value::Bool(true)) {
+            required.push(Value::String(name.clone()));
+        }
+
+        let converted = convert_parameter(parameter);
+        let mut schema = converted
+            .get("schema")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+
+        if let (Some(description), Value::Object(schema)) =
+            (converted.get("description"), &mut schema)
+        {
+            schema.insert("description".to_string(), description.clone());
+        }
+
+        properties.insert(name, schema);
+    }
+
+    let mut schema = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+
+    let mut result = Map::new();
+    if schema.get("required").is_some() {
+        result.insert("required".to_string(), Value::Bool(true));
+    }
+    result.insert("content".to_string(), content(&media_types, schema, None));
+
+    Value::Object(result)
+}
+
+fn content(media_types: &[String], schema: Value, examples: Option<&Value>) -> Value {
+    let mut media_types = media_types.to_vec();
+    if media_types.is_empty() {
+        media_types.push(DEFAULT_MEDIA_TYPE.to_string());
+    }
+
+    Value::Object(
+        media_types
+            .into_iter()
+            .map(|media_type| {
+                let mut item = json!({ "schema": schema });
+                if let Some(example) = examples.and_then(|e| e.get(&media_type)) {
+                    item["example"] = example.clone();
+                }
+
+                (media_type, item)
+            })
+            .collect(),
+    )
+}
+
+fn convert_responses(responses: Value, produces: &[String]) -> Value {
+    let Value::Object(responses) = responses else {
+        return responses;
+    };
+
+    Value::Object(
+        responses
+            .into_iter()
+            .map(|(code, response)| (code, convert_response(response, produces)))
+            .collect(),
+    )
+}
+
+fn convert_response(response: Value, produces: &[String]) -> Value {
+    let Value::Object(mut response) = response else {
+        return response;
+    };
+
+    if response.contains_key("$ref") {
+        return Value::Object(response);
+    }
+
+    let examples = response.shift_remove("examples");
+    let mut result = Map::new();
+
+    for (key, value) in response {
+        match key.as_str() {
+            "schema" => {
+                result.insert(
+                    "content".to_string(),
+                    content(produces, convert_schema(value), examples.as_ref()),
+                );
+            }
+            "headers" => {
+                result.insert(key, convert_headers(value));
+            }
+            _ => {
+                result.insert(key, value);
+            }
+        }
+    }
+
+    Value::Object(result)
+}
+
+fn convert_headers(headers: Value) -> Value {
+    let Value::Object(headers) = headers else {
+        return headers;
+    };
+
+    Value::Object(
+        headers
+            .into_iter()
+            .map(|(name, header)| {
+                let mut header = convert_parameter(header);
+                if let Value::Object(header) = &mut header {
+                    header.shift_remove("style");
+                    header.shift_remove("explode");
+                }
+
+                (name, header)
+            })
+            .collect(),
+    )
+}
+
+fn convert_schemas(schemas: Value) -> Value {
+    let Value::Object(schemas) = schemas else {
+        return schemas;
+    };
+
+    Value::Object(
+        schemas
+            .into_iter()
+            .map(|(name, schema)| (name, convert_schema(schema)))
+            .collect(),
+    )
+}
+
+/// Converts swagger specific keywords of schema object
+pub fn convert_schema(schema: Value) -> Value {
+    let Value::Object(schema) = schema else {
+        return schema;
+    };
+
+    let mut result = Map::new();
+
+    for (key, value) in schema {
+        match key.as_str() {
+            "x-nullable" => {
+                result.insert("nullable".to_string(), value);
+            }
+            "type" if value == json!("file") => {
+                result.insert(key, json!("string"));
+                result.insert("format".to_string(), json!("binary"));
+            }
+            "discriminator" if value.is_string() => {
+                result.insert(key, json!({ "propertyName": value }));
+            }
+            "collectionFormat" => {}
+            "properties" => {
+                result.insert(key, convert_schemas(value));
+            }
+            "items" | "additionalProperties" | "not" => {
+                result.insert(key, convert_schema(value));
+            }
+            "allOf" | "anyOf" | "oneOf" => {
+                let value = match value {
+                    Value::Array(values) => {
+                        Value::Array(values.into_iter().map(convert_schema).collect())
+                    }
+                    value => value,
+                };
+                result.insert(key, value);
+            }
+            _ => {
+                result.insert(key, value);
+            }
+        }
+    }
+
+    Value::Object(result)
+}
+
+fn convert_security_definitions(definitions: Value) -> Value {
+    let Value::Object(definitions) = definitions else {
+        return definitions;
+    };
+
+    Value::Object(
+        definitions
+            .into_iter()
+            .map(|(name, definition)| (name, convert_security_definition(definition)))
+            .collect(),
+    )
+}
+
+fn convert_security_definition(definition: Value) -> Value {
+    let Value::Object(mut definition) = definition else {
+        return definition;
+    };
+
+    let kind = definition
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    match kind.as_str() {
+        "basic" => {
+            definition.insert("type".to_string(), json!("http"));
+            definition.insert("scheme".to_string(), json!("basic"));
+        }
+        "oauth2" => {
+            let flow = definition.shift_remove("flow");
+            let mut converted = Map::new();
+
+            for key in ["authorizationUrl", "tokenUrl", "scopes"] {
+                if let Some(value) = definition.shift_remove(key) {
+                    converted.insert(key.to_string(), value);
+                }
+            }
+            converted.entry("scopes").or_insert_with(|| json!({}));
+
+            let flow = match flow.as_ref().and_then(Value::as_str) {
+                Some("implicit") => "implicit",
+                Some("password") => "password",
+                Some("application") => "clientCredentials",
+                _ => "authorizationCode",
+            };
+
+            definition.insert("flows".to_string(), json!({ flow: converted }));
+        }
+        _ => {}
+    }
+
+    Value::Object(definition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use url::Url;
+
+    #[test]
+    fn test_convert_swagger() {
+        let value = json!({
+            "swagger": "2.0",
+            "info": { "title": "Pets", "version": "1.0.0" },
+            "host": "api.example.com",
+            "basePath": "/v1",
+            "schemes": ["https", "http"],
+            "consumes": ["application/json"],
+            "produces": ["application/json"],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "operationId": "listPets",
+                        "parameters": [
+                            { "$ref": "#/parameters/Limit" },
+                            { "name": "tags", "in": "query", "type": "array", "items": { "type": "string" } }
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "Pets",
+                                "headers": { "X-Total": { "type": "integer" } },
+                                "schema": { "type": "array", "items": { "$ref": "#/definitions/Pet" } }
+                            },
+                            "default": { "$ref": "#/responses/Error" }
+                        }
+                    },
+                    "post": {
+                        "operationId": "createPet",
+                        "parameters": [
+                            { "name": "pet", "in": "body", "required": true, "schema": { "$ref": "#/definitions/Pet" } }
+                        ],
+                        "responses": { "201": { "description": "Created" } }
+                    }
+                },
+                "/pets/{id}/photo": {
+                    "parameters": [
+                        { "name": "id", "in": "path", "required": true, "type": "string" }
+                    ],
+                    "put": {
+                        "consumes": ["multipart/form-data"],
+                        "parameters": [
+                            { "name": "file", "in": "formData", "required": true, "type": "file" },
+                            { "name": "caption", "in": "formData", "type": "string" }
+                        ],
+                        "responses": { "204": { "description": "Uploaded" } },
+                        "security": [{ "oauth": ["write"] }]
+                    }
+                }
+            },
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "discriminator": "kind",
+                    "properties": {
+                        "kind": { "type": "string" },
+                        "owner": { "type": "string", "x-nullable": true }
+                    }
+                }
+            },
+            "parameters": {
+                "Limit": { "name": "limit", "in": "query", "type": "integer", "format": "int32" }
+            },
+            "responses": {
+                "Error": { "description": "Error", "schema": { "type": "string" } }
+            },
+            "securityDefinitions": {
+                "basic": { "type": "basic" },
+                "oauth": {
+                    "type": "oauth2",
+                    "flow": "accessCode",
+                    "authorizationUrl": "https://example.com/authorize",
+                    "tokenUrl": "https://example.com/token",
+                    "scopes": { "write": "Write access" }
+                }
+            }
+        });
+
+        let expected = json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Pets", "version": "1.0.0" },
+            "servers": [
+                { "url": "https://api.example.com/v1" },
+                { "url": "http://api.example.com/v1" }
+            ],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "operationId": "listPets",
+                        "parameters": [
+                            { "$ref": "#/components/parameters/Limit" },
+                            { "name": "tags", "in": "query", "style": "form", "explode": false, "schema": { "type": "array", "items": { "type": "string" } } }
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "Pets",
+                                "headers": { "X-Total": { "schema": { "type": "integer" } } },
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "type": "array", "items": { "$ref": "#/components/schemas/Pet" } }
+                                    }
+                                }
+                            },
+                            "default": { "$ref": "#/components/responses/Error" }
+                        }
+                    },
+                    "post": {
+                        "operationId": "createPet",
+                        "requestBody": {
+                            "required": true,
+                            "content": {
+                                "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } }
+                            }
+                        },
+                        "responses": { "201": { "description": "Created" } }
+                    }
+                },
+                "/pets/{id}/photo": {
+                    "parameters": [
+                        { "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }
+                    ],
+                    "put": {
+                        "requestBody": {
+                            "required": true,
+                            "content": {
+                                "multipart/form-data": {
+                                    "schema": {
+                                        "type": "object",
+                                        "properties": {
+                                            "file": { "type": "string", "format": "binary" },
+                                            "caption": { "type": "string" }
+                                        },
+                                        "required": ["file"]
+                                    }
+                                }
+                            }
+                        },
+                        "responses": { "204": { "description": "Uploaded" } },
+                        "security": [{ "oauth": ["write"] }]
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "discriminator": { "propertyName": "kind" },
+                        "properties": {
+                            "kind": { "type": "string" },
+                            "owner": { "type": "string", "nullable": true }
+                        }
+                    }
+                },
+                "parameters": {
+                    "Limit": { "name": "limit", "in": "query", "schema": { "type": "integer", "format": "int32" } }
+                },
+                "responses": {
+                    "Error": {
+                        "description": "Error",
+                        "content": { "application/json": { "schema": { "type": "string" } } }
+                    }
+                },
+                "securitySchemes": {
+                    "basic": { "type": "http", "scheme": "basic" },
+                    "oauth": {
+                        "type": "oauth2",
+                        "flows": {
+                            "authorizationCode": {
+                                "authorizationUrl": "https://example.com/authorize",
+                                "tokenUrl": "https://example.com/token",
+                                "scopes": { "write": "Write access" }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        Converter::options().process(&mut schema).unwrap();
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_body_parameter_reference() {
+        let value = json!({
+            "swagger": "2.0",
+            "info": { "title": "Pets", "version": "1.0.0" },
+            "paths": {
+                "/pets": {
+                    "post": {
+                        "parameters": [{ "$ref": "#/parameters/Pet" }],
+                        "responses": {}
+                    }
+                }
+            },
+            "parameters": {
+                "Pet": { "name": "pet", "in": "body", "schema": { "type": "object" } }
+            }
+        });
+
+        let expected = json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Pets", "version": "1.0.0" },
+            "paths": {
+                "/pets": {
+                    "post": {
+                        "requestBody": { "$ref": "#/components/requestBodies/Pet" },
+                        "responses": {}
+                    }
+                }
+            },
+            "components": {
+                "requestBodies": {
+                    "Pet": { "content": { "application/json": { "schema": { "type": "object" } } } }
+                }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        Converter::options().process(&mut schema).unwrap();
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_external_references() {
+        let url = Url::parse(&format!(
+            "file://{}/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            "resources/test/swagger/01-external-definitions.yaml"
+        ))
+        .unwrap();
+        let mut schema = Schema::load_url(url).unwrap();
+
+        Converter::options().process(&mut schema).unwrap();
+
+        let operation = schema.get_body().pointer("/paths/~1pets/post").unwrap();
+
+        assert_eq!(
+            operation.get("parameters"),
+            Some(&json!([{ "$ref": "./01-common.yaml#/parameters/Body" }]))
+        );
+        assert_eq!(
+            operation.pointer("/responses/200/content/application~1json/schema/$ref"),
+            Some(&json!("#/components/schemas/Pet"))
+        );
+        assert_eq!(
+            operation.pointer("/responses/default/content/application~1json/schema/$ref"),
+            Some(&json!("./01-common.yaml#/definitions/Error"))
+        );
+    }
+
+    #[test]
+    fn test_not_swagger() {
+        let mut schema = Schema::from_json(json!({"type": "object"}));
+
+        assert!(Converter::options().process(&mut schema).is_err());
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::components::Component;
+use super::rename_schema::rewrite_refs;
+use crate::{error::Error, schema::Schema};
+
+/// Keywords which values are maps of schemas
+const SCHEMA_MAPS: [&str; 5] = [
+    "properties",
+    "patternProperties",
+    "definitions",
+    "$defs",
+    "dependentSchemas",
+];
+
+/// Keywords which values are schemas or lists of schemas
+const SCHEMA_KEYS: [&str; 15] = [
+    "items",
+    "prefixItems",
+    "additionalItems",
+    "additionalProperties",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "contains",
+    "propertyNames",
+    "not",
+    "if",
+    "then",
+    "else",
+    "allOf",
+    "oneOf",
+    "anyOf",
+];
+
+pub struct Deduplicator;
+
+pub struct DeduplicatorOptions {
+    pub ignore: Vec<String>,
+    pub report: bool,
+}
+
+/// Structurally equal schemas, duplicates are replaced by canonical one
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DuplicateGroup {
+    pub canonical: String,
+    pub duplicates: Vec<String>,
+}
+
+impl DeduplicatorOptions {
+    /// Keywords skipped when schemas are compared, ex. `title`, `description`
+    pub fn with_ignore(&mut self, value: Vec<String>) -> &mut Self {
+        self.ignore = value;
+        self
+    }
+
+    /// Only reports duplicates, schema is left intact
+    pub fn with_report(&mut self, value: bool) -> &mut Self {
+        self.report = value;
+        self
+    }
+
+    /// Replaces structurally equal `components/schemas` by first of them, returns merged groups
+    pub fn process(&self, schema: &mut Schema) -> Result<Vec<DuplicateGroup>, Error> {
+        let url = schema.get_url().clone();
+        let mut copy;
+
+        let root = if self.report {
+            copy = schema.get_body().clone();
+            &mut copy
+        } else {
+            schema.get_body_mut()
+        };
+
+        let mut groups: Vec<DuplicateGroup> = vec![];
+
+        // merging schemas may make schemas referencing them equal, so repeat until nothing changes
+        loop {
+            let found = self.find_duplicates(root);
+
+            if found.is_empty() {
+                break;
+            }
+
+            for names in found {
+                let (canonical, duplicates) = names.split_first().unwrap();
+
+                for duplicate in duplicates {
+                    if let Some(Value::Object(schemas)) = root.pointer_mut("/components/schemas") {
+                        schemas.shift_remove(duplicate);
+                    }
+
+                    rewrite_refs(root, &url, &Component::new("schemas", duplicate), canonical);
+                }
+
+                merge_group(&mut groups, canonical, duplicates);
+            }
+        }
+
+        for group in groups.iter() {
+            log::info!(
+                "/components/schemas/{}: duplicated by {}",
+                group.canonical,
+                group.duplicates.join(", ")
+            );
+        }
+
+        Ok(groups)
+    }
+
+    /// Names of equal schemas in document order
+    fn find_duplicates(&self, root: &Value) -> Vec<Vec<String>> {
+        let Some(schemas) = root
+            .pointer("/components/schemas")
+            .and_then(Value::as_object)
+        else {
+            return vec![];
+        };
+
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut found: Vec<Vec<String>> = vec![];
+
+        for (name, schema) in schemas {
+            let key = canonical(schema, &self.ignore).to_string();
+
+            match positions.get(&key) {
+                Some(position) => found[*position].push(name.clone()),
+                None => {
+                    positions.insert(key, found.len());
+                    found.push(vec![name.clone()]);
+                }
+            }
+        }
+
+        found.into_iter().filter(|names| names.len() > 1).collect()
+    }
+}
+
+impl Deduplicator {
+    pub fn options() -> DeduplicatorOptions {
+        DeduplicatorOptions {
+            ignore: vec![],
+            report: false,
+        }
+    }
+}
+
+/// Adds merged schemas to groups, groups of schemas merged in previous passes are joined
+fn merge_group(groups: &mut Vec<DuplicateGroup>, canonical: &str, duplicates: &[String]) {
+    let mut merged = vec![];
+
+    for duplicate in duplicates {
+        merged.push(duplicate.clone());
+
+        if let Some(position) = groups.iter().position(|g| g.canonical == *duplicate) {
+            merged.extend(groups.remove(position).duplicates);
+        }
+    }
+
+    match groups.iter_mut().find(|g| g.canonical == canonical) {
+        Some(group) => group.duplicates.extend(merged),
+        None => groups.push(DuplicateGroup {
+            canonical: canonical.to_string(),
+            duplicates: merged,
+        }),
+    }
+}
+
+/// Schema with sorted keys and without ignored keywords
+fn canonical(node: &Value, ignore: &[String]) -> Value {
+    match node {
+        Value::Object(map) => {
+            let mut keys = map
+                .keys()
+                .filter(|k| !ignore.contains(k))
+                .collect::<Vec<_>>();
+            keys.sort();
+
+            Value::Object(
+                keys.into_iter()
+                    .map(|key| {
+                        let value = &map[key];
+
+                        let value = match (key.as_str(), value) {
+                            (k, Value::Object(schemas)) if SCHEMA_MAPS.contains(&k) => {
+                                let mut names = schemas.keys().collect::<Vec<_>>();
+                                names.sort();
+
+                                Value::Object(
+                                    names
+                                        .into_iter()
+                                        .map(|n| (n.clone(), canonical(&schemas[n], ignore)))
+                                        .collect::<Map<_, _>>(),
+                                )
+                            }
+                            (k, _) if SCHEMA_KEYS.contains(&k) => canonical(value, ignore),
+                            // order of required properties is meaningless
+                            ("required", Value::Array(items)) => {
+                                let mut items = items.clone();
+                                items.sort_by_key(|i| i.to_string());
+                                Value::Array(items)
+                            }
+                            _ => sorted(value),
+                        };
+
+                        (key.clone(), value)
+                    })
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|i| canonical(i, ignore)).collect()),
+        _ => node.clone(),
+    }
+}
+
+/// Value with recursively sorted keys
+pub(crate) fn sorted(node: &Value) -> Value {
+    match node {
+        Value::Object(map) => {
+            let mut keys = map.keys().collect::<Vec<_>>();
+            keys.sort();
+
+            Value::Object(
+                keys.into_iter()
+                    .map(|key| (key.clone(), sorted(&map[key])))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(sorted).collect()),
+        _ => node.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> Value {
+        json!({
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "responses": {
+                            "400": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/ApiError" }
+                                    }
+                                }
+                            },
+                            "500": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/ErrorResponse" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Error": {
+                        "type": "object",
+                        "required": ["code", "detail"],
+                        "properties": {
+                            "code": { "type": "integer" },
+                            "detail": { "$ref": "#/components/schemas/Detail" }
+                        }
+                    },
+                    "Detail": {
+                        "type": "object",
+                        "properties": { "title": { "type": "string" } }
+                    },
+                    "ErrorResponse": {
+                        "description": "Error response",
+                        "type": "object",
+                        "required": ["detail", "code"],
+                        "properties": {
+                            "detail": { "$ref": "#/components/schemas/ErrorDetail" },
+                            "code": { "type": "integer" }
+                        }
+                    },
+                    "ErrorDetail": {
+                        "title": "Detail",
+                        "properties": { "title": { "type": "string" } },
+                        "type": "object"
+                    },
+                    "ApiError": {
+                        "title": "Api error",
+                        "type": "object",
+                        "required": ["code", "detail"],
+                        "properties": {
+                            "code": { "type": "integer" },
+                            "detail": { "$ref": "#/components/schemas/Detail" }
+                        }
+                    },
+                    "Pet": {
+                        "type": "object",
+                        "properties": { "code": { "type": "string" } }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_dedupe() {
+        let mut schema = Schema::from_json(spec());
+
+        let groups = Deduplicator::options()
+            .with_ignore(vec!["title".to_string(), "description".to_string()])
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(
+            groups,
+            vec![
+                DuplicateGroup {
+                    canonical: "Error".to_string(),
+                    duplicates: vec!["ApiError".to_string(), "ErrorResponse".to_string()]
+                },
+                DuplicateGroup {
+                    canonical: "Detail".to_string(),
+                    duplicates: vec!["ErrorDetail".to_string()]
+                },
+            ]
+        );
+
+        let body = schema.get_body();
+
+        assert_eq!(
+            body.pointer("/components/schemas")
+                .and_then(Value::as_object)
+                .map(|s| s.keys().cloned().collect::<Vec<_>>()),
+            Some(vec![
+                "Error".to_string(),
+                "Detail".to_string(),
+                "Pet".to_string()
+            ])
+        );
+        assert_eq!(
+            body.pointer("/paths/~1pets/get/responses/400/content/application~1json/schema/$ref"),
+            Some(&json!("#/components/schemas/Error"))
+        );
+        assert_eq!(
+            body.pointer("/paths/~1pets/get/responses/500/content/application~1json/schema/$ref"),
+            Some(&json!("#/components/schemas/Error"))
+        );
+    }
+
+    #[test]
+    fn test_dedupe_without_ignore() {
+        let mut schema = Schema::from_json(spec());
+
+        let groups = Deduplicator::options()
+            .with_report(true)
+            .process(&mut schema)
+            .unwrap();
+
+        // titles of properties are not keywords, only title of ErrorDetail differs
+        assert!(groups.is_empty());
+        assert_eq!(schema.get_body(), &spec());
+    }
+
+    #[test]
+    fn test_report() {
+        let mut schema = Schema::from_json(spec());
+
+        let groups = Deduplicator::options()
+            .with_ignore(vec!["title".to_string(), "description".to_string()])
+            .with_report(true)
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(schema.get_body(), &spec());
+    }
+}
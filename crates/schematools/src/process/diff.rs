@@ -0,0 +1,1772 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::Error, resolver::SchemaResolver, schema::Schema, scope::SchemaScope,
+    storage::SchemaStorage, Client,
+};
+
+pub const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+pub struct Differ;
+
+pub struct DifferOptions {
+    pub original: Schema,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    Breaking,
+    NonBreaking,
+    Unknown,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeKind {
+    EndpointAdded,
+    EndpointRemoved,
+    EndpointDeprecated,
+    ParameterAdded,
+    ParameterRemoved,
+    ParameterRequired,
+    ParameterOptional,
+    ParameterDeprecated,
+    RequestBodyAdded,
+    RequestBodyRemoved,
+    RequestBodyRequired,
+    RequestBodyOptional,
+    ResponseAdded,
+    ResponseRemoved,
+    MediaTypeAdded,
+    MediaTypeRemoved,
+    HeaderAdded,
+    HeaderRemoved,
+    PropertyAdded,
+    PropertyRemoved,
+    PropertyRequired,
+    PropertyOptional,
+    PropertyDeprecated,
+    EnumValueAdded,
+    EnumValueRemoved,
+    TypeNarrowed,
+    TypeWidened,
+    TypeChanged,
+    FormatChanged,
+    ConstraintTightened,
+    ConstraintRelaxed,
+    ConstraintChanged,
+    SecurityChanged,
+    DescriptionChanged,
+}
+
+impl ChangeKind {
+    /// Change affects documentation only
+    pub fn is_documentation(&self) -> bool {
+        matches!(self, Self::DescriptionChanged)
+    }
+
+    /// Change adds something to the api
+    pub fn is_addition(&self) -> bool {
+        matches!(
+            self,
+            Self::EndpointAdded
+                | Self::ParameterAdded
+                | Self::RequestBodyAdded
+                | Self::ResponseAdded
+                | Self::MediaTypeAdded
+                | Self::HeaderAdded
+                | Self::PropertyAdded
+                | Self::EnumValueAdded
+        )
+    }
+
+    /// Change removes something from the api
+    pub fn is_removal(&self) -> bool {
+        matches!(
+            self,
+            Self::EndpointRemoved
+                | Self::ParameterRemoved
+                | Self::RequestBodyRemoved
+                | Self::ResponseRemoved
+                | Self::MediaTypeRemoved
+                | Self::HeaderRemoved
+                | Self::PropertyRemoved
+                | Self::EnumValueRemoved
+        )
+    }
+
+    /// Change marks something as deprecated
+    pub fn is_deprecation(&self) -> bool {
+        matches!(
+            self,
+            Self::EndpointDeprecated | Self::ParameterDeprecated | Self::PropertyDeprecated
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    pub method: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub severity: Severity,
+    pub pointer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<Operation>,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Report {
+    pub changes: Vec<Change>,
+}
+
+impl Report {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.changes
+            .iter()
+            .filter(|c| c.severity == severity)
+            .count()
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        self.count(Severity::Breaking) > 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Breaking => write!(f, "breaking"),
+            Self::NonBreaking => write!(f, "non-breaking"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method.to_uppercase(), self.path)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "breaking: {}, non-breaking: {}, unknown: {}",
+            self.count(Severity::Breaking),
+            self.count(Severity::NonBreaking),
+            self.count(Severity::Unknown)
+        )?;
+
+        for change in &self.changes {
+            match &change.operation {
+                Some(operation) => writeln!(
+                    f,
+                    "[{}] {}: {} ({})",
+                    change.severity, operation, change.message, change.pointer
+                )?,
+                None => writeln!(
+                    f,
+                    "[{}] {} ({})",
+                    change.severity, change.message, change.pointer
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Differ {
+    pub fn options(original: Schema) -> DifferOptions {
+        DifferOptions { original }
+    }
+}
+
+impl DifferOptions {
+    pub fn process(
+        &self,
+        schema: &mut Schema,
+        storage: &SchemaStorage,
+        client: &Client,
+    ) -> Result<(), Error> {
+        let report = self.compare(schema, storage, client)?;
+
+        let body = schema.get_body_mut();
+        *body = serde_json::to_value(report).map_err(Error::SerdeJsonError)?;
+
+        Ok(())
+    }
+
+    /// Compares schema with original one, external files of original are loaded with client
+    pub fn compare(
+        &self,
+        schema: &Schema,
+        storage: &SchemaStorage,
+        client: &Client,
+    ) -> Result<Report, Error> {
+        let original_storage = SchemaStorage::new(&self.original, client);
+
+        let mut context = Context {
+            old: SchemaResolver::new(&self.original, &original_storage),
+            new: SchemaResolver::new(schema, storage),
+            scope: SchemaScope::default(),
+            changes: vec![],
+            operation: None,
+            direction: Direction::Request,
+            visited: HashSet::new(),
+        };
+
+        let old = self
+            .original
+            .get_body()
+            .as_object()
+            .ok_or(Error::InvalidOpenapiSchemaError)?;
+        let new = schema
+            .get_body()
+            .as_object()
+            .ok_or(Error::InvalidOpenapiSchemaError)?;
+
+        compare_paths(old, new, &mut context);
+
+        Ok(Report {
+            changes: context.changes,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Direction {
+    Request,
+    Response,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Side {
+    Old,
+    New,
+}
+
+struct Context<'a> {
+    old: SchemaResolver<'a>,
+    new: SchemaResolver<'a>,
+    scope: SchemaScope,
+    changes: Vec<Change>,
+    operation: Option<Operation>,
+    direction: Direction,
+    visited: HashSet<(String, String)>,
+}
+
+impl Context<'_> {
+    fn add(&mut self, kind: ChangeKind, severity: Severity, pointer: &str, message: String) {
+        log::debug!("{}: {} {:?}", pointer, severity, kind);
+
+        self.changes.push(Change {
+            kind,
+            severity,
+            pointer: pointer.to_string(),
+            operation: self.operation.clone(),
+            message,
+        });
+    }
+
+    /// Severity of change which narrows set of accepted values
+    fn narrowing(&self) -> Severity {
+        match self.direction {
+            Direction::Request => Severity::Breaking,
+            Direction::Response => Severity::NonBreaking,
+        }
+    }
+
+    /// Severity of change which widens set of accepted values
+    fn widening(&self) -> Severity {
+        match self.direction {
+            Direction::Request => Severity::NonBreaking,
+            Direction::Response => Severity::Breaking,
+        }
+    }
+
+    fn side_name(&self) -> &'static str {
+        match self.direction {
+            Direction::Request => "request",
+            Direction::Response => "response",
+        }
+    }
+
+    fn resolve(&mut self, side: Side, node: &Value) -> Value {
+        let resolver = match side {
+            Side::Old => &self.old,
+            Side::New => &self.new,
+        };
+
+        resolver
+            .resolve(node, &mut self.scope, |resolved, _| Ok(resolved.clone()))
+            .unwrap_or_else(|_| node.clone())
+    }
+}
+
+pub fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn normalize_path(path: &str) -> String {
+    lazy_static! {
+        static ref PARAMETER: regex::Regex = regex::Regex::new(r"\{[^}]*\}").unwrap();
+    }
+
+    PARAMETER.replace_all(path, "{}").to_string()
+}
+
+fn operation(path: &str, method: &str, details: &Map<String, Value>) -> Operation {
+    Operation {
+        method: method.to_string(),
+        path: path.to_string(),
+        operation_id: details
+            .get("operationId")
+            .and_then(|s| s.as_str())
+            .map(|s| s.to_string()),
+        tags: details
+            .get("tags")
+            .and_then(|s| s.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+fn operations(root: &Map<String, Value>, ctx: &mut Context, side: Side) -> Vec<OperationEntry> {
+    let mut result = vec![];
+
+    if let Some(Value::Object(paths)) = root.get("paths") {
+        for (path, item) in paths {
+            let item = ctx.resolve(side, item);
+
+            if let Value::Object(item) = item {
+                for method in METHODS {
+                    if let Some(Value::Object(details)) = item.get(method) {
+                        result.push(OperationEntry {
+                            key: (normalize_path(path), method.to_string()),
+                            path: path.clone(),
+                            method: method.to_string(),
+                            details: details.clone(),
+                            parameters: item
+                                .get("parameters")
+                                .cloned()
+                                .unwrap_or(Value::Array(vec![])),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+struct OperationEntry {
+    key: (String, String),
+    path: String,
+    method: String,
+    details: Map<String, Value>,
+    parameters: Value,
+}
+
+fn compare_paths(old: &Map<String, Value>, new: &Map<String, Value>, ctx: &mut Context) {
+    let old_operations = operations(old, ctx, Side::Old);
+    let new_operations = operations(new, ctx, Side::New);
+
+    for o in old_operations.iter() {
+        if !new_operations.iter().any(|n| n.key == o.key) {
+            ctx.operation = Some(operation(&o.path, &o.method, &o.details));
+            ctx.add(
+                ChangeKind::EndpointRemoved,
+                Severity::Breaking,
+                &format!("/paths/{}/{}", escape(&o.path), o.method),
+                "endpoint removed".to_string(),
+            );
+        }
+    }
+
+    for n in new_operations.iter() {
+        let pointer = format!("/paths/{}/{}", escape(&n.path), n.method);
+        ctx.operation = Some(operation(&n.path, &n.method, &n.details));
+
+        match old_operations.iter().find(|o| o.key == n.key) {
+            Some(o) => {
+                compare_operation(o, n, old, new, &pointer, ctx);
+            }
+            None => ctx.add(
+                ChangeKind::EndpointAdded,
+                Severity::NonBreaking,
+                &pointer,
+                "endpoint added".to_string(),
+            ),
+        }
+    }
+
+    ctx.operation = None;
+}
+
+fn compare_operation(
+    old: &OperationEntry,
+    new: &OperationEntry,
+    old_root: &Map<String, Value>,
+    new_root: &Map<String, Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    if !is_true(old.details.get("deprecated")) && is_true(new.details.get("deprecated")) {
+        ctx.add(
+            ChangeKind::EndpointDeprecated,
+            Severity::NonBreaking,
+            &format!("{pointer}/deprecated"),
+            "endpoint deprecated".to_string(),
+        );
+    }
+
+    for key in ["summary", "description"] {
+        compare_documentation(
+            old.details.get(key),
+            new.details.get(key),
+            &format!("{pointer}/{key}"),
+            ctx,
+        );
+    }
+
+    ctx.direction = Direction::Request;
+    compare_parameters(old, new, ctx);
+    compare_request_body(
+        old.details.get("requestBody"),
+        new.details.get("requestBody"),
+        &format!("{pointer}/requestBody"),
+        ctx,
+    );
+
+    ctx.direction = Direction::Response;
+    compare_responses(
+        old.details.get("responses"),
+        new.details.get("responses"),
+        &format!("{pointer}/responses"),
+        ctx,
+    );
+
+    compare_security(
+        old.details.get("security").or(old_root.get("security")),
+        new.details.get("security").or(new_root.get("security")),
+        &format!("{pointer}/security"),
+        ctx,
+    );
+}
+
+fn is_true(value: Option<&Value>) -> bool {
+    value.and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+fn compare_documentation(
+    old: Option<&Value>,
+    new: Option<&Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    if old != new {
+        ctx.add(
+            ChangeKind::DescriptionChanged,
+            Severity::NonBreaking,
+            pointer,
+            format!("{} changed", pointer.rsplit('/').next().unwrap_or_default()),
+        );
+    }
+}
+
+/// Effective list of operation parameters, operation level overrides path level
+fn parameters(entry: &OperationEntry, ctx: &mut Context, side: Side) -> Vec<(String, Value)> {
+    let mut result: Vec<(String, Value)> = vec![];
+
+    let lists = [
+        (
+            format!("/paths/{}/parameters", escape(&entry.path)),
+            entry.parameters.clone(),
+        ),
+        (
+            format!("/paths/{}/{}/parameters", escape(&entry.path), entry.method),
+            entry
+                .details
+                .get("parameters")
+                .cloned()
+                .unwrap_or(Value::Array(vec![])),
+        ),
+    ];
+
+    for (pointer, list) in lists {
+        if let Value::Array(list) = list {
+            for (i, parameter) in list.iter().enumerate() {
+                let resolved = ctx.resolve(side, parameter);
+                let key = parameter_key(&resolved);
+
+                result.retain(|(_, p)| parameter_key(p) != key);
+                result.push((format!("{pointer}/{i}"), resolved));
+            }
+        }
+    }
+
+    result
+}
+
+fn parameter_key(parameter: &Value) -> (String, String) {
+    (
+        parameter["name"].as_str().unwrap_or_default().to_string(),
+        parameter["in"].as_str().unwrap_or_default().to_string(),
+    )
+}
+
+fn compare_parameters(old: &OperationEntry, new: &OperationEntry, ctx: &mut Context) {
+    let old_parameters = parameters(old, ctx, Side::Old);
+    let new_parameters = parameters(new, ctx, Side::New);
+
+    for (old_pointer, o) in old_parameters.iter() {
+        let key = parameter_key(o);
+
+        // path parameters are matched with normalized path
+        if key.1 == "path" {
+            continue;
+        }
+
+        if !new_parameters.iter().any(|(_, n)| parameter_key(n) == key) {
+            ctx.add(
+                ChangeKind::ParameterRemoved,
+                Severity::Unknown,
+                old_pointer,
+                format!("{} parameter {} removed", key.1, key.0),
+            );
+        }
+    }
+
+    for (new_pointer, n) in new_parameters.iter() {
+        let key = parameter_key(n);
+        let required = is_true(n.get("required"));
+
+        match old_parameters.iter().find(|(_, o)| parameter_key(o) == key) {
+            Some((_, o)) => {
+                let was_required = is_true(o.get("required"));
+
+                if !was_required && required {
+                    ctx.add(
+                        ChangeKind::ParameterRequired,
+                        Severity::Breaking,
+                        &format!("{new_pointer}/required"),
+                        format!("{} parameter {} became required", key.1, key.0),
+                    );
+                } else if was_required && !required {
+                    ctx.add(
+                        ChangeKind::ParameterOptional,
+                        Severity::NonBreaking,
+                        &format!("{new_pointer}/required"),
+                        format!("{} parameter {} became optional", key.1, key.0),
+                    );
+                }
+
+                if !is_true(o.get("deprecated")) && is_true(n.get("deprecated")) {
+                    ctx.add(
+                        ChangeKind::ParameterDeprecated,
+                        Severity::NonBreaking,
+                        &format!("{new_pointer}/deprecated"),
+                        format!("{} parameter {} deprecated", key.1, key.0),
+                    );
+                }
+
+                compare_documentation(
+                    o.get("description"),
+                    n.get("description"),
+                    &format!("{new_pointer}/description"),
+                    ctx,
+                );
+
+                if let (Some(os), Some(ns)) = (o.get("schema"), n.get("schema")) {
+                    compare_schema(os, ns, &format!("{new_pointer}/schema"), ctx);
+                }
+            }
+            None => {
+                if key.1 == "path" {
+                    continue;
+                }
+
+                ctx.add(
+                    ChangeKind::ParameterAdded,
+                    if required {
+                        Severity::Breaking
+                    } else {
+                        Severity::NonBreaking
+                    },
+                    new_pointer,
+                    format!(
+                        "{} {} parameter {} added",
+                        if required { "required" } else { "optional" },
+                        key.1,
+                        key.0
+                    ),
+                )
+            }
+        }
+    }
+}
+
+fn compare_request_body(
+    old: Option<&Value>,
+    new: Option<&Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    match (old, new) {
+        (Some(o), Some(n)) => {
+            let o = ctx.resolve(Side::Old, o);
+            let n = ctx.resolve(Side::New, n);
+
+            let was_required = is_true(o.get("required"));
+            let required = is_true(n.get("required"));
+
+            if !was_required && required {
+                ctx.add(
+                    ChangeKind::RequestBodyRequired,
+                    Severity::Breaking,
+                    &format!("{pointer}/required"),
+                    "request body became required".to_string(),
+                );
+            } else if was_required && !required {
+                ctx.add(
+                    ChangeKind::RequestBodyOptional,
+                    Severity::NonBreaking,
+                    &format!("{pointer}/required"),
+                    "request body became optional".to_string(),
+                );
+            }
+
+            compare_documentation(
+                o.get("description"),
+                n.get("description"),
+                &format!("{pointer}/description"),
+                ctx,
+            );
+            compare_content(o.get("content"), n.get("content"), pointer, ctx);
+        }
+        (None, Some(n)) => {
+            let n = ctx.resolve(Side::New, n);
+            let required = is_true(n.get("required"));
+
+            ctx.add(
+                ChangeKind::RequestBodyAdded,
+                if required {
+                    Severity::Breaking
+                } else {
+                    Severity::NonBreaking
+                },
+                pointer,
+                format!(
+                    "{} request body added",
+                    if required { "required" } else { "optional" }
+                ),
+            );
+        }
+        (Some(_), None) => ctx.add(
+            ChangeKind::RequestBodyRemoved,
+            Severity::Unknown,
+            pointer,
+            "request body removed".to_string(),
+        ),
+        (None, None) => {}
+    }
+}
+
+fn compare_content(old: Option<&Value>, new: Option<&Value>, pointer: &str, ctx: &mut Context) {
+    let empty = Map::new();
+    let old = old.and_then(|c| c.as_object()).unwrap_or(&empty);
+    let new = new.and_then(|c| c.as_object()).unwrap_or(&empty);
+
+    for (media_type, _) in old.iter() {
+        if !new.contains_key(media_type) {
+            ctx.add(
+                ChangeKind::MediaTypeRemoved,
+                Severity::Breaking,
+                &format!("{pointer}/content/{}", escape(media_type)),
+                format!("{} media type {} removed", ctx.side_name(), media_type),
+            );
+        }
+    }
+
+    for (media_type, n) in new.iter() {
+        let media_pointer = format!("{pointer}/content/{}", escape(media_type));
+
+        match old.get(media_type) {
+            Some(o) => {
+                if let (Some(os), Some(ns)) = (o.get("schema"), n.get("schema")) {
+                    compare_schema(os, ns, &format!("{media_pointer}/schema"), ctx);
+                }
+            }
+            None => ctx.add(
+                ChangeKind::MediaTypeAdded,
+                Severity::NonBreaking,
+                &media_pointer,
+                format!("{} media type {} added", ctx.side_name(), media_type),
+            ),
+        }
+    }
+}
+
+fn compare_responses(old: Option<&Value>, new: Option<&Value>, pointer: &str, ctx: &mut Context) {
+    let empty = Map::new();
+    let old = old.and_then(|c| c.as_object()).unwrap_or(&empty);
+    let new = new.and_then(|c| c.as_object()).unwrap_or(&empty);
+
+    for (status, _) in old.iter() {
+        if !new.contains_key(status) {
+            ctx.add(
+                ChangeKind::ResponseRemoved,
+                if status.starts_with('2') {
+                    Severity::Breaking
+                } else {
+                    Severity::NonBreaking
+                },
+                &format!("{pointer}/{}", escape(status)),
+                format!("response {status} removed"),
+            );
+        }
+    }
+
+    for (status, n) in new.iter() {
+        let response_pointer = format!("{pointer}/{}", escape(status));
+
+        match old.get(status) {
+            Some(o) => {
+                let o = ctx.resolve(Side::Old, o);
+                let n = ctx.resolve(Side::New, n);
+
+                compare_documentation(
+                    o.get("description"),
+                    n.get("description"),
+                    &format!("{response_pointer}/description"),
+                    ctx,
+                );
+                compare_headers(
+                    o.get("headers"),
+                    n.get("headers"),
+                    &format!("{response_pointer}/headers"),
+                    ctx,
+                );
+                compare_content(o.get("content"), n.get("content"), &response_pointer, ctx);
+            }
+            None => ctx.add(
+                ChangeKind::ResponseAdded,
+                Severity::NonBreaking,
+                &response_pointer,
+                format!("response {status} added"),
+            ),
+        }
+    }
+}
+
+fn compare_headers(old: Option<&Value>, new: Option<&Value>, pointer: &str, ctx: &mut Context) {
+    let empty = Map::new();
+    let old = old.and_then(|c| c.as_object()).unwrap_or(&empty);
+    let new = new.and_then(|c| c.as_object()).unwrap_or(&empty);
+
+    for (name, _) in old.iter() {
+        if !new.contains_key(name) {
+            ctx.add(
+                ChangeKind::HeaderRemoved,
+                Severity::Breaking,
+                &format!("{pointer}/{}", escape(name)),
+                format!("response header {name} removed"),
+            );
+        }
+    }
+
+    for (name, n) in new.iter() {
+        let header_pointer = format!("{pointer}/{}", escape(name));
+
+        match old.get(name) {
+            Some(o) => {
+                let o = ctx.resolve(Side::Old, o);
+                let n = ctx.resolve(Side::New, n);
+
+                if let (Some(os), Some(ns)) = (o.get("schema"), n.get("schema")) {
+                    compare_schema(os, ns, &format!("{header_pointer}/schema"), ctx);
+                }
+            }
+            None => ctx.add(
+                ChangeKind::HeaderAdded,
+                Severity::NonBreaking,
+                &header_pointer,
+                format!("response header {name} added"),
+            ),
+        }
+    }
+}
+
+fn security_alternatives(value: Option<&Value>) -> Vec<Vec<String>> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|list| {
+            list.iter()
+                .map(|requirement| {
+                    let mut names = requirement
+                        .as_object()
+                        .map(|o| o.keys().cloned().collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    names.sort();
+                    names
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn compare_security(old: Option<&Value>, new: Option<&Value>, pointer: &str, ctx: &mut Context) {
+    let old = security_alternatives(old);
+    let new = security_alternatives(new);
+
+    if old == new {
+        return;
+    }
+
+    let allows_anonymous =
+        |list: &Vec<Vec<String>>| list.is_empty() || list.iter().any(|a| a.is_empty());
+
+    // every previously accepted way of authentication has to be still accepted
+    let breaking = (allows_anonymous(&old) && !allows_anonymous(&new))
+        || (!allows_anonymous(&new) && old.iter().any(|a| !new.contains(a)));
+
+    ctx.add(
+        ChangeKind::SecurityChanged,
+        if breaking {
+            Severity::Breaking
+        } else {
+            Severity::NonBreaking
+        },
+        pointer,
+        "security requirements changed".to_string(),
+    );
+}
+
+fn types(schema: &Map<String, Value>) -> Option<Vec<String>> {
+    let mut result = match schema.get("type") {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(a)) => a
+            .iter()
+            .filter_map(|s| s.as_str().map(|s| s.to_string()))
+            .collect(),
+        _ => return None,
+    };
+
+    if is_true(schema.get("nullable")) && !result.contains(&"null".to_string()) {
+        result.push("null".to_string());
+    }
+
+    Some(result)
+}
+
+/// Checks if every type from first set is accepted by second set
+fn types_subset(a: &[String], b: &[String]) -> bool {
+    a.iter()
+        .all(|t| b.contains(t) || (t == "integer" && b.contains(&"number".to_string())))
+}
+
+fn compare_schema(old: &Value, new: &Value, pointer: &str, ctx: &mut Context) {
+    let key = (
+        old.get("$ref")
+            .and_then(|r| r.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        new.get("$ref")
+            .and_then(|r| r.as_str())
+            .unwrap_or_default()
+            .to_string(),
+    );
+    let is_reference = !key.0.is_empty() || !key.1.is_empty();
+
+    if is_reference {
+        if ctx.visited.contains(&key) {
+            return;
+        }
+
+        ctx.visited.insert(key.clone());
+    }
+
+    let old = ctx.resolve(Side::Old, old);
+    let new = ctx.resolve(Side::New, new);
+
+    match (&old, &new) {
+        (Value::Object(o), Value::Object(n)) => compare_schema_objects(o, n, pointer, ctx),
+        (o, n) => {
+            if o != n {
+                ctx.add(
+                    ChangeKind::TypeChanged,
+                    Severity::Unknown,
+                    pointer,
+                    "schema changed".to_string(),
+                );
+            }
+        }
+    }
+
+    if is_reference {
+        ctx.visited.remove(&key);
+    }
+}
+
+fn compare_schema_objects(
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    compare_documentation(
+        old.get("description"),
+        new.get("description"),
+        &format!("{pointer}/description"),
+        ctx,
+    );
+
+    if !is_true(old.get("deprecated")) && is_true(new.get("deprecated")) {
+        ctx.add(
+            ChangeKind::PropertyDeprecated,
+            Severity::NonBreaking,
+            &format!("{pointer}/deprecated"),
+            "schema deprecated".to_string(),
+        );
+    }
+
+    // type
+    match (types(old), types(new)) {
+        (Some(o), Some(n)) if types_subset(&o, &n) && types_subset(&n, &o) => {}
+        (Some(o), Some(n)) => {
+            if types_subset(&n, &o) {
+                ctx.add(
+                    ChangeKind::TypeNarrowed,
+                    ctx.narrowing(),
+                    &format!("{pointer}/type"),
+                    format!("type narrowed from {} to {}", o.join("|"), n.join("|")),
+                );
+            } else if types_subset(&o, &n) {
+                ctx.add(
+                    ChangeKind::TypeWidened,
+                    ctx.widening(),
+                    &format!("{pointer}/type"),
+                    format!("type widened from {} to {}", o.join("|"), n.join("|")),
+                );
+            } else {
+                ctx.add(
+                    ChangeKind::TypeChanged,
+                    Severity::Breaking,
+                    &format!("{pointer}/type"),
+                    format!("type changed from {} to {}", o.join("|"), n.join("|")),
+                );
+            }
+        }
+        (None, Some(n)) => ctx.add(
+            ChangeKind::TypeNarrowed,
+            ctx.narrowing(),
+            &format!("{pointer}/type"),
+            format!("type narrowed to {}", n.join("|")),
+        ),
+        (Some(o), None) => ctx.add(
+            ChangeKind::TypeWidened,
+            ctx.widening(),
+            &format!("{pointer}/type"),
+            format!("type widened from {}", o.join("|")),
+        ),
+        (None, None) => {}
+    }
+
+    if old.get("format") != new.get("format") {
+        ctx.add(
+            ChangeKind::FormatChanged,
+            Severity::Unknown,
+            &format!("{pointer}/format"),
+            format!(
+                "format changed from {} to {}",
+                old.get("format").unwrap_or(&Value::Null),
+                new.get("format").unwrap_or(&Value::Null)
+            ),
+        );
+    }
+
+    compare_enum(old, new, pointer, ctx);
+    compare_constraints(old, new, pointer, ctx);
+    compare_properties(old, new, pointer, ctx);
+
+    // additionalProperties
+    match (
+        old.get("additionalProperties"),
+        new.get("additionalProperties"),
+    ) {
+        (Some(Value::Object(o)), Some(Value::Object(n))) => {
+            compare_schema(
+                &Value::Object(o.clone()),
+                &Value::Object(n.clone()),
+                &format!("{pointer}/additionalProperties"),
+                ctx,
+            );
+        }
+        (o, n) => {
+            let closed = |v: Option<&Value>| v == Some(&Value::Bool(false));
+
+            if !closed(o) && closed(n) {
+                ctx.add(
+                    ChangeKind::ConstraintTightened,
+                    ctx.narrowing(),
+                    &format!("{pointer}/additionalProperties"),
+                    "additional properties disallowed".to_string(),
+                );
+            } else if closed(o) && !closed(n) {
+                ctx.add(
+                    ChangeKind::ConstraintRelaxed,
+                    ctx.widening(),
+                    &format!("{pointer}/additionalProperties"),
+                    "additional properties allowed".to_string(),
+                );
+            }
+        }
+    }
+
+    if let (Some(o), Some(n)) = (old.get("items"), new.get("items")) {
+        compare_schema(o, n, &format!("{pointer}/items"), ctx);
+    }
+
+    for form in ["oneOf", "anyOf", "allOf"] {
+        compare_composition(form, old.get(form), new.get(form), pointer, ctx);
+    }
+}
+
+fn compare_enum(
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    let enum_pointer = format!("{pointer}/enum");
+
+    match (old.get("enum"), new.get("enum")) {
+        (Some(Value::Array(o)), Some(Value::Array(n))) => {
+            for value in o.iter() {
+                if !n.contains(value) {
+                    ctx.add(
+                        ChangeKind::EnumValueRemoved,
+                        ctx.narrowing(),
+                        &enum_pointer,
+                        format!("enum value {value} removed"),
+                    );
+                }
+            }
+
+            for value in n.iter() {
+                if !o.contains(value) {
+                    ctx.add(
+                        ChangeKind::EnumValueAdded,
+                        ctx.widening(),
+                        &enum_pointer,
+                        format!("enum value {value} added"),
+                    );
+                }
+            }
+        }
+        (None, Some(_)) => ctx.add(
+            ChangeKind::ConstraintTightened,
+            ctx.narrowing(),
+            &enum_pointer,
+            "enum added".to_string(),
+        ),
+        (Some(_), None) => ctx.add(
+            ChangeKind::ConstraintRelaxed,
+            ctx.widening(),
+            &enum_pointer,
+            "enum removed".to_string(),
+        ),
+        _ => {}
+    }
+}
+
+fn compare_constraints(
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    // lower bounds: increasing value tightens schema
+    for key in ["minimum", "minLength", "minItems", "minProperties"] {
+        compare_bound(key, old, new, true, pointer, ctx);
+    }
+
+    // upper bounds: decreasing value tightens schema
+    for key in ["maximum", "maxLength", "maxItems", "maxProperties"] {
+        compare_bound(key, old, new, false, pointer, ctx);
+    }
+
+    for key in [
+        "pattern",
+        "multipleOf",
+        "exclusiveMinimum",
+        "exclusiveMaximum",
+    ] {
+        let constraint_pointer = format!("{pointer}/{key}");
+
+        match (old.get(key), new.get(key)) {
+            (None, Some(_)) => ctx.add(
+                ChangeKind::ConstraintTightened,
+                ctx.narrowing(),
+                &constraint_pointer,
+                format!("{key} added"),
+            ),
+            (Some(_), None) => ctx.add(
+                ChangeKind::ConstraintRelaxed,
+                ctx.widening(),
+                &constraint_pointer,
+                format!("{key} removed"),
+            ),
+            (Some(o), Some(n)) if o != n => ctx.add(
+                ChangeKind::ConstraintChanged,
+                Severity::Unknown,
+                &constraint_pointer,
+                format!("{key} changed from {o} to {n}"),
+            ),
+            _ => {}
+        }
+    }
+
+    if !is_true(old.get("uniqueItems")) && is_true(new.get("uniqueItems")) {
+        ctx.add(
+            ChangeKind::ConstraintTightened,
+            ctx.narrowing(),
+            &format!("{pointer}/uniqueItems"),
+            "unique items required".to_string(),
+        );
+    } else if is_true(old.get("uniqueItems")) && !is_true(new.get("uniqueItems")) {
+        ctx.add(
+            ChangeKind::ConstraintRelaxed,
+            ctx.widening(),
+            &format!("{pointer}/uniqueItems"),
+            "unique items not required".to_string(),
+        );
+    }
+}
+
+fn compare_bound(
+    key: &str,
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    lower: bool,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    let o = old.get(key).and_then(|v| v.as_f64());
+    let n = new.get(key).and_then(|v| v.as_f64());
+
+    let tightened = match (o, n) {
+        (None, Some(_)) => Some(true),
+        (Some(_), None) => Some(false),
+        (Some(o), Some(n)) if o != n => Some((n > o) == lower),
+        _ => None,
+    };
+
+    let constraint_pointer = format!("{pointer}/{key}");
+    let describe = |v: Option<f64>| v.map(|v| v.to_string()).unwrap_or("none".to_string());
+
+    match tightened {
+        Some(true) => ctx.add(
+            ChangeKind::ConstraintTightened,
+            ctx.narrowing(),
+            &constraint_pointer,
+            format!("{key} tightened from {} to {}", describe(o), describe(n)),
+        ),
+        Some(false) => ctx.add(
+            ChangeKind::ConstraintRelaxed,
+            ctx.widening(),
+            &constraint_pointer,
+            format!("{key} relaxed from {} to {}", describe(o), describe(n)),
+        ),
+        None => {}
+    }
+}
+
+fn required(schema: &Map<String, Value>) -> Vec<String> {
+    schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| {
+            r.iter()
+                .filter_map(|s| s.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn compare_properties(
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    let empty = Map::new();
+    let old_properties = old
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .unwrap_or(&empty);
+    let new_properties = new
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .unwrap_or(&empty);
+    let old_required = required(old);
+    let new_required = required(new);
+
+    for (name, _) in old_properties.iter() {
+        if !new_properties.contains_key(name) {
+            let severity = match ctx.direction {
+                Direction::Request
+                    if new.get("additionalProperties") == Some(&Value::Bool(false)) =>
+                {
+                    Severity::Breaking
+                }
+                Direction::Request => Severity::NonBreaking,
+                Direction::Response => Severity::Breaking,
+            };
+
+            ctx.add(
+                ChangeKind::PropertyRemoved,
+                severity,
+                &format!("{pointer}/properties/{}", escape(name)),
+                format!("{} property {name} removed", ctx.side_name()),
+            );
+        }
+    }
+
+    for (name, n) in new_properties.iter() {
+        let property_pointer = format!("{pointer}/properties/{}", escape(name));
+
+        match old_properties.get(name) {
+            Some(o) => {
+                let was_required = old_required.contains(name);
+                let is_required = new_required.contains(name);
+
+                if !was_required && is_required {
+                    ctx.add(
+                        ChangeKind::PropertyRequired,
+                        ctx.narrowing(),
+                        &format!("{pointer}/required"),
+                        format!("{} property {name} became required", ctx.side_name()),
+                    );
+                } else if was_required && !is_required {
+                    ctx.add(
+                        ChangeKind::PropertyOptional,
+                        ctx.widening(),
+                        &format!("{pointer}/required"),
+                        format!("{} property {name} became optional", ctx.side_name()),
+                    );
+                }
+
+                compare_schema(o, n, &property_pointer, ctx);
+            }
+            None => {
+                let is_required = new_required.contains(name);
+
+                ctx.add(
+                    ChangeKind::PropertyAdded,
+                    if is_required {
+                        ctx.narrowing()
+                    } else {
+                        Severity::NonBreaking
+                    },
+                    &property_pointer,
+                    format!(
+                        "{} {} property {name} added",
+                        if is_required { "required" } else { "optional" },
+                        ctx.side_name()
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Pairs indexes of old and new branches, by `$ref` target first, then by equal schemas, equal
+/// types and remaining ones by their order, so inserted or reordered branches are not compared
+/// with unrelated ones
+fn pair_branches(old: &[Value], new: &[Value]) -> Vec<(usize, usize)> {
+    fn same(key: &str, a: &Value, b: &Value) -> bool {
+        a.get(key).is_some() && a.get(key) == b.get(key)
+    }
+
+    let same_ref: fn(&Value, &Value) -> bool = |a, b| same("$ref", a, b);
+    let same_schema: fn(&Value, &Value) -> bool = |a, b| a == b;
+    let same_type: fn(&Value, &Value) -> bool = |a, b| same("type", a, b);
+    let any: fn(&Value, &Value) -> bool = |_, _| true;
+
+    let mut old_paired = vec![false; old.len()];
+    let mut new_paired = vec![false; new.len()];
+    let mut pairs = vec![];
+
+    for matches in [same_ref, same_schema, same_type, any] {
+        for (j, branch) in new.iter().enumerate() {
+            if new_paired[j] {
+                continue;
+            }
+
+            if let Some(i) = (0..old.len()).find(|i| !old_paired[*i] && matches(&old[*i], branch)) {
+                old_paired[i] = true;
+                new_paired[j] = true;
+                pairs.push((i, j));
+            }
+        }
+    }
+
+    pairs.sort_by_key(|(_, j)| *j);
+    pairs
+}
+
+fn compare_composition(
+    form: &str,
+    old: Option<&Value>,
+    new: Option<&Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    let empty = vec![];
+    let o = old.and_then(|v| v.as_array()).unwrap_or(&empty);
+    let n = new.and_then(|v| v.as_array()).unwrap_or(&empty);
+
+    if o.is_empty() && n.is_empty() {
+        return;
+    }
+
+    for (i, j) in pair_branches(o, n) {
+        compare_schema(&o[i], &n[j], &format!("{pointer}/{form}/{j}"), ctx);
+    }
+
+    // more options widen oneOf/anyOf, more members of allOf narrow schema
+    let widening = form != "allOf";
+
+    if n.len() > o.len() {
+        ctx.add(
+            if widening {
+                ChangeKind::TypeWidened
+            } else {
+                ChangeKind::TypeNarrowed
+            },
+            if widening {
+                ctx.widening()
+            } else {
+                ctx.narrowing()
+            },
+            &format!("{pointer}/{form}"),
+            format!("{} {form} schemas added", n.len() - o.len()),
+        );
+    } else if n.len() < o.len() {
+        ctx.add(
+            if widening {
+                ChangeKind::TypeNarrowed
+            } else {
+                ChangeKind::TypeWidened
+            },
+            if widening {
+                ctx.narrowing()
+            } else {
+                ctx.widening()
+            },
+            &format!("{pointer}/{form}"),
+            format!("{} {form} schemas removed", o.len() - n.len()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn compare(old: Value, new: Value) -> Report {
+        let schema = Schema::from_json(new);
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        Differ::options(Schema::from_json(old))
+            .compare(&schema, &storage, &client)
+            .unwrap()
+    }
+
+    fn kinds(report: &Report) -> Vec<(ChangeKind, Severity)> {
+        report
+            .changes
+            .iter()
+            .map(|c| (c.kind, c.severity))
+            .collect()
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let spec = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": { "description": "ok" }
+                        }
+                    }
+                }
+            }
+        });
+
+        assert!(compare(spec.clone(), spec).is_empty());
+    }
+
+    #[test]
+    fn test_endpoints() {
+        let old = json!({
+            "paths": {
+                "/users/{id}": { "get": {}, "delete": {} }
+            }
+        });
+
+        let new = json!({
+            "paths": {
+                "/users/{userId}": { "get": {}, "patch": {} }
+            }
+        });
+
+        let report = compare(old, new);
+
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (ChangeKind::EndpointRemoved, Severity::Breaking),
+                (ChangeKind::EndpointAdded, Severity::NonBreaking)
+            ]
+        );
+        assert_eq!(report.changes[0].pointer, "/paths/~1users~1{id}/delete");
+        assert_eq!(
+            report.changes[1].operation.as_ref().unwrap().to_string(),
+            "PATCH /users/{userId}"
+        );
+    }
+
+    #[test]
+    fn test_parameters() {
+        let old = json!({
+            "paths": {
+                "/users": {
+                    "parameters": [
+                        { "name": "page", "in": "query", "schema": { "type": "integer" } }
+                    ],
+                    "get": {
+                        "parameters": [
+                            { "name": "limit", "in": "query", "schema": { "type": "integer" } }
+                        ]
+                    }
+                }
+            }
+        });
+
+        let new = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "parameters": [
+                            { "$ref": "#/components/parameters/Page" },
+                            { "name": "limit", "in": "query", "required": true, "schema": { "type": "integer" } },
+                            { "name": "X-Tenant", "in": "header", "required": true, "schema": { "type": "string" } }
+                        ]
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "Page": { "name": "page", "in": "query", "schema": { "type": "number" } }
+                }
+            }
+        });
+
+        let report = compare(old, new);
+
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (ChangeKind::TypeWidened, Severity::NonBreaking),
+                (ChangeKind::ParameterRequired, Severity::Breaking),
+                (ChangeKind::ParameterAdded, Severity::Breaking)
+            ]
+        );
+        assert_eq!(
+            report.changes[1].pointer,
+            "/paths/~1users/get/parameters/1/required"
+        );
+    }
+
+    #[test]
+    fn test_request_body() {
+        let old = json!({
+            "paths": {
+                "/users": {
+                    "post": {
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/User" }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "name": { "type": "string" },
+                            "role": { "type": "string", "enum": ["admin", "user", "guest"] },
+                            "age": { "type": "number" }
+                        }
+                    }
+                }
+            }
+        });
+
+        let new = json!({
+            "paths": {
+                "/users": {
+                    "post": {
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/User" }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["name", "role"],
+                        "properties": {
+                            "name": { "type": "string", "maxLength": 20 },
+                            "role": { "type": "string", "enum": ["admin", "user"] },
+                            "age": { "type": "integer" }
+                        }
+                    }
+                }
+            }
+        });
+
+        let report = compare(old, new);
+
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (ChangeKind::ConstraintTightened, Severity::Breaking),
+                (ChangeKind::PropertyRequired, Severity::Breaking),
+                (ChangeKind::EnumValueRemoved, Severity::Breaking),
+                (ChangeKind::TypeNarrowed, Severity::Breaking)
+            ]
+        );
+        assert_eq!(
+            report.changes[2].pointer,
+            "/paths/~1users/post/requestBody/content/application~1json/schema/properties/role/enum"
+        );
+    }
+
+    #[test]
+    fn test_responses() {
+        let old = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "properties": {
+                                                "id": { "type": "string" },
+                                                "status": { "type": "string", "enum": ["active"] }
+                                            }
+                                        }
+                                    }
+                                }
+                            },
+                            "404": { "description": "not found" }
+                        }
+                    }
+                }
+            }
+        });
+
+        let new = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "list of users",
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "properties": {
+                                                "status": { "type": "string", "enum": ["active", "blocked"] },
+                                                "name": { "type": "string" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let report = compare(old, new);
+
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (ChangeKind::ResponseRemoved, Severity::NonBreaking),
+                (ChangeKind::DescriptionChanged, Severity::NonBreaking),
+                (ChangeKind::PropertyRemoved, Severity::Breaking),
+                (ChangeKind::EnumValueAdded, Severity::Breaking),
+                (ChangeKind::PropertyAdded, Severity::NonBreaking)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recursive_reference() {
+        let spec = json!({
+            "paths": {
+                "/nodes": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Node" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Node": {
+                        "type": "object",
+                        "properties": {
+                            "children": {
+                                "type": "array",
+                                "items": { "$ref": "#/components/schemas/Node" }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        assert!(compare(spec.clone(), spec).is_empty());
+    }
+
+    #[test]
+    fn test_process_replaces_body_with_report() {
+        let old = json!({ "paths": { "/users": { "get": {} } } });
+        let mut schema = Schema::from_json(json!({ "paths": {} }));
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        Differ::options(Schema::from_json(old))
+            .process(&mut schema, &storage, &client)
+            .unwrap();
+
+        let report: Report = serde_json::from_value(schema.get_body().clone()).unwrap();
+
+        assert!(report.is_breaking());
+        assert_eq!(
+            schema.get_body()["changes"][0]["kind"],
+            json!("endpoint-removed")
+        );
+    }
+
+    #[test]
+    fn test_composition_branches_paired() {
+        let spec = |branches: Value| {
+            json!({
+                "paths": {
+                    "/pets": {
+                        "get": {
+                            "responses": {
+                                "200": {
+                                    "content": {
+                                        "application/json": {
+                                            "schema": { "oneOf": branches }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            })
+        };
+
+        let old = spec(json!([
+            { "$ref": "#/components/schemas/Cat" },
+            { "type": "object", "properties": { "id": { "type": "integer" } } },
+            { "type": "string" }
+        ]));
+        let new = spec(json!([
+            { "type": "boolean" },
+            { "type": "string" },
+            { "type": "object", "properties": { "id": { "type": "string" } } },
+            { "$ref": "#/components/schemas/Cat" }
+        ]));
+
+        let report = compare(old, new);
+
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (ChangeKind::TypeChanged, Severity::Breaking),
+                (ChangeKind::TypeWidened, Severity::Breaking)
+            ]
+        );
+        assert!(report.changes[0]
+            .pointer
+            .ends_with("/oneOf/2/properties/id/type"));
+    }
+}
@@ -0,0 +1,535 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use super::upgrade_openapi::{schema_paths, schema_pointer};
+use crate::{error::Error, schema::Schema, scope::SchemaScope, tools};
+
+pub const VERSION: &str = "3.0.3";
+
+/// Keywords of json schema 2020-12 which have no equivalent in openapi 3.0
+const LOSSY_KEYWORDS: [&str; 20] = [
+    "if",
+    "then",
+    "else",
+    "prefixItems",
+    "$dynamicRef",
+    "$dynamicAnchor",
+    "$anchor",
+    "$defs",
+    "$id",
+    "$schema",
+    "$comment",
+    "dependentSchemas",
+    "dependentRequired",
+    "unevaluatedProperties",
+    "unevaluatedItems",
+    "contains",
+    "minContains",
+    "maxContains",
+    "propertyNames",
+    "patternProperties",
+];
+
+pub struct Downgrader;
+
+pub struct DowngraderOptions {
+    pub version: String,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct LossyConstruct {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for LossyConstruct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+impl DowngraderOptions {
+    pub fn with_version(&mut self, value: String) -> &mut Self {
+        self.version = value;
+        self
+    }
+
+    /// Converts document to openapi 3.0, returns constructs which were dropped
+    pub fn process(&self, schema: &mut Schema) -> Result<Vec<LossyConstruct>, Error> {
+        let root = schema
+            .get_body_mut()
+            .as_object_mut()
+            .ok_or(Error::InvalidOpenapiSchemaError)?;
+
+        match root.get("openapi").and_then(|v| v.as_str()) {
+            Some(version) if version.starts_with("3.1") => {}
+            Some(version) if version.starts_with("3.0") => {
+                log::warn!("openapi {} is already 3.0, skipping", version);
+                return Ok(vec![]);
+            }
+            _ => return Err(Error::InvalidOpenapiSchemaError),
+        }
+
+        let mut lossy = vec![];
+
+        root.insert("openapi".to_string(), Value::String(self.version.clone()));
+        root.shift_remove("jsonSchemaDialect");
+
+        remove_unsupported(schema.get_body_mut(), &mut lossy);
+
+        let body = schema.get_body_mut();
+        if let Value::Object(root) = &mut *body {
+            root.entry("paths").or_insert_with(|| json!({}));
+        }
+
+        let mut scope = SchemaScope::default();
+
+        for (path, _) in schema_paths() {
+            tools::each_node_mut(body, &mut scope, &path, |node, parts, scope| {
+                log::trace!("{}: downgrading", scope);
+
+                downgrade_schema(node, &schema_pointer(&path, parts), &mut lossy);
+
+                Ok(())
+            })?;
+        }
+
+        for construct in lossy.iter() {
+            log::warn!("{}", construct);
+        }
+
+        Ok(lossy)
+    }
+}
+
+impl Downgrader {
+    pub fn options() -> DowngraderOptions {
+        DowngraderOptions {
+            version: VERSION.to_string(),
+        }
+    }
+}
+
+/// Drops document level features of 3.1
+fn remove_unsupported(root: &mut Value, lossy: &mut Vec<LossyConstruct>) {
+    for (parent, key, message) in [
+        ("", "webhooks", "webhooks are not supported"),
+        (
+            "/components",
+            "pathItems",
+            "path items components are not supported",
+        ),
+        ("/info", "summary", "info summary is not supported"),
+        (
+            "/info/license",
+            "identifier",
+            "license identifier is not supported",
+        ),
+    ] {
+        let removed = root
+            .pointer_mut(parent)
+            .and_then(|p| p.as_object_mut())
+            .and_then(|p| p.shift_remove(key));
+
+        if removed.is_some() {
+            lossy.push(LossyConstruct {
+                pointer: format!("{parent}/{key}"),
+                message: message.to_string(),
+            });
+        }
+    }
+}
+
+pub fn downgrade_schema(node: &mut Value, pointer: &str, lossy: &mut Vec<LossyConstruct>) {
+    let map = match node {
+        Value::Object(map) => map,
+        Value::Bool(value) => {
+            // boolean schemas are not allowed in 3.0
+            *node = if *value {
+                json!({})
+            } else {
+                json!({"not": {}})
+            };
+            return;
+        }
+        _ => return,
+    };
+
+    if let Some(Value::Object(children)) = map.get_mut("properties") {
+        for (name, child) in children.iter_mut() {
+            downgrade_schema(
+                child,
+                &format!("{pointer}/properties/{}", super::diff::escape(name)),
+                lossy,
+            );
+        }
+    }
+
+    for key in ["items", "additionalProperties", "not"] {
+        if let Some(child) = map.get_mut(key) {
+            if !(key == "additionalProperties" && child.is_boolean()) {
+                downgrade_schema(child, &format!("{pointer}/{key}"), lossy);
+            }
+        }
+    }
+
+    for key in ["allOf", "oneOf", "anyOf"] {
+        if let Some(Value::Array(children)) = map.get_mut(key) {
+            for (i, child) in children.iter_mut().enumerate() {
+                downgrade_schema(child, &format!("{pointer}/{key}/{i}"), lossy);
+            }
+        }
+    }
+
+    for keyword in LOSSY_KEYWORDS {
+        if map.shift_remove(keyword).is_some() {
+            lossy.push(LossyConstruct {
+                pointer: format!("{pointer}/{keyword}"),
+                message: format!("{keyword} cannot be expressed in openapi 3.0"),
+            });
+        }
+    }
+
+    downgrade_type(map);
+    downgrade_null_branch(map);
+    downgrade_exclusive(map, "exclusiveMinimum", "minimum", |a, b| a >= b);
+    downgrade_exclusive(map, "exclusiveMaximum", "maximum", |a, b| a <= b);
+    downgrade_const(map);
+    downgrade_examples(map, pointer, lossy);
+    downgrade_content(map);
+    downgrade_reference(map);
+}
+
+fn downgrade_type(map: &mut Map<String, Value>) {
+    let types = match map.get("type") {
+        Some(Value::Array(types)) => types.clone(),
+        _ => return,
+    };
+
+    let nullable = types.contains(&json!("null"));
+    let mut types = types
+        .into_iter()
+        .filter(|t| t != &json!("null"))
+        .collect::<Vec<_>>();
+
+    match types.len() {
+        0 => {
+            map.shift_remove("type");
+        }
+        1 => {
+            map.insert("type".to_string(), types.remove(0));
+        }
+        _ => {
+            map.shift_remove("type");
+
+            // nullable has no effect without type, so each branch has to allow null
+            let branches = Value::Array(
+                types
+                    .into_iter()
+                    .map(|t| {
+                        if nullable {
+                            json!({ "type": t, "nullable": true })
+                        } else {
+                            json!({ "type": t })
+                        }
+                    })
+                    .collect(),
+            );
+
+            // existing anyOf has to hold as well, so types are added as another allOf member
+            if map.contains_key("anyOf") {
+                match map.get_mut("allOf") {
+                    Some(Value::Array(all_of)) => all_of.push(json!({ "anyOf": branches })),
+                    _ => {
+                        map.insert("allOf".to_string(), json!([{ "anyOf": branches }]));
+                    }
+                }
+            } else {
+                map.insert("anyOf".to_string(), branches);
+            }
+
+            return;
+        }
+    }
+
+    if nullable {
+        map.insert("nullable".to_string(), Value::Bool(true));
+    }
+}
+
+/// anyOf/oneOf with {"type": "null"} branch becomes nullable schema
+fn downgrade_null_branch(map: &mut Map<String, Value>) {
+    for key in ["anyOf", "oneOf"] {
+        let branches = match map.get(key) {
+            Some(Value::Array(branches)) if branches.contains(&json!({"type": "null"})) => branches
+                .iter()
+                .filter(|b| *b != &json!({"type": "null"}))
+                .cloned()
+                .collect::<Vec<_>>(),
+            _ => continue,
+        };
+
+        map.insert("nullable".to_string(), Value::Bool(true));
+
+        match &branches[..] {
+            [Value::Object(single)] if single.contains_key("$ref") => {
+                map.shift_remove(key);
+                map.insert("allOf".to_string(), json!([single]));
+            }
+            [Value::Object(single)] => {
+                map.shift_remove(key);
+                for (k, v) in single {
+                    map.entry(k.clone()).or_insert_with(|| v.clone());
+                }
+            }
+            _ => {
+                map.insert(key.to_string(), Value::Array(branches));
+            }
+        }
+    }
+}
+
+fn downgrade_exclusive<F>(map: &mut Map<String, Value>, exclusive: &str, bound: &str, stricter: F)
+where
+    F: Fn(f64, f64) -> bool,
+{
+    let value = match map.get(exclusive) {
+        Some(Value::Number(value)) => Value::Number(value.clone()),
+        _ => return,
+    };
+
+    let current = map.get(bound).and_then(|b| b.as_f64());
+
+    match (current, value.as_f64()) {
+        // existing inclusive bound is already stricter
+        (Some(current), Some(new)) if current != new && stricter(current, new) => {
+            map.shift_remove(exclusive);
+        }
+        _ => {
+            map.insert(bound.to_string(), value);
+            map.insert(exclusive.to_string(), Value::Bool(true));
+        }
+    }
+}
+
+fn downgrade_const(map: &mut Map<String, Value>) {
+    if let Some(value) = map.shift_remove("const") {
+        map.entry("enum").or_insert_with(|| json!([value]));
+    }
+}
+
+fn downgrade_examples(
+    map: &mut Map<String, Value>,
+    pointer: &str,
+    lossy: &mut Vec<LossyConstruct>,
+) {
+    if let Some(Value::Array(mut examples)) = map.shift_remove("examples") {
+        if examples.len() > 1 {
+            lossy.push(LossyConstruct {
+                pointer: format!("{pointer}/examples"),
+                message: format!("only first of {} examples is kept", examples.len()),
+            });
+        }
+
+        if !examples.is_empty() {
+            map.entry("example").or_insert_with(|| examples.remove(0));
+        }
+    }
+}
+
+fn downgrade_content(map: &mut Map<String, Value>) {
+    if map.shift_remove("contentMediaType").is_some() {
+        map.insert("format".to_string(), json!("binary"));
+    } else if map.get("contentEncoding") == Some(&json!("base64")) {
+        map.insert("format".to_string(), json!("byte"));
+    }
+
+    map.shift_remove("contentEncoding");
+}
+
+/// Siblings of $ref are ignored in 3.0, reference is moved to allOf to keep them
+fn downgrade_reference(map: &mut Map<String, Value>) {
+    let annotations = ["description", "summary"];
+
+    if map.contains_key("$ref")
+        && map
+            .keys()
+            .any(|k| k != "$ref" && !annotations.contains(&k.as_str()))
+    {
+        if let Some(reference) = map.shift_remove("$ref") {
+            let mut all_of = vec![json!({ "$ref": reference })];
+
+            if let Some(Value::Array(existing)) = map.shift_remove("allOf") {
+                all_of.extend(existing);
+            }
+
+            map.insert("allOf".to_string(), Value::Array(all_of));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(
+        json!({"type": ["string", "null"]}),
+        json!({"type": "string", "nullable": true})
+    )]
+    #[test_case(
+        json!({"type": ["string", "integer"]}),
+        json!({"anyOf": [{"type": "string"}, {"type": "integer"}]})
+    )]
+    #[test_case(
+        json!({"type": ["string", "integer", "null"]}),
+        json!({"anyOf": [{"type": "string", "nullable": true}, {"type": "integer", "nullable": true}]})
+    )]
+    #[test_case(
+        json!({"type": ["string", "integer"], "anyOf": [{"minLength": 2}, {"minimum": 10}]}),
+        json!({"anyOf": [{"minLength": 2}, {"minimum": 10}], "allOf": [{"anyOf": [{"type": "string"}, {"type": "integer"}]}]})
+    )]
+    #[test_case(
+        json!({"description": "Owner", "anyOf": [{"$ref": "#/components/schemas/User"}, {"type": "null"}]}),
+        json!({"description": "Owner", "nullable": true, "allOf": [{"$ref": "#/components/schemas/User"}]})
+    )]
+    #[test_case(
+        json!({"type": "integer", "exclusiveMinimum": 0, "maximum": 10, "exclusiveMaximum": 20}),
+        json!({"type": "integer", "exclusiveMinimum": true, "maximum": 10, "minimum": 0})
+    )]
+    #[test_case(
+        json!({"const": "admin"}),
+        json!({"enum": ["admin"]})
+    )]
+    #[test_case(
+        json!({"type": "string", "contentMediaType": "image/png"}),
+        json!({"type": "string", "format": "binary"})
+    )]
+    #[test_case(
+        json!({"$ref": "#/components/schemas/User", "readOnly": true}),
+        json!({"readOnly": true, "allOf": [{"$ref": "#/components/schemas/User"}]})
+    )]
+    fn test_downgrade_schema(mut given: Value, expected: Value) {
+        let mut lossy = vec![];
+        downgrade_schema(&mut given, "", &mut lossy);
+
+        assert_eq!(given.to_string(), expected.to_string());
+        assert!(lossy.is_empty());
+    }
+
+    #[test]
+    fn test_downgrade_openapi() {
+        let value = json!({
+            "openapi": "3.1.0",
+            "jsonSchemaDialect": "https://spec.openapis.org/oas/3.1/dialect/base",
+            "info": { "title": "Test", "summary": "Test api", "version": "1.0.0" },
+            "webhooks": {},
+            "components": {
+                "schemas": {
+                    "Shape": {
+                        "type": "object",
+                        "properties": {
+                            "kind": { "type": "string", "examples": ["circle", "square"] },
+                            "point": { "type": "array", "prefixItems": [{ "type": "number" }] }
+                        },
+                        "if": { "properties": { "kind": { "const": "circle" } } },
+                        "then": { "required": ["radius"] }
+                    }
+                }
+            }
+        });
+
+        let expected = json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Test", "version": "1.0.0" },
+            "components": {
+                "schemas": {
+                    "Shape": {
+                        "type": "object",
+                        "properties": {
+                            "kind": { "type": "string", "example": "circle" },
+                            "point": { "type": "array" }
+                        }
+                    }
+                }
+            },
+            "paths": {}
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        let lossy = Downgrader::options().process(&mut schema).unwrap();
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+        assert_eq!(
+            lossy.iter().map(|l| l.pointer.as_str()).collect::<Vec<_>>(),
+            vec![
+                "/webhooks",
+                "/info/summary",
+                "/components/schemas/Shape/properties/kind/examples",
+                "/components/schemas/Shape/properties/point/prefixItems",
+                "/components/schemas/Shape/if",
+                "/components/schemas/Shape/then",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_downgrade_callbacks() {
+        let callback = json!({
+            "{$request.body#/url}": {
+                "post": {
+                    "responses": {
+                        "200": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "type": ["string", "null"], "exclusiveMaximum": 10 }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let mut schema = Schema::from_json(json!({
+            "openapi": "3.1.0",
+            "paths": {
+                "/subscriptions": {
+                    "post": {
+                        "callbacks": { "onEvent": callback.clone() },
+                        "responses": { "201": { "description": "Created" } }
+                    }
+                }
+            },
+            "components": {
+                "callbacks": { "onEvent": callback }
+            }
+        }));
+
+        let lossy = Downgrader::options().process(&mut schema).unwrap();
+
+        let downgraded = json!({
+            "type": "string",
+            "nullable": true,
+            "maximum": 10,
+            "exclusiveMaximum": true
+        });
+        let pointer = "/{$request.body#~1url}/post/responses/200/content/application~1json/schema";
+        let body = schema.get_body();
+
+        assert!(lossy.is_empty());
+        assert_eq!(
+            body.pointer(&format!(
+                "/paths/~1subscriptions/post/callbacks/onEvent{pointer}"
+            )),
+            Some(&downgraded)
+        );
+        assert_eq!(
+            body.pointer(&format!("/components/callbacks/onEvent{pointer}")),
+            Some(&downgraded)
+        );
+    }
+}
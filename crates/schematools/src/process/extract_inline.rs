@@ -0,0 +1,537 @@
+use serde_json::{json, Map, Value};
+
+use super::diff::{escape, METHODS};
+use super::name::endpoint;
+use crate::error::Error;
+use crate::schema::Schema;
+use crate::scope::{SchemaNamingStrategy, SchemaScope};
+use crate::tools;
+
+pub struct Extractor;
+
+pub struct ExtractorOptions {
+    pub naming_strategy: SchemaNamingStrategy,
+}
+
+impl Extractor {
+    pub fn options() -> ExtractorOptions {
+        ExtractorOptions {
+            naming_strategy: SchemaNamingStrategy::Default,
+        }
+    }
+}
+
+impl ExtractorOptions {
+    pub fn with_naming_strategy(&mut self, value: SchemaNamingStrategy) -> &mut Self {
+        self.naming_strategy = value;
+        self
+    }
+
+    /// Moves inline object, enum and oneOf/anyOf schemas to `components/schemas`,
+    /// names are the same as models generated by codegen, returns names of new components
+    pub fn process(&self, schema: &mut Schema) -> Result<Vec<String>, Error> {
+        let root = schema.get_body_mut();
+
+        let mut extraction = Extraction {
+            existing: root
+                .pointer("/components/schemas")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default(),
+            extracted: Map::new(),
+        };
+
+        let mut scope = SchemaScope::new(self.naming_strategy.clone());
+
+        tools::each_node_mut(
+            root,
+            &mut scope,
+            "/any:components/any:schemas/definition:*",
+            |node, parts, ctx| {
+                if let [key] = parts {
+                    ctx.glue(key);
+                    extraction.visit(node, ctx, false)?;
+                    ctx.pop();
+                }
+
+                Ok(())
+            },
+        )?;
+
+        tools::each_node_mut(
+            root,
+            &mut scope,
+            "/any:components/any:parameters/definition:*/any:schema",
+            |node, parts, ctx| {
+                if let [key] = parts {
+                    ctx.glue(key).glue("parameter");
+                    extraction.visit(node, ctx, true)?;
+                    ctx.reduce(2);
+                }
+
+                Ok(())
+            },
+        )?;
+
+        tools::each_node_mut(
+            root,
+            &mut scope,
+            "/any:components/any:responses/definition:*/any:content/any:*/any:schema",
+            |node, parts, ctx| {
+                if let [key, _] = parts {
+                    ctx.glue(key).glue("response");
+                    extraction.visit(node, ctx, true)?;
+                    ctx.reduce(2);
+                }
+
+                Ok(())
+            },
+        )?;
+
+        tools::each_node_mut(
+            root,
+            &mut scope,
+            "/any:components/any:requestBodies/definition:*/any:content/any:*/any:schema",
+            |node, parts, ctx| {
+                if let [key, _] = parts {
+                    ctx.glue(key).glue("request");
+                    extraction.visit(node, ctx, true)?;
+                    ctx.reduce(2);
+                }
+
+                Ok(())
+            },
+        )?;
+
+        tools::each_node_mut(root, &mut scope, "/path:paths/any:*", |node, parts, ctx| {
+            if let [path] = parts {
+                extraction.visit_path(node, path, ctx)?;
+            }
+
+            Ok(())
+        })?;
+
+        let names = extraction.extracted.keys().cloned().collect::<Vec<_>>();
+
+        if !extraction.extracted.is_empty() {
+            if !root["components"].is_object() {
+                root["components"] = json!({});
+            }
+            if !root["components"]["schemas"].is_object() {
+                root["components"]["schemas"] = json!({});
+            }
+
+            if let Some(Value::Object(schemas)) = root.pointer_mut("/components/schemas") {
+                schemas.extend(extraction.extracted);
+            }
+        }
+
+        Ok(names)
+    }
+}
+
+struct Extraction {
+    existing: Map<String, Value>,
+    extracted: Map<String, Value>,
+}
+
+impl Extraction {
+    fn visit_path(
+        &mut self,
+        node: &mut Value,
+        path: &str,
+        ctx: &mut SchemaScope,
+    ) -> Result<(), Error> {
+        let Value::Object(item) = node else {
+            return Ok(());
+        };
+
+        if let Some(Value::Array(parameters)) = item.get_mut("parameters") {
+            self.visit_parameters(parameters, ctx)?;
+        }
+
+        for (method, operation) in item.iter_mut() {
+            if !METHODS.contains(&method.as_str()) {
+                continue;
+            }
+
+            let operation_id = match operation.get("operationId").and_then(Value::as_str) {
+                Some(id) => id.to_string(),
+                None => endpoint::Endpoint::new(method.to_string(), path.to_string())?
+                    .get_operation_id(true),
+            };
+
+            ctx.operation(&operation_id, method, path);
+
+            if let Some(Value::Array(parameters)) = operation.get_mut("parameters") {
+                self.visit_parameters(parameters, ctx)?;
+            }
+
+            if let Some(Value::Object(content)) = operation.pointer_mut("/requestBody/content") {
+                ctx.request();
+
+                for schema in content.values_mut().filter_map(|m| m.get_mut("schema")) {
+                    self.visit(schema, ctx, true)?;
+                }
+
+                ctx.pop();
+            }
+
+            if let Some(Value::Object(responses)) = operation.get_mut("responses") {
+                for (code, response) in responses.iter_mut() {
+                    let Some(Value::Object(content)) = response.get_mut("content") else {
+                        continue;
+                    };
+
+                    ctx.response(code);
+
+                    for schema in content.values_mut().filter_map(|m| m.get_mut("schema")) {
+                        self.visit(schema, ctx, true)?;
+                    }
+
+                    ctx.pop();
+                }
+            }
+
+            ctx.pop();
+        }
+
+        Ok(())
+    }
+
+    fn visit_parameters(
+        &mut self,
+        parameters: &mut [Value],
+        ctx: &mut SchemaScope,
+    ) -> Result<(), Error> {
+        for parameter in parameters.iter_mut() {
+            let (Some(name), Some(kind)) = (
+                parameter
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(String::from),
+                parameter
+                    .get("in")
+                    .and_then(Value::as_str)
+                    .map(String::from),
+            ) else {
+                continue;
+            };
+
+            if let Some(schema) = parameter.get_mut("schema") {
+                ctx.glue(&name).glue(&kind);
+                self.visit(schema, ctx, true)?;
+                ctx.reduce(2);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Extracts nested schemas first, so parent is moved with references to its children
+    fn visit(
+        &mut self,
+        node: &mut Value,
+        ctx: &mut SchemaScope,
+        extract: bool,
+    ) -> Result<(), Error> {
+        let Value::Object(map) = node else {
+            return Ok(());
+        };
+
+        if map.contains_key("$ref") {
+            return Ok(());
+        }
+
+        let title = match map.get("title") {
+            Some(Value::String(title)) => ctx.namer().convert(title),
+            _ => match ctx.namer().simple() {
+                Ok(title) => title,
+                Err(_) => {
+                    log::warn!("{}: cannot resolve name, skipping", ctx);
+                    return Ok(());
+                }
+            },
+        };
+
+        ctx.entity(&title);
+
+        if let Some(Value::Object(properties)) = map.get_mut("properties") {
+            ctx.form("properties");
+
+            for (name, property) in properties.iter_mut() {
+                ctx.property(name);
+                self.visit(property, ctx, true)?;
+                ctx.pop();
+            }
+
+            ctx.pop();
+        }
+
+        for key in ["items", "additionalProperties"] {
+            if let Some(value @ Value::Object(_)) = map.get_mut(key) {
+                ctx.form(key);
+                self.visit(value, ctx, true)?;
+                ctx.pop();
+            }
+        }
+
+        for key in ["oneOf", "anyOf", "allOf"] {
+            if let Some(Value::Array(options)) = map.get_mut(key) {
+                ctx.form(key);
+
+                for (i, option) in options.iter_mut().enumerate() {
+                    ctx.index(i);
+                    // allOf parts are kept inline, they are meant to be merged
+                    self.visit(option, ctx, key != "allOf")?;
+                    ctx.pop();
+                }
+
+                ctx.pop();
+            }
+        }
+
+        ctx.pop();
+
+        if extract && is_extractable(map) {
+            let name = self.register(&title, Value::Object(std::mem::take(map)));
+
+            log::info!("{}: extracted to {}", ctx, name);
+            *node = json!({ "$ref": format!("#/components/schemas/{}", escape(&name)) });
+        }
+
+        Ok(())
+    }
+
+    /// Adds schema to components, equal schemas share component, others get numeric suffix
+    fn register(&mut self, title: &str, schema: Value) -> String {
+        let mut name = title.to_string();
+        let mut i = 2;
+
+        loop {
+            match self
+                .existing
+                .get(&name)
+                .or_else(|| self.extracted.get(&name))
+            {
+                None => {
+                    self.extracted.insert(name.clone(), schema);
+                    return name;
+                }
+                Some(current) if *current == schema => return name,
+                Some(_) => {
+                    name = format!("{title}{i}");
+                    i += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Object with properties, enum of primitives or oneOf/anyOf
+fn is_extractable(map: &Map<String, Value>) -> bool {
+    let type_ = map.get("type").and_then(Value::as_str);
+
+    let is_object = matches!(type_, Some("object") | None)
+        && map
+            .get("properties")
+            .and_then(Value::as_object)
+            .map(|p| !p.is_empty())
+            .unwrap_or(false);
+
+    let is_enum =
+        matches!(type_, Some("string" | "integer" | "number")) && map.contains_key("enum");
+
+    let is_union = ["oneOf", "anyOf"]
+        .iter()
+        .any(|key| map.get(*key).map(Value::is_array).unwrap_or(false));
+
+    is_object || is_enum || is_union
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_inline() {
+        let mut schema = Schema::from_json(json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "operationId": "listPets",
+                        "parameters": [
+                            {
+                                "name": "status",
+                                "in": "query",
+                                "schema": { "type": "string", "enum": ["alive", "dead"] }
+                            }
+                        ],
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "array",
+                                            "items": { "$ref": "#/components/schemas/Pet" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    "post": {
+                        "operationId": "createPet",
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "type": "object",
+                                        "properties": {
+                                            "name": { "type": "string" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "owner": {
+                                "type": "object",
+                                "properties": {
+                                    "address": {
+                                        "type": "object",
+                                        "properties": { "city": { "type": "string" } }
+                                    }
+                                }
+                            },
+                            "kind": {
+                                "oneOf": [
+                                    { "type": "string", "enum": ["cat", "dog"] },
+                                    { "$ref": "#/components/schemas/Other" }
+                                ]
+                            },
+                            "tags": {
+                                "type": "array",
+                                "items": { "title": "tag", "type": "object", "properties": { "label": { "type": "string" } } }
+                            },
+                            "mixed": {
+                                "allOf": [
+                                    { "$ref": "#/components/schemas/Other" },
+                                    { "type": "object", "properties": { "extra": { "type": "string" } } }
+                                ]
+                            }
+                        }
+                    },
+                    "Other": { "type": "object" }
+                }
+            }
+        }));
+
+        let names = Extractor::options().process(&mut schema).unwrap();
+
+        assert_eq!(
+            names,
+            vec![
+                "PetOwnerAddress",
+                "PetOwner",
+                "PetKindOption1",
+                "PetKind",
+                "Tag",
+                "ListPetsStatusQuery",
+                "CreatePetRequestBody"
+            ]
+        );
+
+        let body = schema.get_body();
+
+        assert_eq!(
+            body.pointer("/paths/~1pets/post/requestBody/content/application~1json/schema"),
+            Some(&json!({ "$ref": "#/components/schemas/CreatePetRequestBody" }))
+        );
+        assert_eq!(
+            body.pointer("/paths/~1pets/get/parameters/0/schema"),
+            Some(&json!({ "$ref": "#/components/schemas/ListPetsStatusQuery" }))
+        );
+        assert_eq!(
+            body.pointer("/components/schemas/Pet/properties")
+                .map(|p| p.to_string()),
+            Some(
+                json!({
+                    "name": { "type": "string" },
+                    "owner": { "$ref": "#/components/schemas/PetOwner" },
+                    "kind": { "$ref": "#/components/schemas/PetKind" },
+                    "tags": {
+                        "type": "array",
+                        "items": { "$ref": "#/components/schemas/Tag" }
+                    },
+                    "mixed": {
+                        "allOf": [
+                            { "$ref": "#/components/schemas/Other" },
+                            { "type": "object", "properties": { "extra": { "type": "string" } } }
+                        ]
+                    }
+                })
+                .to_string()
+            )
+        );
+        assert_eq!(
+            body.pointer("/components/schemas/PetOwner"),
+            Some(&json!({
+                "type": "object",
+                "properties": {
+                    "address": { "$ref": "#/components/schemas/PetOwnerAddress" }
+                }
+            }))
+        );
+        assert_eq!(
+            body.pointer("/components/schemas/PetKind"),
+            Some(&json!({
+                "oneOf": [
+                    { "$ref": "#/components/schemas/PetKindOption1" },
+                    { "$ref": "#/components/schemas/Other" }
+                ]
+            }))
+        );
+    }
+
+    #[test]
+    fn test_name_collision() {
+        let mut schema = Schema::from_json(json!({
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {
+                            "owner": { "type": "object", "properties": { "id": { "type": "string" } } },
+                            "status": { "type": "string", "enum": ["alive"] }
+                        }
+                    },
+                    "PetOwner": { "type": "string" },
+                    "PetStatus": { "type": "string", "enum": ["alive"] }
+                }
+            }
+        }));
+
+        let names = Extractor::options().process(&mut schema).unwrap();
+        let body = schema.get_body();
+
+        assert_eq!(names, vec!["PetOwner2"]);
+        assert_eq!(
+            body.pointer("/components/schemas/Pet/properties/owner/$ref"),
+            Some(&json!("#/components/schemas/PetOwner2"))
+        );
+        assert_eq!(
+            body.pointer("/components/schemas/Pet/properties/status/$ref"),
+            Some(&json!("#/components/schemas/PetStatus"))
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use super::{components, diff::METHODS};
+use crate::{error::Error, schema::Schema, tools};
+
+pub struct Selector;
+
+pub struct SelectorOptions {
+    pub tags: Vec<String>,
+    pub paths: Vec<String>,
+    pub methods: Vec<String>,
+    pub operation_ids: Vec<String>,
+    pub filter: tools::Filter,
+}
+
+impl SelectorOptions {
+    pub fn with_tags(&mut self, value: Vec<String>) -> &mut Self {
+        self.tags = value;
+        self
+    }
+
+    /// Glob patterns of paths, `*` matches one segment and `**` any number of them
+    pub fn with_paths(&mut self, value: Vec<String>) -> &mut Self {
+        self.paths = value;
+        self
+    }
+
+    pub fn with_methods(&mut self, value: Vec<String>) -> &mut Self {
+        self.methods = value.iter().map(|m| m.to_lowercase()).collect();
+        self
+    }
+
+    pub fn with_operation_ids(&mut self, value: Vec<String>) -> &mut Self {
+        self.operation_ids = value;
+        self
+    }
+
+    /// Conditions checked against operation object, ex. `x-audience="partner"`
+    pub fn with_filter(&mut self, value: tools::Filter) -> &mut Self {
+        self.filter = value;
+        self
+    }
+
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        let globs = self.globs()?;
+
+        let root = schema
+            .get_body_mut()
+            .as_object_mut()
+            .ok_or(Error::InvalidOpenapiSchemaError)?;
+
+        for key in ["paths", "webhooks"] {
+            if let Some(Value::Object(items)) = root.get_mut(key) {
+                self.filter_items(items, &globs);
+            }
+        }
+
+        remove_unused_tags(root);
+
+        let body = schema.get_body_mut();
+        let used = components::used(body);
+
+        for component in components::remove(body, &used, |_| false) {
+            log::info!("{}: removed", component.pointer());
+        }
+
+        Ok(())
+    }
+
+    fn filter_items(&self, items: &mut Map<String, Value>, globs: &[Regex]) {
+        items.retain(|path, item| {
+            let Value::Object(item) = item else {
+                return true;
+            };
+
+            // referenced path items cannot be filtered by operation
+            if item.contains_key("$ref") {
+                return true;
+            }
+
+            item.retain(|method, operation| {
+                !METHODS.contains(&method.as_str()) || self.matches(path, method, operation, globs)
+            });
+
+            let keep = item.keys().any(|k| METHODS.contains(&k.as_str()));
+            if !keep {
+                log::info!("{}: removed", path);
+            }
+
+            keep
+        });
+    }
+
+    pub(crate) fn globs(&self) -> Result<Vec<Regex>, Error> {
+        self.paths.iter().map(|p| tools::glob(p)).collect()
+    }
+
+    pub(crate) fn matches(
+        &self,
+        path: &str,
+        method: &str,
+        operation: &Value,
+        globs: &[Regex],
+    ) -> bool {
+        let tags = operation
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| tags.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let operation_id = operation.get("operationId").and_then(Value::as_str);
+
+        (self.tags.is_empty() || self.tags.iter().any(|t| tags.contains(&t.as_str())))
+            && (globs.is_empty() || globs.iter().any(|g| g.is_match(path)))
+            && (self.methods.is_empty() || self.methods.iter().any(|m| m == method))
+            && (self.operation_ids.is_empty()
+                || operation_id
+                    .map(|id| self.operation_ids.iter().any(|o| o == id))
+                    .unwrap_or(false))
+            && self.filter.check(operation, true)
+    }
+}
+
+impl Selector {
+    pub fn options() -> SelectorOptions {
+        SelectorOptions {
+            tags: vec![],
+            paths: vec![],
+            methods: vec![],
+            operation_ids: vec![],
+            filter: tools::Filter::default(),
+        }
+    }
+}
+
+pub(crate) fn remove_unused_tags(root: &mut Map<String, Value>) {
+    let mut used = HashSet::new();
+
+    for key in ["paths", "webhooks"] {
+        if let Some(Value::Object(items)) = root.get(key) {
+            for operation in items
+                .values()
+                .filter_map(Value::as_object)
+                .flat_map(|item| item.iter())
+                .filter(|(method, _)| METHODS.contains(&method.as_str()))
+                .map(|(_, operation)| operation)
+            {
+                if let Some(Value::Array(tags)) = operation.get("tags") {
+                    used.extend(tags.iter().filter_map(Value::as_str).map(String::from));
+                }
+            }
+        }
+    }
+
+    if let Some(Value::Array(tags)) = root.get_mut("tags") {
+        tags.retain(|tag| {
+            tag.get("name")
+                .and_then(Value::as_str)
+                .map(|name| used.contains(name))
+                .unwrap_or(true)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use test_case::test_case;
+
+    fn spec() -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "tags": [{ "name": "pets" }, { "name": "admin" }],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "tags": ["pets"],
+                        "operationId": "listPets",
+                        "x-audience": "partner",
+                        "responses": { "200": { "$ref": "#/components/responses/Pets" } }
+                    },
+                    "post": {
+                        "tags": ["pets"],
+                        "operationId": "createPet",
+                        "requestBody": { "$ref": "#/components/requestBodies/Pet" },
+                        "responses": { "201": { "description": "Created" } }
+                    }
+                },
+                "/admin/users/{id}": {
+                    "parameters": [{ "$ref": "#/components/parameters/Id" }],
+                    "delete": {
+                        "tags": ["admin"],
+                        "operationId": "deleteUser",
+                        "security": [{ "basic": [] }],
+                        "responses": { "204": { "description": "Deleted" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": { "type": "object" }
+                },
+                "responses": {
+                    "Pets": {
+                        "description": "Pets",
+                        "content": {
+                            "application/json": {
+                                "schema": { "type": "array", "items": { "$ref": "#/components/schemas/Pet" } }
+                            }
+                        }
+                    }
+                },
+                "requestBodies": {
+                    "Pet": {
+                        "content": {
+                            "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } }
+                        }
+                    }
+                },
+                "parameters": {
+                    "Id": { "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }
+                },
+                "securitySchemes": {
+                    "basic": { "type": "http", "scheme": "basic" }
+                }
+            }
+        })
+    }
+
+    fn operations(value: &Value) -> Vec<String> {
+        value["paths"]
+            .as_object()
+            .unwrap()
+            .values()
+            .flat_map(|item| item.as_object().unwrap().values())
+            .filter_map(|operation| operation.get("operationId"))
+            .map(|id| id.as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test_case(Selector::options().with_tags(vec!["pets".to_string()]), vec!["listPets", "createPet"])]
+    #[test_case(Selector::options().with_paths(vec!["/admin/**".to_string()]), vec!["deleteUser"])]
+    #[test_case(Selector::options().with_paths(vec!["/*".to_string()]).with_methods(vec!["POST".to_string()]), vec!["createPet"])]
+    #[test_case(Selector::options().with_operation_ids(vec!["deleteUser".to_string(), "listPets".to_string()]), vec!["listPets", "deleteUser"])]
+    #[test_case(Selector::options().with_filter(tools::Filter::new(&["x-audience=\"partner\"".to_string()]).unwrap()), vec!["listPets"])]
+    fn test_select(options: &mut SelectorOptions, expected: Vec<&str>) {
+        let mut schema = Schema::from_json(spec());
+
+        options.process(&mut schema).unwrap();
+
+        assert_eq!(operations(schema.get_body()), expected);
+    }
+
+    #[test]
+    fn test_prune() {
+        let mut schema = Schema::from_json(spec());
+
+        Selector::options()
+            .with_operation_ids(vec!["listPets".to_string()])
+            .process(&mut schema)
+            .unwrap();
+
+        let expected = json!({
+            "openapi": "3.0.3",
+            "tags": [{ "name": "pets" }],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "tags": ["pets"],
+                        "operationId": "listPets",
+                        "x-audience": "partner",
+                        "responses": { "200": { "$ref": "#/components/responses/Pets" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": { "type": "object" }
+                },
+                "responses": {
+                    "Pets": {
+                        "description": "Pets",
+                        "content": {
+                            "application/json": {
+                                "schema": { "type": "array", "items": { "$ref": "#/components/schemas/Pet" } }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+}
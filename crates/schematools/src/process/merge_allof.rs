@@ -1,5 +1,9 @@
-use serde_json::Value;
+use std::fmt;
 
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use super::diff::escape;
 use crate::{
     resolver::SchemaResolver, schema::Schema, scope::SchemaScope, storage::SchemaStorage, tools,
 };
@@ -9,6 +13,20 @@ pub struct Merger;
 pub struct MergerOptions {
     pub leave_invalid_properties: bool,
     pub filter: tools::Filter,
+    pub semantic: bool,
+}
+
+/// Constraints of allOf members which cannot be satisfied together
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Conflict {
+    pub pointer: String,
+    pub reason: String,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.reason)
+    }
 }
 
 impl MergerOptions {
@@ -22,13 +40,27 @@ impl MergerOptions {
         self
     }
 
-    pub fn process(&self, schema: &mut Schema, storage: &SchemaStorage) {
+    /// Intersects constraints of allOf members instead of overwriting them with later ones
+    pub fn with_semantic(&mut self, value: bool) -> &mut Self {
+        self.semantic = value;
+        self
+    }
+
+    /// Merges allOf members, returns conflicts found by semantic merge
+    pub fn process(&self, schema: &mut Schema, storage: &SchemaStorage) -> Vec<Conflict> {
         let resolver = SchemaResolver::new(schema, storage);
 
         let root = schema.get_body_mut();
         let mut scope = SchemaScope::default();
+        let mut conflicts = vec![];
 
-        process_node(root, self, &mut scope, &resolver);
+        process_node(root, self, &mut scope, &resolver, &mut conflicts);
+
+        for conflict in conflicts.iter() {
+            log::warn!("{}", conflict);
+        }
+
+        conflicts
     }
 }
 
@@ -37,6 +69,7 @@ impl Merger {
         MergerOptions {
             leave_invalid_properties: false,
             filter: tools::Filter::default(),
+            semantic: false,
         }
     }
 }
@@ -46,6 +79,7 @@ fn process_merge(
     options: &MergerOptions,
     scope: &mut SchemaScope,
     resolver: &SchemaResolver,
+    conflicts: &mut Vec<Conflict>,
 ) {
     if !options.filter.check(root, true) {
         return log::info!("allOf skipped because of filter");
@@ -68,7 +102,7 @@ fn process_merge(
                 let mut first = resolver
                     .resolve(schemas.get_mut(0).unwrap(), scope, |v, ss| {
                         let mut node = v.clone();
-                        process_node(&mut node, options, ss, resolver);
+                        process_node(&mut node, options, ss, resolver, conflicts);
                         Ok(node)
                     })
                     .unwrap();
@@ -77,19 +111,26 @@ fn process_merge(
                     let value = resolver
                         .resolve(schemas.get_mut(n).unwrap(), scope, |v, ss| {
                             let mut node = v.clone();
-                            process_node(&mut node, options, ss, resolver);
+                            process_node(&mut node, options, ss, resolver, conflicts);
                             Ok(node)
                         })
                         .unwrap();
-                    merge_values(&mut first, value);
+                    combine(&mut first, value, options, scope, conflicts);
                 }
 
                 first
             };
 
-            // todo: leave_invalid_properties vs
             root.as_object_mut().unwrap().remove("allOf");
-            merge_values(root, first);
+
+            // keywords next to allOf are overwritten by its members unless they should be left,
+            // semantic merge intersects them like any other member so nothing is overwritten
+            if options.leave_invalid_properties && !options.semantic {
+                let siblings = std::mem::replace(root, first);
+                combine(root, siblings, options, scope, conflicts);
+            } else {
+                combine(root, first, options, scope, conflicts);
+            }
         }
 
         Value::Null => {}
@@ -105,6 +146,7 @@ fn process_node(
     options: &MergerOptions,
     scope: &mut SchemaScope,
     resolver: &SchemaResolver,
+    conflicts: &mut Vec<Conflict>,
 ) {
     match root {
         Value::Object(ref mut map) => {
@@ -113,20 +155,20 @@ fn process_node(
             {
                 for (property, value) in map.into_iter() {
                     scope.any(property);
-                    process_node(value, options, scope, resolver);
+                    process_node(value, options, scope, resolver, conflicts);
                     scope.pop();
                 }
             }
 
             // process allOf
             if map.contains_key("allOf") {
-                process_merge(root, options, scope, resolver)
+                process_merge(root, options, scope, resolver, conflicts)
             }
         }
         Value::Array(a) => {
             for (index, x) in a.iter_mut().enumerate() {
                 scope.index(index);
-                process_node(x, options, scope, resolver);
+                process_node(x, options, scope, resolver, conflicts);
                 scope.pop();
             }
         }
@@ -134,6 +176,390 @@ fn process_node(
     }
 }
 
+fn combine(
+    a: &mut Value,
+    b: Value,
+    options: &MergerOptions,
+    scope: &mut SchemaScope,
+    conflicts: &mut Vec<Conflict>,
+) {
+    if !options.semantic {
+        return merge_values(a, b);
+    }
+
+    let pointer = scope.path();
+    let mut found = vec![];
+    intersect(a, b, pointer.trim_end_matches('/'), &mut found);
+
+    // referenced schemas are processed each time they are resolved
+    for conflict in found {
+        if !conflicts.contains(&conflict) {
+            conflicts.push(conflict);
+        }
+    }
+}
+
+/// Schema accepting only values accepted by both schemas
+pub(crate) fn intersect(a: &mut Value, b: Value, pointer: &str, conflicts: &mut Vec<Conflict>) {
+    match (a, b) {
+        (_, Value::Bool(true)) | (Value::Bool(false), _) => {}
+        (a @ Value::Bool(true), b) | (a, b @ Value::Bool(false)) => *a = b,
+        (Value::Object(a), Value::Object(b)) => intersect_objects(a, b, pointer, conflicts),
+        (a, b) => merge_values(a, b),
+    }
+}
+
+fn intersect_objects(
+    a: &mut Map<String, Value>,
+    mut b: Map<String, Value>,
+    pointer: &str,
+    conflicts: &mut Vec<Conflict>,
+) {
+    intersect_bound(a, &mut b, "minimum", "exclusiveMinimum", f64::max);
+    intersect_bound(a, &mut b, "maximum", "exclusiveMaximum", f64::min);
+
+    // properties not listed by closed schema are not allowed by it
+    let forbidden = [closed_properties(a, &b), closed_properties(&b, a)].concat();
+
+    let mut combined = vec![];
+
+    for (key, value) in b {
+        let Some(current) = a.get_mut(&key) else {
+            a.insert(key, value);
+            continue;
+        };
+
+        let at = format!("{pointer}/{key}");
+
+        match key.as_str() {
+            "type" => match intersect_types(current, &value) {
+                Some(types) => *current = types,
+                None => report(
+                    conflicts,
+                    &at,
+                    format!("types {current} and {value} do not overlap"),
+                ),
+            },
+            "enum" => {
+                let common = current
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|v| value.as_array().is_some_and(|b| b.contains(v)))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                if common.is_empty() {
+                    report(
+                        conflicts,
+                        &at,
+                        format!("enums {current} and {value} have no common value"),
+                    );
+                } else {
+                    *current = Value::Array(common);
+                }
+            }
+            "const" | "format" => {
+                if *current != value {
+                    report(
+                        conflicts,
+                        &at,
+                        format!("{current} and {value} cannot be satisfied together"),
+                    );
+                }
+            }
+            "minimum" | "exclusiveMinimum" | "minLength" | "minItems" | "minProperties"
+            | "minContains" => tighten(current, value, f64::max),
+            "maximum" | "exclusiveMaximum" | "maxLength" | "maxItems" | "maxProperties"
+            | "maxContains" => tighten(current, value, f64::min),
+            "multipleOf" => match (current.as_f64(), value.as_f64()) {
+                (Some(x), Some(y)) if x % y == 0.0 => {}
+                (Some(x), Some(y)) if y % x == 0.0 => *current = value,
+                _ => combined.push(json!({ key: value })),
+            },
+            "uniqueItems" => {
+                *current = Value::Bool(*current == Value::Bool(true) || value == Value::Bool(true))
+            }
+            // cannot be merged into one keyword, both have to hold
+            "pattern" | "oneOf" | "anyOf" | "not" => {
+                if *current != value {
+                    combined.push(json!({ key: value }));
+                }
+            }
+            "properties" | "patternProperties" | "dependentSchemas" | "$defs" | "definitions" => {
+                let (Value::Object(schemas), Value::Object(other)) = (current, value) else {
+                    continue;
+                };
+
+                for (name, schema) in other {
+                    let at = format!("{at}/{}", escape(&name));
+
+                    match schemas.get_mut(&name) {
+                        Some(existing) => intersect(existing, schema, &at, conflicts),
+                        None => {
+                            schemas.insert(name, schema);
+                        }
+                    }
+                }
+            }
+            "items"
+            | "additionalProperties"
+            | "unevaluatedProperties"
+            | "unevaluatedItems"
+            | "contains"
+            | "propertyNames" => intersect(current, value, &at, conflicts),
+            _ => merge_values(current, value),
+        }
+    }
+
+    if !combined.is_empty() {
+        match a.get_mut("allOf") {
+            Some(Value::Array(all_of)) => all_of.extend(combined),
+            _ => {
+                a.insert("allOf".to_string(), Value::Array(combined));
+            }
+        }
+    }
+
+    for name in forbidden {
+        let is_required = a
+            .get("required")
+            .and_then(Value::as_array)
+            .is_some_and(|r| r.contains(&Value::String(name.clone())));
+
+        if is_required {
+            report(
+                conflicts,
+                &format!("{pointer}/properties/{}", escape(&name)),
+                "required property is not allowed by additionalProperties: false".to_string(),
+            );
+        } else if let Some(Value::Object(properties)) = a.get_mut("properties") {
+            log::info!(
+                "{}/properties/{}: removed, not allowed by additionalProperties: false",
+                pointer,
+                escape(&name)
+            );
+            properties.shift_remove(&name);
+        }
+    }
+
+    check_bounds(a, pointer, conflicts);
+}
+
+fn report(conflicts: &mut Vec<Conflict>, pointer: &str, reason: String) {
+    conflicts.push(Conflict {
+        pointer: pointer.to_string(),
+        reason,
+    });
+}
+
+/// Properties of other schema which are not allowed by closed schema
+fn closed_properties(closed: &Map<String, Value>, other: &Map<String, Value>) -> Vec<String> {
+    if closed.get("additionalProperties") != Some(&Value::Bool(false)) {
+        return vec![];
+    }
+
+    let allowed = closed.get("properties").and_then(Value::as_object);
+
+    other
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|p| p.keys())
+        .filter(|name| !allowed.is_some_and(|a| a.contains_key(*name)))
+        .cloned()
+        .collect()
+}
+
+/// Common types, integer is a subset of number
+fn intersect_types(a: &Value, b: &Value) -> Option<Value> {
+    let list = |v: &Value| -> Vec<String> {
+        match v {
+            Value::String(t) => vec![t.clone()],
+            Value::Array(types) => types
+                .iter()
+                .filter_map(|t| t.as_str().map(String::from))
+                .collect(),
+            _ => vec![],
+        }
+    };
+
+    let (a, b) = (list(a), list(b));
+    let mut common: Vec<String> = vec![];
+
+    for t in a.iter() {
+        let found = if b.contains(t) {
+            Some(t.as_str())
+        } else if (t == "integer" && b.iter().any(|x| x == "number"))
+            || (t == "number" && b.iter().any(|x| x == "integer"))
+        {
+            Some("integer")
+        } else {
+            None
+        };
+
+        if let Some(found) = found {
+            if !common.iter().any(|c| c == found) {
+                common.push(found.to_string());
+            }
+        }
+    }
+
+    match &common[..] {
+        [] => None,
+        [single] => Some(Value::String(single.clone())),
+        _ => Some(json!(common)),
+    }
+}
+
+/// Keeps tighter bound
+fn tighten(current: &mut Value, value: Value, pick: fn(f64, f64) -> f64) {
+    if let (Some(x), Some(y)) = (current.as_f64(), value.as_f64()) {
+        if pick(x, y) != x {
+            *current = value;
+        }
+    }
+}
+
+/// Openapi 3.0 boolean `exclusiveMinimum`/`exclusiveMaximum` belongs to its bound, so flag is
+/// taken from member with tighter bound and both flags count only when bounds are equal
+fn intersect_bound(
+    a: &mut Map<String, Value>,
+    b: &mut Map<String, Value>,
+    bound: &str,
+    flag: &str,
+    pick: fn(f64, f64) -> f64,
+) {
+    if !a.get(flag).is_some_and(Value::is_boolean) && !b.get(flag).is_some_and(Value::is_boolean) {
+        return;
+    }
+
+    // schema without any of keywords does not limit the other one
+    let limits =
+        |schema: &Map<String, Value>| schema.contains_key(bound) || schema.contains_key(flag);
+    if !limits(a) || !limits(b) {
+        return;
+    }
+
+    let limit = |schema: &Map<String, Value>| {
+        schema
+            .get(bound)
+            .filter(|v| v.is_number())
+            .map(|v| (v.clone(), schema.get(flag) == Some(&Value::Bool(true))))
+    };
+
+    let winner = match (limit(a), limit(b)) {
+        (Some((x, x_exclusive)), Some((y, y_exclusive))) => {
+            let (fx, fy) = (x.as_f64().unwrap(), y.as_f64().unwrap());
+
+            if fx == fy {
+                Some((x, x_exclusive || y_exclusive))
+            } else if pick(fx, fy) == fx {
+                Some((x, x_exclusive))
+            } else {
+                Some((y, y_exclusive))
+            }
+        }
+        (x, y) => x.or(y),
+    };
+
+    b.shift_remove(bound);
+    b.shift_remove(flag);
+
+    match winner {
+        Some((value, exclusive)) => {
+            a.insert(bound.to_string(), value);
+
+            if exclusive {
+                a.insert(flag.to_string(), Value::Bool(true));
+            } else {
+                a.shift_remove(flag);
+            }
+        }
+        None => {
+            a.shift_remove(flag);
+        }
+    }
+}
+
+/// Effective numeric bound and its exclusiveness, both openapi 3.0 boolean and 2020-12 numeric
+/// exclusive keywords are supported
+pub(crate) fn numeric_bound(
+    schema: &Map<String, Value>,
+    bound: &str,
+    flag: &str,
+    pick: fn(f64, f64) -> f64,
+) -> Option<(f64, bool)> {
+    let inclusive = schema.get(bound).and_then(Value::as_f64);
+
+    match schema.get(flag) {
+        Some(Value::Bool(exclusive)) => inclusive.map(|v| (v, *exclusive)),
+        Some(value) if value.is_number() => {
+            let exclusive = value.as_f64().unwrap();
+
+            match inclusive {
+                Some(v) if v != exclusive && pick(v, exclusive) == v => Some((v, false)),
+                _ => Some((exclusive, true)),
+            }
+        }
+        _ => inclusive.map(|v| (v, false)),
+    }
+}
+
+fn check_bounds(schema: &Map<String, Value>, pointer: &str, conflicts: &mut Vec<Conflict>) {
+    if let (Some((x, x_exclusive)), Some((y, y_exclusive))) = (
+        numeric_bound(schema, "minimum", "exclusiveMinimum", f64::max),
+        numeric_bound(schema, "maximum", "exclusiveMaximum", f64::min),
+    ) {
+        if x > y || (x == y && (x_exclusive || y_exclusive)) {
+            let key = if schema.contains_key("minimum") {
+                "minimum"
+            } else {
+                "exclusiveMinimum"
+            };
+
+            report(
+                conflicts,
+                &format!("{pointer}/{key}"),
+                format!("no number is between lower bound {x} and upper bound {y}"),
+            );
+        }
+    }
+
+    let bounds = [
+        ("minLength", "maxLength"),
+        ("minItems", "maxItems"),
+        ("minProperties", "maxProperties"),
+        ("minContains", "maxContains"),
+    ];
+
+    for (min, max) in bounds {
+        if let (Some(x), Some(y)) = (
+            schema.get(min).and_then(Value::as_f64),
+            schema.get(max).and_then(Value::as_f64),
+        ) {
+            if x > y {
+                report(
+                    conflicts,
+                    &format!("{pointer}/{min}"),
+                    format!("{min} {x} is greater than {max} {y}"),
+                );
+            }
+        }
+    }
+
+    if let (Some(constant), Some(Value::Array(values))) = (schema.get("const"), schema.get("enum"))
+    {
+        if !values.contains(constant) {
+            report(
+                conflicts,
+                &format!("{pointer}/const"),
+                format!("{constant} is not one of enum values"),
+            );
+        }
+    }
+}
+
 fn merge_values(a: &mut Value, b: Value) {
     match (a, b) {
         (a @ &mut Value::Object(_), Value::Object(b)) => {
@@ -639,4 +1065,200 @@ mod tests {
 
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
+
+    #[test]
+    fn test_semantic() {
+        let value = json!({
+            "allOf": [
+                {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["code"],
+                    "properties": {
+                        "code": { "type": "string", "enum": ["a", "b", "c"], "pattern": "^[a-z]$" },
+                        "count": { "type": "number", "minimum": 0, "maximum": 100 },
+                        "note": { "type": "string" }
+                    }
+                },
+                {
+                    "required": ["count"],
+                    "properties": {
+                        "code": { "enum": ["c", "b", "x"], "pattern": "^[a-c]$" },
+                        "count": { "type": ["integer", "null"], "minimum": 10, "maximum": 200 },
+                        "extra": { "type": "string" }
+                    }
+                }
+            ]
+        });
+
+        let expected = json!({
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["code", "count"],
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "enum": ["b", "c"],
+                    "pattern": "^[a-z]$",
+                    "allOf": [{ "pattern": "^[a-c]$" }]
+                },
+                "count": { "type": "integer", "minimum": 10, "maximum": 100 },
+                "note": { "type": "string" }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        let client = Client::new();
+        let ss = SchemaStorage::new(&schema, &client);
+
+        let conflicts = Merger::options()
+            .with_semantic(true)
+            .process(&mut schema, &ss);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_semantic_conflicts() {
+        let value = json!({
+            "definitions": {
+                "closed": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": { "id": { "type": "string" } }
+                }
+            },
+            "properties": {
+                "item": {
+                    "allOf": [
+                        { "$ref": "#/definitions/closed" },
+                        {
+                            "required": ["name"],
+                            "properties": {
+                                "id": { "type": "integer" },
+                                "name": { "type": "string" }
+                            }
+                        }
+                    ]
+                },
+                "size": {
+                    "allOf": [
+                        { "type": "string", "enum": ["s", "m"], "minLength": 5 },
+                        { "enum": ["l"], "maxLength": 2 }
+                    ]
+                }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        let client = Client::new();
+        let ss = SchemaStorage::new(&schema, &client);
+
+        let conflicts = Merger::options()
+            .with_semantic(true)
+            .process(&mut schema, &ss);
+
+        assert_eq!(
+            conflicts
+                .iter()
+                .map(|c| c.pointer.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "/properties/item/properties/id/type",
+                "/properties/item/properties/name",
+                "/properties/size/enum",
+                "/properties/size/minLength",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_semantic_exclusive_bounds() {
+        let value = json!({
+            "properties": {
+                "openapi": {
+                    "allOf": [
+                        { "type": "number", "minimum": 0, "exclusiveMinimum": true, "maximum": 50 },
+                        { "minimum": 10, "maximum": 50, "exclusiveMaximum": true }
+                    ]
+                },
+                "equal": {
+                    "allOf": [
+                        { "minimum": 10, "exclusiveMinimum": true },
+                        { "minimum": 10, "exclusiveMinimum": false }
+                    ]
+                },
+                "numeric": {
+                    "allOf": [
+                        { "exclusiveMinimum": 5, "maximum": 8 },
+                        { "exclusiveMinimum": 3, "exclusiveMaximum": 10 }
+                    ]
+                },
+                "empty": {
+                    "allOf": [
+                        { "exclusiveMinimum": 5 },
+                        { "maximum": 5 }
+                    ]
+                }
+            }
+        });
+
+        let expected = json!({
+            "properties": {
+                "openapi": { "type": "number", "minimum": 10, "maximum": 50, "exclusiveMaximum": true },
+                "equal": { "minimum": 10, "exclusiveMinimum": true },
+                "numeric": { "exclusiveMinimum": 5, "maximum": 8, "exclusiveMaximum": 10 },
+                "empty": { "exclusiveMinimum": 5, "maximum": 5 }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        let client = Client::new();
+        let ss = SchemaStorage::new(&schema, &client);
+
+        let conflicts = Merger::options()
+            .with_semantic(true)
+            .process(&mut schema, &ss);
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+        assert_eq!(
+            conflicts
+                .iter()
+                .map(|c| c.pointer.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/properties/empty/exclusiveMinimum"]
+        );
+    }
+
+    #[test]
+    fn test_leave_invalid_properties() {
+        let value = json!({
+            "type": "object",
+            "description": "Pet",
+            "allOf": [
+                { "description": "Animal", "properties": { "id": { "type": "string" } } }
+            ]
+        });
+
+        for (leave, description) in [(false, "Animal"), (true, "Pet")] {
+            let mut schema = Schema::from_json(value.clone());
+
+            let client = Client::new();
+            let ss = SchemaStorage::new(&schema, &client);
+
+            Merger::options()
+                .with_leave_invalid_properties(leave)
+                .process(&mut schema, &ss);
+
+            assert_eq!(
+                schema.get_body().get("description"),
+                Some(&json!(description))
+            );
+            assert!(schema.get_body().pointer("/properties/id").is_some());
+        }
+    }
 }
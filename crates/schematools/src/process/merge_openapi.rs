@@ -2,11 +2,14 @@ use serde_json::Value;
 
 use crate::{error::Error, schema::Schema, scope::SchemaScope, tools};
 
+use super::prefix_paths;
+
 pub struct Merger;
 
 pub struct MergerOptions {
     pub retag: Option<String>,
     pub add_version: Option<String>,
+    pub prefix: Option<String>,
     pub schema: Schema,
 }
 
@@ -21,9 +24,23 @@ impl MergerOptions {
         self
     }
 
+    pub fn with_prefix(&mut self, value: Option<String>) -> &mut Self {
+        self.prefix = value;
+        self
+    }
+
     pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
         let mut scope = SchemaScope::default();
-        let merged = self.schema.get_body();
+
+        let prefixed = match &self.prefix {
+            Some(prefix) => {
+                let mut prefixed = self.schema.clone();
+                prefix_paths::Prefixer::options(prefix.clone()).process(&mut prefixed)?;
+                Some(prefixed)
+            }
+            None => None,
+        };
+        let merged = prefixed.as_ref().unwrap_or(&self.schema).get_body();
         let root = schema.get_body_mut();
 
         if let Some(openapi) = root.as_object_mut() {
@@ -151,6 +168,7 @@ impl Merger {
         MergerOptions {
             retag: None,
             add_version: None,
+            prefix: None,
             schema,
         }
     }
@@ -363,6 +381,54 @@ mod tests {
         assert_eq!(schema.get_body().to_string(), expected.to_string());
     }
 
+    #[test]
+    fn test_paths_prefix() {
+        let first = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "type": "object",
+                    }
+                }
+            },
+        });
+
+        let second = json!({
+            "paths": {
+                "/invoices": {
+                    "get": {
+                        "type": "object",
+                    }
+                }
+            },
+        });
+
+        let expected = json!({
+            "paths": {
+                "/users": {
+                    "get": {
+                        "type": "object",
+                    }
+                },
+                "/billing/invoices": {
+                    "get": {
+                        "type": "object",
+                    }
+                }
+            },
+            "components": {},
+            "tags": [],
+        });
+
+        let mut schema = Schema::from_json(first);
+
+        let _result = Merger::options(Schema::from_json(second))
+            .with_prefix(Some("/billing".to_string()))
+            .process(&mut schema);
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
     #[test]
     fn test_components_missing() {
         let first = json!({
@@ -2,12 +2,31 @@
 
 #[cfg(feature = "semver")]
 pub mod bump_openapi;
+pub mod changelog;
+pub mod components;
+pub mod convert_swagger;
+pub mod dedupe;
 pub mod dereference;
+pub mod diff;
+pub mod downgrade_openapi;
+pub mod extract_inline;
+pub mod filter;
 pub mod flatten_allof;
 pub mod merge_allof;
 pub mod merge_openapi;
 pub mod name;
+pub mod normalize_parameters;
+pub mod operations;
+pub mod overlay;
 pub mod patch;
+#[cfg(feature = "json-patch")]
+pub mod patch_rebase;
+pub mod prefix_paths;
+pub mod publish;
+pub mod remove_unused;
+pub mod rename_schema;
+pub mod upgrade_jsonschema;
+pub mod upgrade_openapi;
 
 use serde_json::Value;
 use url::Url;
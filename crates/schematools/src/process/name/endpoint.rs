@@ -1,9 +1,61 @@
-use super::word::{pluralize, singularize};
+use std::collections::HashMap;
+
+use super::word::Dictionary;
 
 use crate::error::Error;
+use crate::tools::template_pieces;
 use inflector::Inflector;
 use regex::Regex;
 
+/// Placeholders available in operationId templates
+const PLACEHOLDERS: [&str; 7] = [
+    "verb",
+    "method",
+    "resource",
+    "subresource",
+    "resources",
+    "param",
+    "version",
+];
+
+#[derive(Clone, Debug, Default)]
+pub struct OperationIdOptions {
+    /// Reverts order to resource+method+version, ignored when template is set
+    pub resource_method_version: bool,
+
+    /// Pattern like `{verb}{Resource}{Subresource}By{Param}`
+    pub template: Option<String>,
+
+    /// Overrides of verbs, keys are methods optionally suffixed with `:collection` or `:item`
+    pub verbs: HashMap<String, String>,
+
+    /// Irregular and uncountable words used when resources are singularized/pluralized
+    pub dictionary: Dictionary,
+}
+
+impl OperationIdOptions {
+    pub fn validate(&self) -> Result<(), Error> {
+        if let Some(template) = &self.template {
+            let pieces = template_pieces(template).ok_or_else(|| {
+                Error::OperationIdTemplateInvalid(format!("unclosed placeholder in {template}"))
+            })?;
+
+            for (_, placeholder) in pieces {
+                if let Some(placeholder) = placeholder {
+                    if !PLACEHOLDERS.contains(&placeholder.to_lowercase().as_str()) {
+                        return Err(Error::OperationIdTemplateInvalid(format!(
+                            "unknown placeholder {{{placeholder}}}, available: {}",
+                            PLACEHOLDERS.join(", ")
+                        )));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 pub struct Endpoint {
     original: String,
     version: Option<String>,
@@ -60,28 +112,109 @@ impl Endpoint {
     }
 
     pub fn get_operation_id(&self, resource_method_version: bool) -> String {
+        self.operation_id(&OperationIdOptions {
+            resource_method_version,
+            ..OperationIdOptions::default()
+        })
+    }
+
+    pub fn operation_id(&self, options: &OperationIdOptions) -> String {
+        if let Some(template) = &options.template {
+            return self.render(template, options);
+        }
+
         let mut parts: Vec<String> = vec![];
 
         if let Some(v) = self.version.clone() {
             parts.push(v);
         }
 
-        parts.push(
-            match self.method.as_str() {
-                "get" => {
-                    if !self.original.ends_with('}') {
-                        "list"
-                    } else {
-                        "get"
-                    }
+        parts.push(self.verb(&options.verbs));
+
+        let mut resources = self.resources(&options.dictionary);
+
+        if !options.resource_method_version {
+            parts.append(&mut resources)
+        } else {
+            parts.reverse();
+            resources.append(&mut parts);
+            parts = resources;
+        };
+
+        // camelcase
+        parts
+            .into_iter()
+            .collect::<Vec<String>>()
+            .join(" ")
+            .to_camel_case()
+    }
+
+    fn verb(&self, verbs: &HashMap<String, String>) -> String {
+        let kind = if self.original.ends_with('}') {
+            "item"
+        } else {
+            "collection"
+        };
+
+        verbs
+            .get(&format!("{}:{}", self.method, kind))
+            .or_else(|| verbs.get(&self.method))
+            .cloned()
+            .unwrap_or_else(|| {
+                match (self.method.as_str(), kind) {
+                    ("get", "collection") => "list",
+                    ("post", _) => "create",
+                    ("patch", _) => "update",
+                    (m, _) => m,
                 }
-                "post" => "create",
-                "patch" => "update",
-                m => m,
+                .to_string()
+            })
+    }
+
+    /// Renders template, literal text preceding empty placeholder is skipped
+    fn render(&self, template: &str, options: &OperationIdOptions) -> String {
+        let resources = self.resources(&options.dictionary);
+        let param = if self.original.ends_with('}') {
+            self.identifiers
+                .last()
+                .map(|i| i.trim_matches(|c| c == '{' || c == '}').to_string())
+        } else {
+            None
+        };
+
+        let mut parts: Vec<String> = vec![];
+
+        for (literal, placeholder) in template_pieces(template).unwrap_or_default() {
+            let value = match placeholder.map(|p| p.to_lowercase()).as_deref() {
+                Some("verb") => Some(self.verb(&options.verbs)),
+                Some("method") => Some(self.method.clone()),
+                Some("resource") => resources.first().cloned(),
+                Some("subresource") => Some(
+                    resources
+                        .iter()
+                        .skip(1)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                Some("resources") => Some(resources.join(" ")),
+                Some("param") => param.clone(),
+                Some("version") => self.version.clone(),
+                _ => None,
             }
-            .to_string(),
-        );
+            .filter(|v| !v.is_empty());
 
+            if placeholder.is_none() || value.is_some() {
+                parts.push(literal.to_string());
+            }
+
+            parts.extend(value);
+        }
+
+        parts.join(" ").to_camel_case()
+    }
+
+    fn resources(&self, dictionary: &Dictionary) -> Vec<String> {
         let mut resources: Vec<String> = vec![];
         for (i, resource) in self.resources.iter().enumerate() {
             let processed = resource.clone().to_camel_case();
@@ -90,12 +223,12 @@ impl Endpoint {
                 {
                     if i < self.identifiers.len() {
                         // has identifier
-                        singularize(processed)
+                        dictionary.singularize(processed)
                     } else {
                         match self.method.as_str() {
-                            "post" => singularize(processed),
+                            "post" => dictionary.singularize(processed),
                             "get" => processed,
-                            _ => pluralize(processed),
+                            _ => dictionary.pluralize(processed),
                         }
                     }
                 }
@@ -103,20 +236,7 @@ impl Endpoint {
             );
         }
 
-        if !resource_method_version {
-            parts.append(&mut resources)
-        } else {
-            parts.reverse();
-            resources.append(&mut parts);
-            parts = resources;
-        };
-
-        // camelcase
-        parts
-            .into_iter()
-            .collect::<Vec<String>>()
-            .join(" ")
-            .to_camel_case()
+        resources
     }
 }
 
@@ -163,4 +283,62 @@ mod tests {
             expected
         );
     }
+
+    #[test_case(
+        "get",
+        "users/{userId}",
+        "{verb}{Resource}{Subresource}By{Param}",
+        "getUserByUserId"
+    )]
+    #[test_case("get", "users", "{verb}{Resource}{Subresource}By{Param}", "listUsers")]
+    #[test_case(
+        "get",
+        "v2/users/{userId}/groups/{groupId}",
+        "{verb}{Resource}{Subresource}By{Param}",
+        "getUserGroupByGroupId"
+    )]
+    #[test_case(
+        "patch",
+        "v2/users/{userId}",
+        "{verb}{Resource}{Version}",
+        "modifyUserV2"
+    )]
+    #[test_case("delete", "users/{userId}", "{resources}_{method}", "userDelete")]
+    fn test_operation_id_template(method: &str, path: &str, template: &str, expected: &str) {
+        let options = OperationIdOptions {
+            template: Some(template.to_string()),
+            verbs: HashMap::from([("patch".to_string(), "modify".to_string())]),
+            ..OperationIdOptions::default()
+        };
+
+        assert_eq!(
+            Endpoint::new(method.to_string(), path.to_string())
+                .unwrap()
+                .operation_id(&options),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_verbs() {
+        let options = OperationIdOptions {
+            verbs: HashMap::from([("get:collection".to_string(), "search".to_string())]),
+            ..OperationIdOptions::default()
+        };
+        let endpoint = |path: &str| Endpoint::new("get".to_string(), path.to_string()).unwrap();
+
+        assert_eq!(endpoint("users").operation_id(&options), "searchUsers");
+        assert_eq!(endpoint("users/{id}").operation_id(&options), "getUser");
+    }
+
+    #[test_case("{verb}{Entity}")]
+    #[test_case("{verb")]
+    fn test_invalid_template(template: &str) {
+        let options = OperationIdOptions {
+            template: Some(template.to_string()),
+            ..OperationIdOptions::default()
+        };
+
+        assert!(options.validate().is_err());
+    }
 }
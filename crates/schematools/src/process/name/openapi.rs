@@ -2,8 +2,11 @@ use crate::error::Error;
 use crate::process::name::jsonschema;
 use crate::{schema::Schema, scope::SchemaNamingStrategy, scope::SchemaScope, tools};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 
 use super::endpoint;
+use super::word::Dictionary;
+use crate::process::diff::METHODS;
 
 pub struct OpenapiNamer;
 
@@ -12,6 +15,9 @@ pub struct OpenapiNamerOptions {
     pub overwrite: bool,
     pub overwrite_ambiguous: bool,
     pub naming_strategy: SchemaNamingStrategy,
+    pub operation_id_template: Option<String>,
+    pub verbs: HashMap<String, String>,
+    pub dictionary: Dictionary,
 }
 
 impl OpenapiNamer {
@@ -21,6 +27,9 @@ impl OpenapiNamer {
             overwrite: false,
             overwrite_ambiguous: false,
             naming_strategy: SchemaNamingStrategy::Default,
+            operation_id_template: None,
+            verbs: HashMap::new(),
+            dictionary: Dictionary::default(),
         }
     }
 }
@@ -46,7 +55,33 @@ impl OpenapiNamerOptions {
         self
     }
 
+    /// Pattern of generated operationId, ex. `{verb}{Resource}{Subresource}By{Param}`
+    pub fn with_operation_id_template(&mut self, value: Option<String>) -> &mut Self {
+        self.operation_id_template = value;
+        self
+    }
+
+    /// Verbs used in operationId, ex. `get:collection` => `list`, `patch` => `update`
+    pub fn with_verbs(&mut self, value: HashMap<String, String>) -> &mut Self {
+        self.verbs = value;
+        self
+    }
+
+    /// Irregular and uncountable words used in operationId
+    pub fn with_dictionary(&mut self, value: Dictionary) -> &mut Self {
+        self.dictionary = value;
+        self
+    }
+
     pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        let operation_id_options = endpoint::OperationIdOptions {
+            resource_method_version: self.resource_method_version,
+            template: self.operation_id_template.clone(),
+            verbs: self.verbs.clone(),
+            dictionary: self.dictionary.clone(),
+        };
+        operation_id_options.validate()?;
+
         let root = schema.get_body_mut();
 
         let mut scope = SchemaScope::new(self.naming_strategy.clone());
@@ -126,6 +161,18 @@ impl OpenapiNamerOptions {
             },
         )?;
 
+        // operationIds which are kept, generated ones cannot collide with them
+        let mut used: HashSet<String> = HashSet::new();
+        if !self.overwrite {
+            tools::each_node(root, &mut scope, "/path:paths/any:*/any:*", |node, _, _| {
+                if let Some(Value::String(id)) = node.get("operationId") {
+                    used.insert(id.clone());
+                }
+
+                Ok(())
+            })?;
+        }
+
         tools::each_node_mut(
             root,
             &mut scope,
@@ -136,10 +183,11 @@ impl OpenapiNamerOptions {
 
                     match endpoint::Endpoint::new(method.to_string(), endpoint.to_string()) {
                         Ok(endpoint) => {
-                            let operation_id =
-                                endpoint.get_operation_id(self.resource_method_version);
+                            let operation_id = endpoint.operation_id(&operation_id_options);
 
                             if !details.contains_key("operationId") || self.overwrite {
+                                let operation_id = disambiguate(operation_id, &mut used);
+
                                 log::debug!("{}/operationId -> {}", ctx, operation_id);
                                 details
                                     .insert("operationId".to_string(), Value::String(operation_id));
@@ -160,6 +208,208 @@ impl OpenapiNamerOptions {
             },
         )?;
 
+        // default strategy keeps historical behaviour of naming components only
+        if self.naming_strategy != SchemaNamingStrategy::Default {
+            tools::each_node_mut(
+                root,
+                &mut scope,
+                "/path:paths/any:*/any:*",
+                |node, parts, ctx| {
+                    if let [endpoint, method] = parts {
+                        if METHODS.contains(&method.as_str()) {
+                            self.name_operation(node, endpoint, method, ctx)?;
+                        }
+                    }
+
+                    Ok(())
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Names inline request body and response schemas of operation
+    fn name_operation(
+        &self,
+        node: &mut Value,
+        endpoint: &str,
+        method: &str,
+        ctx: &mut SchemaScope,
+    ) -> Result<(), Error> {
+        let Some(operation_id) = node
+            .get("operationId")
+            .and_then(Value::as_str)
+            .map(String::from)
+        else {
+            return Ok(());
+        };
+
+        let options = jsonschema::NamerOptions {
+            overwrite: self.overwrite,
+            overwrite_ambiguous: self.overwrite_ambiguous,
+            base_name: None,
+        };
+
+        ctx.operation(&operation_id, method, endpoint);
+
+        if let Some(Value::Object(content)) = node.pointer_mut("/requestBody/content") {
+            ctx.request();
+
+            for schema in content.values_mut().filter_map(|m| m.get_mut("schema")) {
+                if schema.get("$ref").is_none() {
+                    jsonschema::name_schema(schema, ctx, &options)?;
+                }
+            }
+
+            ctx.pop();
+        }
+
+        if let Some(Value::Object(responses)) = node.get_mut("responses") {
+            for (code, response) in responses.iter_mut() {
+                let Some(Value::Object(content)) = response.get_mut("content") else {
+                    continue;
+                };
+
+                ctx.response(code);
+
+                for schema in content.values_mut().filter_map(|m| m.get_mut("schema")) {
+                    if schema.get("$ref").is_none() {
+                        jsonschema::name_schema(schema, ctx, &options)?;
+                    }
+                }
+
+                ctx.pop();
+            }
+        }
+
+        ctx.pop();
+
         Ok(())
     }
 }
+
+/// Adds numeric suffix to operationId which is already used
+fn disambiguate(operation_id: String, used: &mut HashSet<String>) -> String {
+    let mut result = operation_id.clone();
+    let mut i = 2;
+
+    while used.contains(&result) {
+        result = format!("{operation_id}{i}");
+        i += 1;
+    }
+
+    if result != operation_id {
+        log::warn!(
+            "operationId {} is duplicated, using {}",
+            operation_id,
+            result
+        );
+    }
+
+    used.insert(result.clone());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::str::FromStr;
+    use test_case::test_case;
+
+    fn spec() -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/pets/{petId}": {
+                    "post": {
+                        "operationId": "createPet",
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "type": "object",
+                                        "properties": {
+                                            "owner": { "type": "object" }
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                        "responses": {
+                            "200": {
+                                "description": "Created",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "type": "object" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn titles(value: &Value) -> Vec<&str> {
+        let operation = &value["paths"]["/pets/{petId}"]["post"];
+        let request = &operation["requestBody"]["content"]["application/json"]["schema"];
+
+        vec![
+            request["title"].as_str().unwrap_or_default(),
+            request["properties"]["owner"]["title"]
+                .as_str()
+                .unwrap_or_default(),
+            operation["responses"]["200"]["content"]["application/json"]["schema"]["title"]
+                .as_str()
+                .unwrap_or_default(),
+        ]
+    }
+
+    #[test_case("default", vec!["", "", ""])]
+    #[test_case("operation", vec!["CreatePetRequest", "CreatePetRequestOwner", "CreatePetResponse200"])]
+    #[test_case("path", vec!["PetsPetIdPostRequest", "PetsPetIdPostRequestOwner", "PetsPetIdPostResponse200"])]
+    #[test_case("{operation}{Form}{Property}", vec!["CreatePetRequest", "CreatePetRequestOwner", "CreatePetResponse200"])]
+    fn test_naming_strategy(strategy: &str, expected: Vec<&str>) {
+        let mut schema = Schema::from_json(spec());
+
+        OpenapiNamer::options()
+            .with_naming_strategy(SchemaNamingStrategy::from_str(strategy).unwrap())
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(titles(schema.get_body()), expected);
+    }
+
+    #[test]
+    fn test_operation_id_collisions() {
+        let mut schema = Schema::from_json(json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/users": { "get": {}, "post": { "operationId": "listUsers" } },
+                "/v2/users": { "get": {} }
+            }
+        }));
+
+        OpenapiNamer::options()
+            .with_operation_id_template(Some("{verb}{Resources}".to_string()))
+            .process(&mut schema)
+            .unwrap();
+
+        let body = schema.get_body();
+        assert_eq!(body["paths"]["/users"]["get"]["operationId"], "listUsers2");
+        assert_eq!(
+            body["paths"]["/v2/users"]["get"]["operationId"],
+            "listUsers3"
+        );
+    }
+
+    #[test_case("unknown")]
+    #[test_case("{operation}{verb}")]
+    #[test_case("{operation")]
+    fn test_invalid_naming_strategy(strategy: &str) {
+        assert!(SchemaNamingStrategy::from_str(strategy).is_err());
+    }
+}
@@ -1,5 +1,11 @@
 #![allow(clippy::trivial_regex)]
+use std::collections::HashMap;
+
 use regex::Regex;
+use serde::Deserialize;
+
+use crate::error::Error;
+use crate::schema::{path_to_url, Schema};
 
 // create replacement tuple
 macro_rules! crtpl {
@@ -66,6 +72,105 @@ lazy_static! {
     ];
 }
 
+/// Domain words which built-in rules get wrong, ex.
+///
+/// ```yaml
+/// irregular:
+///   criterion: criteria
+/// uncountable:
+///   - data
+///   - sms
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Dictionary {
+    /// Singular to plural forms
+    #[serde(default)]
+    pub irregular: HashMap<String, String>,
+
+    #[serde(default)]
+    pub uncountable: Vec<String>,
+}
+
+impl Dictionary {
+    /// Loads dictionary from json/yaml file
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let file = Schema::load_url(path_to_url(path.to_string())?)?;
+
+        serde_json::from_value(file.get_body().clone()).map_err(Error::SerdeJsonError)
+    }
+
+    pub fn singularize(&self, word: String) -> String {
+        self.singular(&word).unwrap_or_else(|| singularize(word))
+    }
+
+    pub fn pluralize(&self, word: String) -> String {
+        self.plural(&word).unwrap_or_else(|| pluralize(word))
+    }
+
+    /// Singular form if word is known to dictionary
+    pub fn singular(&self, word: &str) -> Option<String> {
+        self.lookup(word, |last| {
+            self.irregular
+                .iter()
+                .find(|(_, plural)| plural.to_lowercase() == last)
+                .map(|(singular, _)| singular.clone())
+        })
+    }
+
+    /// Plural form if word is known to dictionary
+    pub fn plural(&self, word: &str) -> Option<String> {
+        self.lookup(word, |last| {
+            self.irregular
+                .iter()
+                .find(|(singular, _)| singular.to_lowercase() == last)
+                .map(|(_, plural)| plural.clone())
+        })
+    }
+
+    /// Checks whole word and then its last part (after `-`, `_` or camel case boundary)
+    fn lookup<F>(&self, word: &str, irregular: F) -> Option<String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let boundary = word
+            .char_indices()
+            .filter(|(i, c)| *i > 0 && (c.is_uppercase() || *c == '-' || *c == '_'))
+            .map(|(i, c)| if c.is_uppercase() { i } else { i + 1 })
+            .next_back();
+
+        [Some(0), boundary]
+            .into_iter()
+            .flatten()
+            .filter(|start| *start < word.len())
+            .find_map(|start| {
+                let last = &word[start..];
+                let lower = last.to_lowercase();
+
+                let replacement = if self.uncountable.iter().any(|u| u.to_lowercase() == lower) {
+                    Some(last.to_string())
+                } else {
+                    irregular(&lower)
+                }?;
+
+                Some(format!(
+                    "{}{}",
+                    &word[..start],
+                    keep_case(last, &replacement)
+                ))
+            })
+    }
+}
+
+/// Applies capitalization of first letter of original word to replacement
+fn keep_case(original: &str, replacement: &str) -> String {
+    match (original.chars().next(), replacement.chars().next()) {
+        (Some(o), Some(r)) if o.is_uppercase() => {
+            r.to_uppercase().collect::<String>() + &replacement[r.len_utf8()..]
+        }
+        _ => replacement.to_string(),
+    }
+}
+
 pub fn singularize(word: String) -> String {
     for (re, replacement) in SINGULAR_LIST.iter() {
         if re.is_match(&word) {
@@ -117,4 +222,30 @@ mod tests {
     fn test_singularize(word: String, expected: String) {
         assert_eq!(singularize(word), expected);
     }
+
+    fn dictionary() -> Dictionary {
+        Dictionary {
+            irregular: HashMap::from([("criterion".to_string(), "criteria".to_string())]),
+            uncountable: vec!["data".to_string(), "sms".to_string()],
+        }
+    }
+
+    #[test_case("data", "data")]
+    #[test_case("userData", "userData")]
+    #[test_case("user-sms", "user-sms")]
+    #[test_case("criterion", "criteria")]
+    #[test_case("searchCriterion", "searchCriteria")]
+    #[test_case("user", "users")]
+    fn test_dictionary_pluralize(word: &str, expected: &str) {
+        assert_eq!(dictionary().pluralize(word.to_string()), expected);
+    }
+
+    #[test_case("data", "data")]
+    #[test_case("sms", "sms")]
+    #[test_case("criteria", "criterion")]
+    #[test_case("search_criteria", "search_criterion")]
+    #[test_case("users", "user")]
+    fn test_dictionary_singularize(word: &str, expected: &str) {
+        assert_eq!(dictionary().singularize(word.to_string()), expected);
+    }
 }
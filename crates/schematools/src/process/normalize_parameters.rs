@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use super::components::Component;
+use super::dedupe::sorted;
+use super::diff::{escape, METHODS};
+use crate::{error::Error, schema::Schema, scope::SchemaScope};
+
+const PREFIX: &str = "#/components/parameters/";
+
+pub struct Normalizer;
+
+pub struct NormalizerOptions {
+    pub min_occurrences: usize,
+    pub hoist: bool,
+}
+
+impl NormalizerOptions {
+    /// Number of identical copies needed to move parameter to `components/parameters`
+    pub fn with_min_occurrences(&mut self, value: usize) -> &mut Self {
+        self.min_occurrences = value;
+        self
+    }
+
+    /// Moves parameters shared by every operation of path to path item
+    pub fn with_hoist(&mut self, value: bool) -> &mut Self {
+        self.hoist = value;
+        self
+    }
+
+    /// Hoists shared parameters to path items and replaces repeated ones with references
+    /// to `components/parameters`, returns names of created components
+    pub fn process(&self, schema: &mut Schema) -> Result<Vec<String>, Error> {
+        let root = schema.get_body_mut();
+
+        if !root.is_object() {
+            return Err(Error::InvalidOpenapiSchemaError);
+        }
+
+        let components = root
+            .pointer("/components/parameters")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+
+        if self.hoist {
+            for key in ["paths", "webhooks"] {
+                if let Some(Value::Object(items)) = root.get_mut(key) {
+                    for (path, item) in items.iter_mut() {
+                        if let Value::Object(item) = item {
+                            hoist(item, &components, &format!("/{}/{}", key, escape(path)));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut registry = Registry::new(components);
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for parameter in lists(root).into_iter().flatten() {
+            if parameter.get("$ref").is_none() {
+                *counts.entry(sorted(parameter).to_string()).or_default() += 1;
+            }
+        }
+
+        for parameter in lists_mut(root).into_iter().flatten() {
+            if parameter.get("$ref").is_some() {
+                continue;
+            }
+
+            let key = sorted(parameter).to_string();
+
+            let name = match registry.find(&key) {
+                Some(name) => name,
+                None if counts[&key] >= self.min_occurrences => registry.register(parameter),
+                None => continue,
+            };
+
+            *parameter = json!({ "$ref": format!("{}{}", PREFIX, escape(&name)) });
+        }
+
+        if !registry.created.is_empty() {
+            let body = root.as_object_mut().unwrap();
+            let components = body
+                .entry("components")
+                .or_insert_with(|| Value::Object(Map::new()));
+
+            if let Value::Object(components) = components {
+                let Value::Object(parameters) = components
+                    .entry("parameters")
+                    .or_insert_with(|| Value::Object(Map::new()))
+                else {
+                    return Err(Error::InvalidOpenapiSchemaError);
+                };
+
+                for name in registry.created.iter() {
+                    log::info!("{}: created", Component::new("parameters", name).pointer());
+                    parameters.insert(name.clone(), registry.existing[name].clone());
+                }
+            }
+        }
+
+        Ok(registry.created)
+    }
+}
+
+impl Normalizer {
+    pub fn options() -> NormalizerOptions {
+        NormalizerOptions {
+            min_occurrences: 2,
+            hoist: true,
+        }
+    }
+}
+
+/// Components by their sorted representation, created ones are added in document order
+struct Registry {
+    existing: Map<String, Value>,
+    keys: HashMap<String, String>,
+    created: Vec<String>,
+}
+
+impl Registry {
+    fn new(existing: Map<String, Value>) -> Self {
+        let mut keys = HashMap::new();
+
+        for (name, parameter) in existing.iter() {
+            keys.entry(sorted(parameter).to_string())
+                .or_insert_with(|| name.clone());
+        }
+
+        Self {
+            existing,
+            keys,
+            created: vec![],
+        }
+    }
+
+    fn find(&self, key: &str) -> Option<String> {
+        self.keys.get(key).cloned()
+    }
+
+    /// Name is built from parameter name, location and then number is added on conflict
+    fn register(&mut self, parameter: &Value) -> String {
+        let namer = SchemaScope::default().namer();
+        let (name, location) = identity(parameter).unwrap_or_default();
+
+        let base = namer.convert(if name.is_empty() { "parameter" } else { &name });
+        let suffix = if location.is_empty() {
+            String::new()
+        } else {
+            namer.convert(&location)
+        };
+
+        let result = [base.clone(), format!("{base}{suffix}")]
+            .into_iter()
+            .chain((2..).map(|i| format!("{base}{i}")))
+            .find(|candidate| !self.existing.contains_key(candidate))
+            .unwrap();
+
+        self.keys
+            .insert(sorted(parameter).to_string(), result.clone());
+        self.existing.insert(result.clone(), parameter.clone());
+        self.created.push(result.clone());
+
+        result
+    }
+}
+
+/// Name and location of parameter
+fn identity(parameter: &Value) -> Option<(String, String)> {
+    Some((
+        parameter.get("name")?.as_str()?.to_string(),
+        parameter.get("in")?.as_str()?.to_string(),
+    ))
+}
+
+fn label(parameter: &Value) -> String {
+    match (parameter.get("$ref"), identity(parameter)) {
+        (Some(Value::String(reference)), _) => reference.clone(),
+        (_, Some((name, location))) => format!("{location} parameter {name}"),
+        _ => parameter.to_string(),
+    }
+}
+
+/// Parameter with local reference replaced by referenced component
+fn resolve<'a>(parameter: &'a Value, components: &'a Map<String, Value>) -> &'a Value {
+    parameter
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.strip_prefix(PREFIX))
+        .and_then(|name| components.get(&name.replace("~1", "/").replace("~0", "~")))
+        .unwrap_or(parameter)
+}
+
+/// Moves parameters identical in every operation to path item, operation parameters which
+/// override path ones with a different definition are never touched
+fn hoist(item: &mut Map<String, Value>, components: &Map<String, Value>, pointer: &str) {
+    // referenced path items are shared, operations are in other document
+    if item.contains_key("$ref") {
+        return;
+    }
+
+    let shared = match item.get("parameters") {
+        Some(Value::Array(shared)) => shared.clone(),
+        Some(_) => return,
+        None => vec![],
+    };
+
+    let key = |parameter: &Value| sorted(resolve(parameter, components)).to_string();
+    let shared_keys = shared
+        .iter()
+        .map(|p| (identity(resolve(p, components)), key(p)))
+        .collect::<Vec<_>>();
+
+    let methods = item
+        .keys()
+        .filter(|k| METHODS.contains(&k.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    // copies of path parameters in operation are redundant
+    for method in methods.iter() {
+        if let Some(Value::Array(parameters)) = item[method].get_mut("parameters") {
+            parameters.retain(|p| {
+                let keep = !shared_keys.contains(&(identity(resolve(p, components)), key(p)));
+                if !keep {
+                    log::info!(
+                        "{}/{}: {} duplicates path parameter",
+                        pointer,
+                        method,
+                        label(p)
+                    );
+                }
+                keep
+            });
+        }
+    }
+
+    if methods.len() < 2 {
+        cleanup(item, &methods);
+        return;
+    }
+
+    let lists = methods
+        .iter()
+        .map(|m| {
+            item[m]
+                .get("parameters")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    let mut hoisted = vec![];
+
+    for parameter in lists[0].iter() {
+        let Some(id) = identity(resolve(parameter, components)) else {
+            continue;
+        };
+        let parameter_key = key(parameter);
+
+        let everywhere = lists[1..]
+            .iter()
+            .all(|list| list.iter().any(|p| key(p) == parameter_key));
+        let overridden = shared_keys.iter().any(|(i, _)| i.as_ref() == Some(&id));
+
+        if everywhere && !overridden {
+            hoisted.push((parameter.clone(), parameter_key));
+        }
+    }
+
+    if hoisted.is_empty() {
+        cleanup(item, &methods);
+        return;
+    }
+
+    for method in methods.iter() {
+        if let Some(Value::Array(parameters)) = item[method].get_mut("parameters") {
+            parameters.retain(|p| !hoisted.iter().any(|(_, k)| *k == key(p)));
+        }
+    }
+
+    let Value::Array(shared) = item
+        .entry("parameters")
+        .or_insert_with(|| Value::Array(vec![]))
+    else {
+        return;
+    };
+
+    for (parameter, _) in hoisted {
+        log::info!("{}: {} hoisted", pointer, label(&parameter));
+        shared.push(parameter);
+    }
+
+    cleanup(item, &methods);
+}
+
+/// Removes parameter lists left empty
+fn cleanup(item: &mut Map<String, Value>, methods: &[String]) {
+    for method in methods {
+        if let Some(Value::Object(operation)) = item.get_mut(method) {
+            if operation.get("parameters") == Some(&json!([])) {
+                operation.shift_remove("parameters");
+            }
+        }
+    }
+}
+
+/// Parameters of path items and operations
+fn lists(root: &Value) -> Vec<&Vec<Value>> {
+    let Value::Object(root) = root else {
+        return vec![];
+    };
+
+    root.iter()
+        .filter(|(key, _)| *key == "paths" || *key == "webhooks")
+        .filter_map(|(_, items)| items.as_object())
+        .flat_map(|items| items.values())
+        .filter_map(Value::as_object)
+        .flat_map(|item| item.iter())
+        .filter_map(|(key, value)| match key.as_str() {
+            "parameters" => value.as_array(),
+            method if METHODS.contains(&method) => {
+                value.get("parameters").and_then(Value::as_array)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn lists_mut(root: &mut Value) -> Vec<&mut Vec<Value>> {
+    let Value::Object(root) = root else {
+        return vec![];
+    };
+
+    root.iter_mut()
+        .filter(|(key, _)| *key == "paths" || *key == "webhooks")
+        .filter_map(|(_, items)| items.as_object_mut())
+        .flat_map(|items| items.values_mut())
+        .filter_map(Value::as_object_mut)
+        .flat_map(|item| item.iter_mut())
+        .filter_map(|(key, value)| match key.as_str() {
+            "parameters" => value.as_array_mut(),
+            method if METHODS.contains(&method) => {
+                value.get_mut("parameters").and_then(Value::as_array_mut)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> Value {
+        json!({
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "parameters": [
+                            { "name": "page", "in": "query", "schema": { "type": "integer" } },
+                            { "name": "X-Request-Id", "in": "header", "schema": { "type": "string" } }
+                        ]
+                    },
+                    "post": {
+                        "parameters": [
+                            { "in": "header", "name": "X-Request-Id", "schema": { "type": "string" } }
+                        ]
+                    }
+                },
+                "/users": {
+                    "parameters": [
+                        { "name": "limit", "in": "query", "schema": { "type": "integer" } }
+                    ],
+                    "get": {
+                        "parameters": [
+                            { "name": "page", "in": "query", "schema": { "type": "integer" } },
+                            { "name": "limit", "in": "query", "schema": { "type": "integer" } }
+                        ]
+                    },
+                    "put": {
+                        "parameters": [
+                            { "name": "limit", "in": "query", "schema": { "type": "integer", "maximum": 10 } },
+                            { "name": "X-Request-Id", "in": "header", "schema": { "type": "string" } }
+                        ]
+                    },
+                    "delete": {
+                        "parameters": [
+                            { "$ref": "#/components/parameters/RequestId" },
+                            { "name": "page", "in": "path", "required": true, "schema": { "type": "string" } }
+                        ]
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "RequestId": { "name": "X-Request-Id", "in": "header", "schema": { "type": "string" } }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut schema = Schema::from_json(spec());
+
+        let created = Normalizer::options().process(&mut schema).unwrap();
+
+        assert_eq!(created, vec!["Page".to_string()]);
+
+        let expected = json!({
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "parameters": [{ "$ref": "#/components/parameters/Page" }]
+                    },
+                    "post": {},
+                    "parameters": [{ "$ref": "#/components/parameters/RequestId" }]
+                },
+                "/users": {
+                    "parameters": [
+                        { "name": "limit", "in": "query", "schema": { "type": "integer" } }
+                    ],
+                    "get": {
+                        "parameters": [{ "$ref": "#/components/parameters/Page" }]
+                    },
+                    "put": {
+                        "parameters": [
+                            { "name": "limit", "in": "query", "schema": { "type": "integer", "maximum": 10 } },
+                            { "$ref": "#/components/parameters/RequestId" }
+                        ]
+                    },
+                    "delete": {
+                        "parameters": [
+                            { "$ref": "#/components/parameters/RequestId" },
+                            { "name": "page", "in": "path", "required": true, "schema": { "type": "string" } }
+                        ]
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "RequestId": { "name": "X-Request-Id", "in": "header", "schema": { "type": "string" } },
+                    "Page": { "name": "page", "in": "query", "schema": { "type": "integer" } }
+                }
+            }
+        });
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_normalize_without_hoist() {
+        let mut schema = Schema::from_json(spec());
+
+        let created = Normalizer::options()
+            .with_hoist(false)
+            .with_min_occurrences(3)
+            .process(&mut schema)
+            .unwrap();
+
+        // limit is repeated three times but with different schema once
+        assert!(created.is_empty());
+
+        let body = schema.get_body();
+
+        assert_eq!(
+            body.pointer("/paths/~1pets/post/parameters/0"),
+            Some(&json!({ "$ref": "#/components/parameters/RequestId" }))
+        );
+        assert_eq!(
+            body.pointer("/paths/~1pets/get/parameters/0"),
+            spec().pointer("/paths/~1pets/get/parameters/0")
+        );
+        assert_eq!(
+            body.pointer("/paths/~1users/get/parameters/1"),
+            spec().pointer("/paths/~1users/get/parameters/1")
+        );
+    }
+}
@@ -0,0 +1,479 @@
+use serde_json::{json, Map, Value};
+
+use super::diff::{escape, METHODS};
+use super::filter::{Selector, SelectorOptions};
+use crate::{error::Error, schema::Schema};
+
+pub struct Editor;
+
+pub struct EditorOptions {
+    pub selector: SelectorOptions,
+    pub tags: Option<Vec<String>>,
+    pub add_tags: Vec<String>,
+    pub security: Vec<(String, Vec<String>)>,
+    pub parameters: Vec<String>,
+    pub headers: Vec<String>,
+    pub deprecated: bool,
+    pub extensions: Vec<(String, Value)>,
+}
+
+impl EditorOptions {
+    /// Selects edited operations, every operation is edited by default
+    pub fn with_selector(&mut self, value: SelectorOptions) -> &mut Self {
+        self.selector = value;
+        self
+    }
+
+    /// Replaces tags of operation
+    pub fn with_tags(&mut self, value: Option<Vec<String>>) -> &mut Self {
+        self.tags = value;
+        self
+    }
+
+    /// Appends tags missing in operation
+    pub fn with_add_tags(&mut self, value: Vec<String>) -> &mut Self {
+        self.add_tags = value;
+        self
+    }
+
+    /// Security requirements given as `name` or `name:scope1,scope2`
+    pub fn with_security(&mut self, value: Vec<String>) -> &mut Self {
+        self.security = value
+            .iter()
+            .map(|s| match s.split_once(':') {
+                Some((name, scopes)) => (
+                    name.to_string(),
+                    scopes
+                        .split(',')
+                        .filter(|s| !s.is_empty())
+                        .map(String::from)
+                        .collect(),
+                ),
+                None => (s.clone(), vec![]),
+            })
+            .collect();
+        self
+    }
+
+    /// Parameters appended to operation, names of `components/parameters` or references
+    pub fn with_parameters(&mut self, value: Vec<String>) -> &mut Self {
+        self.parameters = value;
+        self
+    }
+
+    /// Headers added to each response, names of `components/headers` or references
+    pub fn with_headers(&mut self, value: Vec<String>) -> &mut Self {
+        self.headers = value;
+        self
+    }
+
+    pub fn with_deprecated(&mut self, value: bool) -> &mut Self {
+        self.deprecated = value;
+        self
+    }
+
+    /// Extensions set on operation, ex. `x-audience`
+    pub fn with_extensions(&mut self, value: Vec<(String, Value)>) -> &mut Self {
+        self.extensions = value;
+        self
+    }
+
+    /// Applies edits to every selected operation, returns number of edited operations
+    pub fn process(&self, schema: &mut Schema) -> Result<usize, Error> {
+        let globs = self.selector.globs()?;
+
+        if let Some((key, _)) = self.extensions.iter().find(|(k, _)| !k.starts_with("x-")) {
+            return Err(Error::OperationEditInvalid(format!(
+                "{key} is not an extension"
+            )));
+        }
+
+        let root = schema
+            .get_body_mut()
+            .as_object_mut()
+            .ok_or(Error::InvalidOpenapiSchemaError)?;
+
+        let parameters = self.references(root, "parameters", &self.parameters)?;
+        let headers = self
+            .headers
+            .iter()
+            .map(|h| Ok((header_name(h), self.reference(root, "headers", h)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        for (name, _) in self.security.iter() {
+            if root
+                .get("components")
+                .and_then(|c| c.get("securitySchemes"))
+                .and_then(|s| s.get(name))
+                .is_none()
+            {
+                return Err(Error::OperationEditInvalid(format!(
+                    "security scheme {name} not found"
+                )));
+            }
+        }
+
+        // operation without security inherits global requirements, which are kept when extended
+        let global_security = root.get("security").cloned();
+
+        let mut count = 0;
+
+        for key in ["paths", "webhooks"] {
+            let Some(Value::Object(items)) = root.get_mut(key) else {
+                continue;
+            };
+
+            for (path, item) in items.iter_mut() {
+                let Value::Object(item) = item else {
+                    continue;
+                };
+
+                for (method, operation) in item.iter_mut() {
+                    if !METHODS.contains(&method.as_str())
+                        || !self.selector.matches(path, method, operation, &globs)
+                    {
+                        continue;
+                    }
+
+                    let Value::Object(operation) = operation else {
+                        continue;
+                    };
+
+                    self.edit(operation, &parameters, &headers, &global_security);
+                    log::info!("/{}/{}/{}: edited", key, escape(path), method);
+                    count += 1;
+                }
+            }
+        }
+
+        if count == 0 {
+            log::warn!("no operation matches selector");
+        }
+
+        self.add_root_tags(root);
+
+        Ok(count)
+    }
+
+    fn edit(
+        &self,
+        operation: &mut Map<String, Value>,
+        parameters: &[String],
+        headers: &[(String, String)],
+        global_security: &Option<Value>,
+    ) {
+        if let Some(tags) = &self.tags {
+            operation.insert("tags".to_string(), json!(tags));
+        }
+
+        if !self.add_tags.is_empty() {
+            let tags = array(operation, "tags");
+
+            for tag in self.add_tags.iter() {
+                if !tags.iter().any(|t| t == tag) {
+                    tags.push(json!(tag));
+                }
+            }
+        }
+
+        if !self.security.is_empty() {
+            if !operation.contains_key("security") {
+                if let Some(security) = global_security {
+                    operation.insert("security".to_string(), security.clone());
+                }
+            }
+
+            let requirements = array(operation, "security");
+
+            for (name, scopes) in self.security.iter() {
+                let requirement = json!({ name: scopes });
+
+                if !requirements.contains(&requirement) {
+                    requirements.push(requirement);
+                }
+            }
+        }
+
+        if !parameters.is_empty() {
+            let list = array(operation, "parameters");
+
+            for reference in parameters {
+                let parameter = json!({ "$ref": reference });
+
+                if !list.contains(&parameter) {
+                    list.push(parameter);
+                }
+            }
+        }
+
+        if !headers.is_empty() {
+            if let Some(Value::Object(responses)) = operation.get_mut("responses") {
+                for (code, response) in responses.iter_mut() {
+                    // shared responses are not modified, they may be used by other operations
+                    let Some(response) =
+                        response.as_object_mut().filter(|r| !r.contains_key("$ref"))
+                    else {
+                        log::debug!("{code}: referenced response skipped");
+                        continue;
+                    };
+
+                    let Value::Object(list) = response
+                        .entry("headers")
+                        .or_insert_with(|| Value::Object(Map::new()))
+                    else {
+                        continue;
+                    };
+
+                    for (name, reference) in headers {
+                        list.entry(name.clone())
+                            .or_insert_with(|| json!({ "$ref": reference }));
+                    }
+                }
+            }
+        }
+
+        if self.deprecated {
+            operation.insert("deprecated".to_string(), Value::Bool(true));
+        }
+
+        for (key, value) in self.extensions.iter() {
+            operation.insert(key.clone(), value.clone());
+        }
+    }
+
+    /// Declares added tags in root `tags` list when document has one
+    fn add_root_tags(&self, root: &mut Map<String, Value>) {
+        let Some(Value::Array(declared)) = root.get_mut("tags") else {
+            return;
+        };
+
+        for tag in self.tags.iter().flatten().chain(self.add_tags.iter()) {
+            if !declared
+                .iter()
+                .any(|t| t.get("name").and_then(Value::as_str) == Some(tag))
+            {
+                declared.push(json!({ "name": tag }));
+            }
+        }
+    }
+
+    fn references(
+        &self,
+        root: &Map<String, Value>,
+        kind: &str,
+        names: &[String],
+    ) -> Result<Vec<String>, Error> {
+        names
+            .iter()
+            .map(|n| self.reference(root, kind, n))
+            .collect()
+    }
+
+    /// Reference to component, local ones have to exist
+    fn reference(
+        &self,
+        root: &Map<String, Value>,
+        kind: &str,
+        name: &str,
+    ) -> Result<String, Error> {
+        if name.contains('#') {
+            return Ok(name.to_string());
+        }
+
+        if root
+            .get("components")
+            .and_then(|c| c.get(kind))
+            .and_then(|c| c.get(name))
+            .is_none()
+        {
+            return Err(Error::OperationEditInvalid(format!(
+                "{kind}/{name} not found"
+            )));
+        }
+
+        Ok(format!("#/components/{}/{}", kind, escape(name)))
+    }
+}
+
+impl Editor {
+    pub fn options() -> EditorOptions {
+        EditorOptions {
+            selector: Selector::options(),
+            tags: None,
+            add_tags: vec![],
+            security: vec![],
+            parameters: vec![],
+            headers: vec![],
+            deprecated: false,
+            extensions: vec![],
+        }
+    }
+}
+
+/// Name of header, last segment of reference
+fn header_name(header: &str) -> String {
+    header
+        .rsplit('/')
+        .next()
+        .unwrap_or(header)
+        .replace("~1", "/")
+        .replace("~0", "~")
+}
+
+fn array<'a>(operation: &'a mut Map<String, Value>, key: &str) -> &'a mut Vec<Value> {
+    let value = operation.entry(key).or_insert_with(|| Value::Array(vec![]));
+
+    if !value.is_array() {
+        *value = Value::Array(vec![]);
+    }
+
+    value.as_array_mut().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools;
+    use serde_json::json;
+
+    fn spec() -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "tags": [{ "name": "pets" }],
+            "security": [{ "apiKey": [] }],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "tags": ["pets"],
+                        "responses": {
+                            "200": { "description": "Pets" },
+                            "default": { "$ref": "#/components/responses/Error" }
+                        }
+                    },
+                    "post": {
+                        "tags": ["pets"],
+                        "x-audience": "partner",
+                        "security": [],
+                        "responses": { "201": { "description": "Created" } }
+                    }
+                },
+                "/users/{id}": {
+                    "get": {
+                        "responses": { "200": { "description": "User" } }
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "Tenant": { "name": "X-Tenant", "in": "header" }
+                },
+                "headers": {
+                    "RateLimit": { "schema": { "type": "integer" } }
+                },
+                "responses": {
+                    "Error": { "description": "Error" }
+                },
+                "securitySchemes": {
+                    "apiKey": { "type": "apiKey", "in": "header", "name": "key" },
+                    "oauth": { "type": "oauth2", "flows": {} }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_edit_selected() {
+        let mut schema = Schema::from_json(spec());
+
+        let mut selector = Selector::options();
+        selector
+            .with_paths(vec!["/pets".to_string()])
+            .with_methods(vec!["GET".to_string()]);
+
+        let count = Editor::options()
+            .with_selector(selector)
+            .with_add_tags(vec!["public".to_string(), "pets".to_string()])
+            .with_security(vec!["oauth:read,write".to_string()])
+            .with_parameters(vec!["Tenant".to_string()])
+            .with_headers(vec!["RateLimit".to_string()])
+            .with_deprecated(true)
+            .with_extensions(vec![("x-stability".to_string(), json!("beta"))])
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(count, 1);
+
+        let body = schema.get_body();
+
+        assert_eq!(
+            body.pointer("/paths/~1pets/get").unwrap().to_string(),
+            json!({
+                "tags": ["pets", "public"],
+                "responses": {
+                    "200": {
+                        "description": "Pets",
+                        "headers": {
+                            "RateLimit": { "$ref": "#/components/headers/RateLimit" }
+                        }
+                    },
+                    "default": { "$ref": "#/components/responses/Error" }
+                },
+                "security": [{ "apiKey": [] }, { "oauth": ["read", "write"] }],
+                "parameters": [{ "$ref": "#/components/parameters/Tenant" }],
+                "deprecated": true,
+                "x-stability": "beta"
+            })
+            .to_string()
+        );
+        assert_eq!(
+            body.get("tags"),
+            Some(&json!([{ "name": "pets" }, { "name": "public" }]))
+        );
+        assert_eq!(
+            body.pointer("/paths/~1users~1{id}/get"),
+            spec().pointer("/paths/~1users~1{id}/get")
+        );
+    }
+
+    #[test]
+    fn test_edit_by_filter() {
+        let mut schema = Schema::from_json(spec());
+
+        let mut selector = Selector::options();
+        selector.with_filter(tools::Filter::new(&["x-audience=\"partner\"".to_string()]).unwrap());
+
+        let count = Editor::options()
+            .with_selector(selector)
+            .with_tags(Some(vec!["partner".to_string()]))
+            .with_security(vec!["apiKey".to_string()])
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(count, 1);
+        assert_eq!(
+            schema.get_body().pointer("/paths/~1pets/post/tags"),
+            Some(&json!(["partner"]))
+        );
+        // explicitly empty security is not replaced by global one
+        assert_eq!(
+            schema.get_body().pointer("/paths/~1pets/post/security"),
+            Some(&json!([{ "apiKey": [] }]))
+        );
+    }
+
+    #[test]
+    fn test_edit_invalid() {
+        for options in [
+            Editor::options().with_parameters(vec!["Missing".to_string()]),
+            Editor::options().with_security(vec!["basic".to_string()]),
+            Editor::options().with_extensions(vec![("deprecated".to_string(), json!(true))]),
+        ] {
+            let mut schema = Schema::from_json(spec());
+
+            assert!(matches!(
+                options.process(&mut schema),
+                Err(Error::OperationEditInvalid(_))
+            ));
+        }
+    }
+}
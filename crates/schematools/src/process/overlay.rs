@@ -0,0 +1,303 @@
+use std::fmt::Display;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::jsonpath::{pointer, JsonPath};
+use crate::schema::Schema;
+use crate::{error::Error, schema::path_to_url};
+
+#[derive(Clone, Debug)]
+pub enum Action {
+    /// Apply overlay file
+    Apply(OverlayApplyOpts),
+}
+
+#[derive(Clone, Debug)]
+pub struct OverlayApplyOpts {
+    /// Path to overlay file
+    pub overlay: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Overlay {
+    pub overlay: String,
+    pub info: OverlayInfo,
+    pub extends: Option<String>,
+    pub actions: Vec<OverlayAction>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct OverlayInfo {
+    pub title: String,
+    pub version: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct OverlayAction {
+    pub target: String,
+    pub description: Option<String>,
+    pub update: Option<Value>,
+    #[serde(default)]
+    pub remove: bool,
+}
+
+/// Action which target did not select any node
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnmatchedAction {
+    pub index: usize,
+    pub target: String,
+}
+
+impl Display for UnmatchedAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "actions[{}]: target {} matched nothing",
+            self.index, self.target
+        )
+    }
+}
+
+pub fn execute(schema: &mut Schema, action: &Action) -> Result<Vec<UnmatchedAction>, Error> {
+    match action {
+        Action::Apply(c) => {
+            let overlay_file = Schema::load_url(path_to_url(c.overlay.clone())?)?;
+            let overlay: Overlay = serde_json::from_value(overlay_file.get_body().clone())
+                .map_err(|e| Error::OverlayInvalid(e.to_string()))?;
+
+            apply(schema, &overlay)
+        }
+    }
+}
+
+/// Applies overlay actions in order, returns actions which matched nothing
+pub fn apply(schema: &mut Schema, overlay: &Overlay) -> Result<Vec<UnmatchedAction>, Error> {
+    if !overlay.overlay.starts_with("1.") {
+        return Err(Error::OverlayInvalid(format!(
+            "unsupported version {}",
+            overlay.overlay
+        )));
+    }
+
+    let mut unmatched = vec![];
+
+    for (index, action) in overlay.actions.iter().enumerate() {
+        let body = schema.get_body_mut();
+        let mut paths = JsonPath::parse(&action.target)?
+            .select(body)
+            .into_iter()
+            .map(|node| node.path)
+            .collect::<Vec<_>>();
+
+        if paths.is_empty() {
+            let action = UnmatchedAction {
+                index,
+                target: action.target.clone(),
+            };
+            log::warn!("{}", action);
+            unmatched.push(action);
+            continue;
+        }
+
+        if action.remove {
+            // removing deepest and last items first keeps remaining paths valid
+            paths.sort_by(|a, b| compare_paths(b, a));
+            paths.dedup();
+
+            for path in paths {
+                log::info!("{}: removed", pointer(&path));
+                remove(body, &path);
+            }
+        } else if let Some(update) = &action.update {
+            for path in paths {
+                log::info!("{}: updated", pointer(&path));
+
+                if let Some(node) = body.pointer_mut(&pointer(&path)) {
+                    update_node(node, update);
+                }
+            }
+        }
+    }
+
+    Ok(unmatched)
+}
+
+fn compare_paths(a: &[String], b: &[String]) -> std::cmp::Ordering {
+    for (x, y) in a.iter().zip(b.iter()) {
+        let ordering = match (x.parse::<usize>(), y.parse::<usize>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+
+    a.len().cmp(&b.len())
+}
+
+fn remove(body: &mut Value, path: &[String]) {
+    let Some((last, parent)) = path.split_last() else {
+        return;
+    };
+
+    match body.pointer_mut(&pointer(parent)) {
+        Some(Value::Object(map)) => {
+            map.shift_remove(last);
+        }
+        Some(Value::Array(items)) => {
+            if let Ok(index) = last.parse::<usize>() {
+                if index < items.len() {
+                    items.remove(index);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Objects are merged recursively, arrays get update appended, other values are replaced
+fn update_node(node: &mut Value, update: &Value) {
+    match (node, update) {
+        (Value::Object(target), Value::Object(source)) => merge(target, source),
+        (Value::Array(items), Value::Array(source)) => items.extend(source.iter().cloned()),
+        (Value::Array(items), value) => items.push(value.clone()),
+        (node, value) => *node = value.clone(),
+    }
+}
+
+fn merge(target: &mut Map<String, Value>, source: &Map<String, Value>) {
+    for (key, value) in source {
+        match (target.get_mut(key), value) {
+            (Some(Value::Object(t)), Value::Object(s)) => merge(t, s),
+            (Some(Value::Array(t)), Value::Array(s)) => t.extend(s.iter().cloned()),
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Pets", "version": "1.0.0" },
+            "tags": [{ "name": "pets" }, { "name": "internal" }],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "tags": ["pets"],
+                        "operationId": "listPets",
+                        "parameters": [
+                            { "name": "limit", "in": "query" },
+                            { "name": "debug", "in": "query", "x-internal": true },
+                            { "name": "trace", "in": "query", "x-internal": true }
+                        ]
+                    }
+                },
+                "/internal/health": {
+                    "get": { "tags": ["internal"], "operationId": "health" }
+                }
+            }
+        })
+    }
+
+    fn overlay(actions: Value) -> Overlay {
+        serde_json::from_value(json!({
+            "overlay": "1.0.0",
+            "info": { "title": "Public", "version": "1.0.0" },
+            "actions": actions
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut schema = Schema::from_json(spec());
+
+        let unmatched = apply(
+            &mut schema,
+            &overlay(json!([
+                {
+                    "target": "$.info",
+                    "update": { "title": "Public pets", "x-audience": "public" }
+                },
+                {
+                    "target": "$.paths.*.*.parameters[?@.x-internal == true]",
+                    "remove": true
+                },
+                {
+                    "target": "$.paths['/internal/health']",
+                    "remove": true
+                },
+                {
+                    "target": "$.tags[?@.name == 'internal']",
+                    "remove": true
+                },
+                {
+                    "target": "$.paths.*.get.tags",
+                    "update": "public"
+                }
+            ])),
+        )
+        .unwrap();
+
+        let expected = json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Public pets", "version": "1.0.0", "x-audience": "public" },
+            "tags": [{ "name": "pets" }],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "tags": ["pets", "public"],
+                        "operationId": "listPets",
+                        "parameters": [
+                            { "name": "limit", "in": "query" }
+                        ]
+                    }
+                }
+            }
+        });
+
+        assert!(unmatched.is_empty());
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_unmatched() {
+        let mut schema = Schema::from_json(spec());
+
+        let unmatched = apply(
+            &mut schema,
+            &overlay(json!([
+                { "target": "$.info", "update": { "description": "Pets" } },
+                { "target": "$.paths['/owners']", "remove": true }
+            ])),
+        )
+        .unwrap();
+
+        assert_eq!(
+            unmatched,
+            vec![UnmatchedAction {
+                index: 1,
+                target: "$.paths['/owners']".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let mut schema = Schema::from_json(spec());
+        let mut overlay = overlay(json!([]));
+        overlay.overlay = "2.0.0".to_string();
+
+        assert!(apply(&mut schema, &overlay).is_err());
+    }
+}
@@ -1,10 +1,12 @@
+#[cfg(feature = "json-patch")]
+use super::patch_rebase;
 use crate::schema::Schema;
 use crate::{error::Error, schema::path_to_url};
 
 #[cfg(feature = "json-patch")]
-use json_patch::{diff, patch, Patch};
+use json_patch::{diff, merge, patch, Patch};
 use serde::Serialize;
-use serde_json::{from_value, Value};
+use serde_json::{from_value, Map, Value};
 
 #[derive(Copy, Clone, Debug, Serialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Operation {
@@ -23,6 +25,15 @@ pub enum Action {
 
     /// Apply inline patch
     Inline(PatchInlineOpts),
+
+    /// Create json merge patch file (RFC 7396)
+    MergeCreate(PatchCreateOpts),
+
+    /// Apply json merge patch file (RFC 7396)
+    MergeApply(PatchApplyOpts),
+
+    /// Rebase json patch file onto new version of original schema
+    Rebase(PatchRebaseOpts),
 }
 
 #[derive(Clone, Debug)]
@@ -37,6 +48,21 @@ pub struct PatchApplyOpts {
     pub patch: String,
 }
 
+#[derive(Clone, Debug)]
+pub struct PatchRebaseOpts {
+    /// Path to schema file patch was created against
+    pub old_base: String,
+
+    /// Path to new version of schema file, processed schema is used when missing
+    pub new_base: Option<String>,
+
+    /// Path to json patch file
+    pub patch: String,
+
+    /// Output status of every operation instead of rebased patch
+    pub report: bool,
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct PatchInlineOpts {
     /// Operation add/remove/replace
@@ -75,5 +101,117 @@ pub fn execute(schema: &mut Schema, action: &Action) -> Result<(), Error> {
 
             patch(schema.get_body_mut(), &p).map_err(Error::JsonPatchError)
         }
+        Action::MergeCreate(c) => {
+            let original = Schema::load_url(path_to_url(c.original.clone())?)?;
+            let body = schema.get_body_mut();
+
+            let result = merge_diff(original.get_body(), body, "");
+
+            body.clone_from(&result);
+
+            Ok(())
+        }
+        Action::MergeApply(c) => {
+            let patch_file = Schema::load_url(path_to_url(c.patch.clone())?)?;
+
+            merge(schema.get_body_mut(), patch_file.get_body());
+
+            Ok(())
+        }
+        Action::Rebase(c) => {
+            let old_base = Schema::load_url(path_to_url(c.old_base.clone())?)?;
+            let patch_file = Schema::load_url(path_to_url(c.patch.clone())?)?;
+            let new_base = match &c.new_base {
+                Some(new_base) => Schema::load_url(path_to_url(new_base.clone())?)?
+                    .get_body()
+                    .clone(),
+                None => schema.get_body().clone(),
+            };
+
+            let result =
+                patch_rebase::rebase(old_base.get_body(), &new_base, patch_file.get_body())?;
+
+            let body = if c.report {
+                serde_json::to_value(result.operations).map_err(Error::SerdeJsonError)?
+            } else {
+                result.patch
+            };
+
+            schema.get_body_mut().clone_from(&body);
+
+            Ok(())
+        }
+    }
+}
+
+/// Creates merge patch transforming original into target,
+/// nulls cannot be expressed in merge patch so they are set as removals
+fn merge_diff(original: &Value, target: &Value, pointer: &str) -> Value {
+    match (original, target) {
+        (Value::Object(original), Value::Object(target)) => {
+            let mut result = Map::new();
+
+            for key in original.keys().filter(|k| !target.contains_key(*k)) {
+                result.insert(key.clone(), Value::Null);
+            }
+
+            for (key, value) in target {
+                let path = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+
+                if value.is_null() {
+                    log::warn!("{}: null cannot be set by merge patch, removing", path);
+                }
+
+                match original.get(key) {
+                    Some(o) if o == value => {}
+                    Some(o) => {
+                        result.insert(key.clone(), merge_diff(o, value, &path));
+                    }
+                    None => {
+                        result.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+
+            Value::Object(result)
+        }
+        _ => target.clone(),
+    }
+}
+
+#[cfg(all(test, feature = "json-patch"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_diff() {
+        let original = json!({
+            "info": { "title": "Pets", "description": "Old", "x-internal": true },
+            "tags": ["pets"],
+            "servers": [{ "url": "https://a" }]
+        });
+        let target = json!({
+            "info": { "title": "Pets", "description": "New" },
+            "tags": ["pets", "admin"],
+            "servers": [{ "url": "https://a" }],
+            "x-audience": "public"
+        });
+
+        let result = merge_diff(&original, &target, "");
+
+        assert_eq!(
+            result,
+            json!({
+                "info": { "x-internal": null, "description": "New" },
+                "tags": ["pets", "admin"],
+                "x-audience": "public"
+            })
+        );
+
+        let mut applied = original.clone();
+        merge(&mut applied, &result);
+
+        assert_eq!(applied, target);
     }
 }
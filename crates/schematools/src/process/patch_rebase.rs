@@ -0,0 +1,394 @@
+use json_patch::{patch, Patch};
+use serde::Serialize;
+use serde_json::{from_value, json, Value};
+
+use crate::error::Error;
+use crate::jsonpath::pointer;
+
+/// Keys identifying array elements, checked in order
+const IDENTITY: &[&[&str]] = &[
+    &["name", "in"],
+    &["name"],
+    &["$ref"],
+    &["operationId"],
+    &["url"],
+];
+
+#[derive(Copy, Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// Operation still applies, possibly with different path
+    Rebased,
+    /// New base already contains the change, operation is no longer needed
+    Obsolete,
+    /// Upstream changed the same location or it cannot be found anymore
+    Conflict,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RebasedOperation {
+    pub index: usize,
+    pub op: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rebased_path: Option<String>,
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Rebase {
+    /// Patch applicable to new base, obsolete and conflicting operations are left out
+    pub patch: Value,
+    pub operations: Vec<RebasedOperation>,
+}
+
+enum Location {
+    Found(Vec<String>),
+    Missing(String),
+}
+
+/// Re-anchors json patch created against old base onto new base,
+/// array elements are matched by content instead of indexes
+pub fn rebase(old_base: &Value, new_base: &Value, operations: &Value) -> Result<Rebase, Error> {
+    from_value::<Patch>(operations.clone()).map_err(Error::SerdeJsonError)?;
+
+    let mut old_doc = old_base.clone();
+    let mut new_doc = new_base.clone();
+    let mut rebased = vec![];
+    let mut report = vec![];
+
+    for (index, operation) in operations.as_array().into_iter().flatten().enumerate() {
+        let op = operation["op"].as_str().unwrap_or_default().to_string();
+        let path = operation["path"].as_str().unwrap_or_default().to_string();
+
+        let (status, new_operation, reason) = classify(&old_doc, &new_doc, operation);
+
+        let (status, reason) = if apply(&mut old_doc, operation).is_err() {
+            (
+                Status::Conflict,
+                Some("does not apply to old base".to_string()),
+            )
+        } else if let (Status::Rebased, Some(new_operation)) = (status, &new_operation) {
+            if apply(&mut new_doc, new_operation).is_err() {
+                (
+                    Status::Conflict,
+                    Some("does not apply to new base".to_string()),
+                )
+            } else {
+                rebased.push(new_operation.clone());
+                (status, reason)
+            }
+        } else {
+            (status, reason)
+        };
+
+        let rebased_path = new_operation
+            .as_ref()
+            .and_then(|o| o["path"].as_str())
+            .filter(|p| *p != path)
+            .map(String::from);
+
+        let entry = RebasedOperation {
+            index,
+            op,
+            path,
+            rebased_path,
+            status,
+            reason,
+        };
+
+        match entry.status {
+            Status::Rebased => log::info!("{}: rebased", entry.path),
+            Status::Obsolete => log::info!(
+                "{}: obsolete, {}",
+                entry.path,
+                entry.reason.clone().unwrap_or_default()
+            ),
+            Status::Conflict => log::warn!(
+                "{}: conflict, {}",
+                entry.path,
+                entry.reason.clone().unwrap_or_default()
+            ),
+        }
+
+        report.push(entry);
+    }
+
+    Ok(Rebase {
+        patch: Value::Array(rebased),
+        operations: report,
+    })
+}
+
+fn apply(doc: &mut Value, operation: &Value) -> Result<(), Error> {
+    let p: Patch = from_value(json!([operation])).map_err(Error::SerdeJsonError)?;
+
+    patch(doc, &p).map_err(Error::JsonPatchError)
+}
+
+fn classify(
+    old_doc: &Value,
+    new_doc: &Value,
+    operation: &Value,
+) -> (Status, Option<Value>, Option<String>) {
+    let op = operation["op"].as_str().unwrap_or_default();
+    let path = operation["path"].as_str().unwrap_or_default();
+    let value = operation.get("value");
+
+    let conflict = |reason: String| (Status::Conflict, None, Some(reason));
+    let obsolete = |reason: &str| (Status::Obsolete, None, Some(reason.to_string()));
+
+    let segments = match locate(old_doc, new_doc, path, op == "add") {
+        Ok(Location::Found(segments)) => segments,
+        Ok(Location::Missing(_)) if op == "remove" => return obsolete("already removed"),
+        Ok(Location::Missing(missing)) => return conflict(format!("{missing} not found")),
+        Err(reason) => return conflict(reason),
+    };
+
+    let new_path = pointer(&segments);
+    let old_value = old_doc.pointer(path);
+    let new_value = new_doc.pointer(&new_path);
+
+    let mut new_operation = operation.clone();
+    new_operation["path"] = Value::String(new_path.clone());
+
+    match op {
+        "remove" => match new_value {
+            None => return obsolete("already removed"),
+            Some(v) if Some(v) != old_value => return conflict("changed in new base".to_string()),
+            _ => {}
+        },
+        "replace" => match new_value {
+            None => return conflict(format!("{new_path} not found")),
+            Some(v) if Some(v) == value => return obsolete("already applied"),
+            Some(v) if Some(v) != old_value => return conflict("changed in new base".to_string()),
+            _ => {}
+        },
+        "add" => {
+            let (_, parent) = segments.split_last().unwrap_or((&String::new(), &[]));
+
+            match new_doc.pointer(&pointer(parent)) {
+                Some(Value::Array(items)) if value.map(|v| items.contains(v)) == Some(true) => {
+                    return obsolete("already applied")
+                }
+                Some(Value::Array(_)) => {}
+                _ => match new_value {
+                    Some(v) if Some(v) == value => return obsolete("already applied"),
+                    Some(v) if Some(v) != old_value => {
+                        return conflict("changed in new base".to_string())
+                    }
+                    _ => {}
+                },
+            }
+        }
+        "test" if new_value != value => {
+            return conflict("tested value differs in new base".to_string())
+        }
+        "move" | "copy" => {
+            let from = operation["from"].as_str().unwrap_or_default();
+
+            match locate(old_doc, new_doc, from, false) {
+                Ok(Location::Found(segments)) => {
+                    new_operation["from"] = Value::String(pointer(&segments));
+                }
+                Ok(Location::Missing(missing)) => return conflict(format!("{missing} not found")),
+                Err(reason) => return conflict(reason),
+            }
+        }
+        _ => {}
+    }
+
+    (Status::Rebased, Some(new_operation), None)
+}
+
+/// Translates json pointer from old document to new one
+fn locate(old_doc: &Value, new_doc: &Value, path: &str, insert: bool) -> Result<Location, String> {
+    let segments = path
+        .split('/')
+        .skip(1)
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>();
+
+    let mut old_node = old_doc;
+    let mut new_node = new_doc;
+    let mut result = vec![];
+
+    for (i, segment) in segments.iter().enumerate() {
+        let last = i == segments.len() - 1;
+
+        match (old_node, new_node) {
+            (Value::Object(old_map), Value::Object(new_map)) => {
+                result.push(segment.clone());
+
+                if last {
+                    break;
+                }
+
+                match (old_map.get(segment), new_map.get(segment)) {
+                    (Some(o), Some(n)) => {
+                        old_node = o;
+                        new_node = n;
+                    }
+                    (None, _) => return Err(format!("{path} not found in old base")),
+                    (_, None) => return Ok(Location::Missing(pointer(&result))),
+                }
+            }
+            (Value::Array(old_items), Value::Array(new_items)) => {
+                if last && insert && segment == "-" {
+                    result.push(segment.clone());
+                    break;
+                }
+
+                let index = segment
+                    .parse::<usize>()
+                    .map_err(|_| format!("{path} has invalid array index"))?;
+
+                let Some(element) = old_items.get(index) else {
+                    if last && insert && index == old_items.len() {
+                        result.push(new_items.len().to_string());
+                        break;
+                    }
+
+                    return Err(format!("{path} not found in old base"));
+                };
+
+                let Some(position) = find(element, new_items) else {
+                    result.push(segment.clone());
+                    return Ok(Location::Missing(pointer(&result)));
+                };
+
+                result.push(position.to_string());
+
+                old_node = element;
+                new_node = &new_items[position];
+            }
+            (Value::Object(_) | Value::Array(_), _) => {
+                return Err(format!("{} changed type in new base", pointer(&result)))
+            }
+            _ => return Err(format!("{path} not found in old base")),
+        }
+    }
+
+    Ok(Location::Found(result))
+}
+
+/// Finds position of element in new array, equal elements are preferred over identity match
+fn find(element: &Value, items: &[Value]) -> Option<usize> {
+    if let Some(position) = items.iter().position(|i| i == element) {
+        return Some(position);
+    }
+
+    let keys = IDENTITY
+        .iter()
+        .find(|keys| keys.iter().all(|k| element.get(k).is_some()))?;
+
+    let matches = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| keys.iter().all(|k| item.get(k) == element.get(k)))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    match matches[..] {
+        [position] => Some(position),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn old_base() -> Value {
+        json!({
+            "info": { "title": "Pets", "version": "1.0.0" },
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "parameters": [
+                            { "name": "limit", "in": "query", "schema": { "type": "string" } },
+                            { "name": "page", "in": "query" }
+                        ]
+                    }
+                }
+            },
+            "tags": ["pets"]
+        })
+    }
+
+    fn new_base() -> Value {
+        json!({
+            "info": { "title": "Pets api", "version": "2.0.0", "description": "Pets" },
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "parameters": [
+                            { "name": "sort", "in": "query" },
+                            { "name": "page", "in": "query" },
+                            { "name": "limit", "in": "query", "schema": { "type": "string" } }
+                        ]
+                    }
+                }
+            },
+            "tags": ["pets", "admin"]
+        })
+    }
+
+    #[test]
+    fn test_rebase() {
+        let operations = json!([
+            { "op": "replace", "path": "/paths/~1pets/get/parameters/0/schema/type", "value": "integer" },
+            { "op": "remove", "path": "/paths/~1pets/get/parameters/1" },
+            { "op": "add", "path": "/tags/-", "value": "admin" },
+            { "op": "add", "path": "/info/description", "value": "Our pets" },
+            { "op": "replace", "path": "/info/version", "value": "1.0.1" },
+            { "op": "add", "path": "/paths/~1owners", "value": {} }
+        ]);
+
+        let result = rebase(&old_base(), &new_base(), &operations).unwrap();
+
+        assert_eq!(
+            result.patch,
+            json!([
+                { "op": "replace", "path": "/paths/~1pets/get/parameters/2/schema/type", "value": "integer" },
+                { "op": "remove", "path": "/paths/~1pets/get/parameters/1" },
+                { "op": "add", "path": "/paths/~1owners", "value": {} }
+            ])
+        );
+        assert_eq!(
+            result
+                .operations
+                .iter()
+                .map(|o| o.status)
+                .collect::<Vec<_>>(),
+            vec![
+                Status::Rebased,
+                Status::Rebased,
+                Status::Obsolete,
+                Status::Conflict,
+                Status::Conflict,
+                Status::Rebased
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_element() {
+        let mut new_base = new_base();
+        new_base["paths"]["/pets"]["get"]["parameters"] = json!([]);
+
+        let operations = json!([
+            { "op": "replace", "path": "/paths/~1pets/get/parameters/0/schema/type", "value": "integer" },
+            { "op": "remove", "path": "/paths/~1pets/get/parameters/1" }
+        ]);
+
+        let result = rebase(&old_base(), &new_base, &operations).unwrap();
+
+        assert_eq!(result.patch, json!([]));
+        assert_eq!(result.operations[0].status, Status::Conflict);
+        assert_eq!(result.operations[1].status, Status::Obsolete);
+    }
+}
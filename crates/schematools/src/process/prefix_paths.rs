@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+use url::Url;
+
+use super::diff::escape;
+use crate::{error::Error, schema::Schema};
+
+pub struct Prefixer;
+
+pub struct PrefixerOptions {
+    pub prefix: String,
+    pub strip_servers: bool,
+}
+
+impl PrefixerOptions {
+    pub fn with_strip_servers(&mut self, value: bool) -> &mut Self {
+        self.strip_servers = value;
+        self
+    }
+
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        let prefix = normalize_prefix(&self.prefix);
+        if prefix.is_empty() {
+            log::warn!("empty prefix, skipping");
+            return Ok(());
+        }
+
+        let root = schema
+            .get_body_mut()
+            .as_object_mut()
+            .ok_or(Error::InvalidOpenapiSchemaError)?;
+
+        let mut renamed = HashMap::new();
+
+        if let Some(Value::Object(paths)) = root.get_mut("paths") {
+            let original = std::mem::take(paths);
+
+            for (path, item) in original {
+                let prefixed = prefix_path(&prefix, &path);
+
+                log::debug!("/paths/{} -> {}", escape(&path), prefixed);
+
+                renamed.insert(escape(&path), escape(&prefixed));
+                paths.insert(prefixed, item);
+            }
+        }
+
+        if self.strip_servers {
+            strip_servers(root, &prefix);
+        }
+
+        // internal references pointing to path items have to follow renamed keys
+        rename_path_references(schema.get_body_mut(), &renamed);
+
+        Ok(())
+    }
+}
+
+impl Prefixer {
+    pub fn options(prefix: String) -> PrefixerOptions {
+        PrefixerOptions {
+            prefix,
+            strip_servers: false,
+        }
+    }
+}
+
+pub fn prefix_path(prefix: &str, path: &str) -> String {
+    let prefix = normalize_prefix(prefix);
+    let path = path.trim_start_matches('/');
+
+    if path.is_empty() {
+        if prefix.is_empty() {
+            "/".to_string()
+        } else {
+            prefix
+        }
+    } else {
+        format!("{prefix}/{path}")
+    }
+}
+
+fn normalize_prefix(prefix: &str) -> String {
+    let trimmed = prefix.trim().trim_matches('/');
+
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{trimmed}")
+    }
+}
+
+fn strip_servers(root: &mut Map<String, Value>, prefix: &str) {
+    strip_servers_list(root.get_mut("servers"), prefix, "/servers");
+
+    if let Some(Value::Object(paths)) = root.get_mut("paths") {
+        for (path, item) in paths.iter_mut() {
+            if let Value::Object(item) = item {
+                strip_servers_list(
+                    item.get_mut("servers"),
+                    prefix,
+                    &format!("/paths/{}/servers", escape(path)),
+                );
+
+                for (method, operation) in item.iter_mut() {
+                    if let Value::Object(operation) = operation {
+                        strip_servers_list(
+                            operation.get_mut("servers"),
+                            prefix,
+                            &format!("/paths/{}/{method}/servers", escape(path)),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn strip_servers_list(servers: Option<&mut Value>, prefix: &str, pointer: &str) {
+    if let Some(Value::Array(servers)) = servers {
+        for server in servers.iter_mut() {
+            if let Some(Value::String(url)) = server.get_mut("url") {
+                if let Some(stripped) = strip_base_path(url, prefix) {
+                    log::debug!("{}: {} -> {}", pointer, url, stripped);
+                    *url = stripped;
+                }
+            }
+        }
+    }
+}
+
+/// Removes prefix from the end of server url path, returns None if url doesn't end with prefix
+fn strip_base_path(url: &str, prefix: &str) -> Option<String> {
+    match Url::parse(url) {
+        Ok(mut parsed) if parsed.has_host() => {
+            let path = parsed.path().trim_end_matches('/').to_string();
+            let stripped = path.strip_suffix(prefix)?;
+
+            parsed.set_path(stripped);
+            Some(parsed.to_string().trim_end_matches('/').to_string())
+        }
+        // relative server urls, ex. /billing/v1 or url templates with variables
+        _ => {
+            let trimmed = url.trim_end_matches('/');
+            let stripped = trimmed.strip_suffix(prefix)?;
+
+            Some(if stripped.is_empty() {
+                "/".to_string()
+            } else {
+                stripped.to_string()
+            })
+        }
+    }
+}
+
+/// Rewrites each reference to path item once, renamed maps escaped old keys to new ones
+fn rename_path_references(node: &mut Value, renamed: &HashMap<String, String>) {
+    match node {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                if let Some(rest) = reference.strip_prefix("#/paths/") {
+                    let (key, tail) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+
+                    if let Some(to) = renamed.get(key) {
+                        *reference = format!("#/paths/{to}{tail}");
+                    }
+                }
+            } else {
+                for (_, value) in map.iter_mut() {
+                    rename_path_references(value, renamed);
+                }
+            }
+        }
+        Value::Array(a) => {
+            for x in a.iter_mut() {
+                rename_path_references(x, renamed);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use test_case::test_case;
+
+    #[test_case("/billing", "/invoices", "/billing/invoices")]
+    #[test_case("billing/", "/invoices/{id}", "/billing/invoices/{id}")]
+    #[test_case("/billing", "/", "/billing")]
+    #[test_case("/", "/invoices", "/invoices")]
+    fn test_prefix_path(prefix: &str, path: &str, expected: &str) {
+        assert_eq!(prefix_path(prefix, path), expected);
+    }
+
+    #[test_case("https://api.example.com/billing", "https://api.example.com")]
+    #[test_case("https://api.example.com/v1/billing/", "https://api.example.com/v1")]
+    #[test_case("/billing", "/")]
+    #[test_case("{scheme}://{host}/billing", "{scheme}://{host}")]
+    fn test_strip_base_path(url: &str, expected: &str) {
+        assert_eq!(strip_base_path(url, "/billing"), Some(expected.to_string()));
+    }
+
+    #[test]
+    fn test_strip_base_path_not_matching() {
+        assert_eq!(
+            strip_base_path("https://api.example.com/users", "/billing"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prefix_paths() {
+        let value = json!({
+            "servers": [
+                { "url": "https://api.example.com/billing" },
+                { "url": "https://sandbox.example.com" }
+            ],
+            "paths": {
+                "/invoices": {
+                    "get": { "operationId": "listInvoices" }
+                },
+                "/invoices/{id}": {
+                    "servers": [{ "url": "https://eu.example.com/billing/" }],
+                    "get": { "operationId": "getInvoice" }
+                },
+                "/invoices/{id}/lines": {
+                    "$ref": "#/paths/~1invoices~1{id}"
+                }
+            }
+        });
+
+        let expected = json!({
+            "servers": [
+                { "url": "https://api.example.com" },
+                { "url": "https://sandbox.example.com" }
+            ],
+            "paths": {
+                "/billing/invoices": {
+                    "get": { "operationId": "listInvoices" }
+                },
+                "/billing/invoices/{id}": {
+                    "servers": [{ "url": "https://eu.example.com" }],
+                    "get": { "operationId": "getInvoice" }
+                },
+                "/billing/invoices/{id}/lines": {
+                    "$ref": "#/paths/~1billing~1invoices~1{id}"
+                }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        Prefixer::options("/billing".to_string())
+            .with_strip_servers(true)
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_prefix_paths_leaves_servers() {
+        let value = json!({
+            "servers": [{ "url": "https://api.example.com/billing" }],
+            "paths": {
+                "/invoices": {}
+            }
+        });
+
+        let expected = json!({
+            "servers": [{ "url": "https://api.example.com/billing" }],
+            "paths": {
+                "/billing/invoices": {}
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        Prefixer::options("billing".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_prefix_paths_references_rewritten_once() {
+        let value = json!({
+            "paths": {
+                "/invoices": {
+                    "get": { "operationId": "listInvoices" }
+                },
+                "/billing/invoices": {
+                    "$ref": "#/paths/~1invoices"
+                },
+                "/archive": {
+                    "get": {
+                        "responses": { "$ref": "#/paths/~1billing~1invoices/get/responses" }
+                    }
+                }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        Prefixer::options("/billing".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        let paths = schema.get_body().get("paths").unwrap();
+
+        assert_eq!(
+            paths.pointer("/~1billing~1billing~1invoices/$ref"),
+            Some(&json!("#/paths/~1billing~1invoices"))
+        );
+        assert_eq!(
+            paths.pointer("/~1billing~1archive/get/responses/$ref"),
+            Some(&json!("#/paths/~1billing~1billing~1invoices/get/responses"))
+        );
+    }
+}
@@ -0,0 +1,492 @@
+use std::collections::HashSet;
+
+use serde_json::{Map, Value};
+
+use super::components::{self, Component};
+use super::diff::{escape, METHODS};
+use super::filter::remove_unused_tags;
+use crate::{error::Error, schema::Schema};
+
+/// Values which are data, not parts of specification
+const DATA_KEYS: [&str; 4] = ["example", "default", "enum", "const"];
+
+/// Maps keyed by names, their keys are never keywords (ex. property or response named `default`)
+const NAME_MAPS: [&str; 14] = [
+    "properties",
+    "patternProperties",
+    "dependentSchemas",
+    "$defs",
+    "definitions",
+    "responses",
+    "schemas",
+    "parameters",
+    "headers",
+    "requestBodies",
+    "securitySchemes",
+    "links",
+    "callbacks",
+    "pathItems",
+];
+
+pub struct Publisher;
+
+pub struct PublisherOptions {
+    pub audience: String,
+    pub internal_key: String,
+    pub audience_key: String,
+}
+
+impl PublisherOptions {
+    /// Audience which specification is published for, ex. `partner`
+    pub fn with_audience(&mut self, value: String) -> &mut Self {
+        self.audience = value;
+        self
+    }
+
+    /// Extension marking elements hidden from every audience, `x-internal` by default
+    pub fn with_internal_key(&mut self, value: String) -> &mut Self {
+        self.internal_key = value;
+        self
+    }
+
+    /// Extension listing audiences element is visible to, `x-audience` by default
+    pub fn with_audience_key(&mut self, value: String) -> &mut Self {
+        self.audience_key = value;
+        self
+    }
+
+    /// Removes operations, parameters, properties, components and tags not visible to audience,
+    /// components which are no longer used are removed too
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        let body = schema.get_body_mut();
+
+        if !body.is_object() {
+            return Err(Error::InvalidOpenapiSchemaError);
+        }
+
+        let used = components::used(body);
+
+        // references to hidden components are removed together with them
+        let mut removed = HashSet::new();
+
+        if let Some(Value::Object(components)) = body.get_mut("components") {
+            components.retain(|kind, entries| {
+                let Value::Object(entries) = entries else {
+                    return true;
+                };
+                let size = entries.len();
+
+                entries.retain(|name, entry| {
+                    let visible = self.is_visible(entry);
+
+                    if !visible {
+                        let component = Component::new(kind, name);
+                        log::info!("{}: removed", component.pointer());
+                        removed.insert(format!("#{}", component.pointer()));
+                    }
+
+                    visible
+                });
+
+                !entries.is_empty() || size == 0
+            });
+        }
+
+        self.walk(body, "", &removed, false);
+
+        let root = body.as_object_mut().unwrap();
+
+        for key in ["paths", "webhooks"] {
+            if let Some(Value::Object(items)) = root.get_mut(key) {
+                items.retain(|path, item| {
+                    let keep = item.get("$ref").is_some()
+                        || item
+                            .as_object()
+                            .map(|i| i.keys().any(|k| METHODS.contains(&k.as_str())))
+                            .unwrap_or(true);
+
+                    if !keep {
+                        log::info!("/{}/{}: removed, no operations left", key, escape(path));
+                    }
+
+                    keep
+                });
+            }
+        }
+
+        remove_unused_tags(root);
+
+        // components which were unused before are left as they are
+        let now_used = components::used(body);
+
+        for component in components::remove(body, &now_used, |c| !used.contains(c)) {
+            log::info!("{}: removed, no longer used", component.pointer());
+        }
+
+        Ok(())
+    }
+
+    fn is_visible(&self, node: &Value) -> bool {
+        let Value::Object(map) = node else {
+            return true;
+        };
+
+        if map.get(&self.internal_key) == Some(&Value::Bool(true)) {
+            return false;
+        }
+
+        match map.get(&self.audience_key) {
+            Some(Value::String(audience)) => *audience == self.audience,
+            Some(Value::Array(audiences)) => audiences.iter().any(|a| a == &self.audience),
+            _ => true,
+        }
+    }
+
+    fn is_removed(&self, node: &Value, removed: &HashSet<String>) -> bool {
+        match node.get("$ref").and_then(Value::as_str) {
+            Some(reference) => is_removed_ref(reference, removed),
+            None => !self.is_visible(node),
+        }
+    }
+
+    /// Removes hidden entries, returns names of removed object keys,
+    /// `names` tells that keys of node are names rather than keywords
+    fn walk(
+        &self,
+        node: &mut Value,
+        pointer: &str,
+        removed: &HashSet<String>,
+        names: bool,
+    ) -> Vec<String> {
+        let mut result = vec![];
+
+        match node {
+            Value::Object(map) => {
+                let is_data = |key: &str| !names && DATA_KEYS.contains(&key);
+
+                map.retain(|key, value| {
+                    let keep = is_data(key) || !self.is_removed(value, removed);
+
+                    if !keep {
+                        log::info!("{}/{}: removed", pointer, escape(key));
+                        result.push(key.clone());
+                    }
+
+                    keep
+                });
+
+                let mut removed_properties = vec![];
+
+                for (key, value) in map.iter_mut() {
+                    if is_data(key) {
+                        continue;
+                    }
+
+                    let at = format!("{}/{}", pointer, escape(key));
+
+                    if names {
+                        self.walk(value, &at, removed, false);
+                        continue;
+                    }
+
+                    match key.as_str() {
+                        "discriminator" => remove_mappings(value, removed),
+                        "security" => remove_security(value, removed),
+                        _ => {}
+                    }
+
+                    let removed_keys =
+                        self.walk(value, &at, removed, NAME_MAPS.contains(&key.as_str()));
+
+                    if key == "properties" {
+                        removed_properties = removed_keys;
+                    }
+                }
+
+                if names {
+                    return result;
+                }
+
+                if let Some(Value::Array(required)) = map.get_mut("required") {
+                    required.retain(|name| {
+                        !name
+                            .as_str()
+                            .map(|n| removed_properties.iter().any(|p| p == n))
+                            .unwrap_or(false)
+                    });
+
+                    if required.is_empty() {
+                        map.shift_remove("required");
+                    }
+                }
+
+                map.shift_remove(&self.internal_key);
+                map.shift_remove(&self.audience_key);
+            }
+            Value::Array(items) => {
+                let mut index = 0;
+
+                items.retain(|item| {
+                    let keep = !self.is_removed(item, removed);
+
+                    if !keep {
+                        log::info!("{}/{}: removed", pointer, index);
+                    }
+
+                    index += 1;
+                    keep
+                });
+
+                for (i, item) in items.iter_mut().enumerate() {
+                    self.walk(item, &format!("{pointer}/{i}"), removed, false);
+                }
+            }
+            _ => {}
+        }
+
+        result
+    }
+}
+
+impl Publisher {
+    pub fn options() -> PublisherOptions {
+        PublisherOptions {
+            audience: String::new(),
+            internal_key: "x-internal".to_string(),
+            audience_key: "x-audience".to_string(),
+        }
+    }
+}
+
+fn is_removed_ref(reference: &str, removed: &HashSet<String>) -> bool {
+    removed.contains(reference)
+        || removed
+            .iter()
+            .any(|r| reference.starts_with(&format!("{r}/")))
+}
+
+fn remove_mappings(discriminator: &mut Value, removed: &HashSet<String>) {
+    if let Some(Value::Object(mapping)) = discriminator.get_mut("mapping") {
+        mapping.retain(|_, reference| {
+            !reference
+                .as_str()
+                .map(|r| is_removed_ref(r, removed))
+                .unwrap_or(false)
+        });
+    }
+}
+
+/// Requirements using hidden security schemes cannot be fulfilled
+fn remove_security(security: &mut Value, removed: &HashSet<String>) {
+    if let Value::Array(requirements) = security {
+        requirements.retain(|requirement| {
+            requirement
+                .as_object()
+                .map(|r: &Map<String, Value>| {
+                    r.keys().all(|name| {
+                        !removed.contains(&format!("#/components/securitySchemes/{}", escape(name)))
+                    })
+                })
+                .unwrap_or(true)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "tags": [{ "name": "pets" }, { "name": "admin", "x-internal": true }],
+            "security": [{ "apiKey": [] }, { "internal": [] }],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "tags": ["pets"],
+                        "parameters": [
+                            { "name": "limit", "in": "query" },
+                            { "name": "debug", "in": "query", "x-internal": true },
+                            { "name": "region", "in": "query", "x-audience": ["partner", "internal"] },
+                            { "$ref": "#/components/parameters/Trace" }
+                        ],
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Pet" }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    "delete": {
+                        "tags": ["admin"],
+                        "x-audience": "internal",
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Audit" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/admin": {
+                    "x-internal": true,
+                    "get": { "tags": ["admin"] }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "Trace": { "name": "trace", "in": "header", "x-internal": true }
+                },
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "required": ["name", "cost"],
+                        "properties": {
+                            "name": { "type": "string" },
+                            "cost": { "type": "number", "x-audience": "internal" },
+                            "owner": { "$ref": "#/components/schemas/Owner" }
+                        }
+                    },
+                    "Owner": { "type": "object", "x-internal": true },
+                    "Audit": { "type": "object" },
+                    "Legacy": { "type": "object" }
+                },
+                "securitySchemes": {
+                    "apiKey": { "type": "apiKey", "in": "header", "name": "key" },
+                    "internal": { "type": "http", "scheme": "basic", "x-internal": true }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_publish() {
+        let mut schema = Schema::from_json(spec());
+
+        Publisher::options()
+            .with_audience("partner".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        let expected = json!({
+            "openapi": "3.0.3",
+            "tags": [{ "name": "pets" }],
+            "security": [{ "apiKey": [] }],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "tags": ["pets"],
+                        "parameters": [
+                            { "name": "limit", "in": "query" },
+                            { "name": "region", "in": "query" }
+                        ],
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Pet" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "name": { "type": "string" }
+                        }
+                    },
+                    "Legacy": { "type": "object" }
+                },
+                "securitySchemes": {
+                    "apiKey": { "type": "apiKey", "in": "header", "name": "key" }
+                }
+            }
+        });
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_publish_internal_audience() {
+        let mut schema = Schema::from_json(spec());
+
+        Publisher::options()
+            .with_audience("internal".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        let body = schema.get_body();
+
+        assert!(body.pointer("/paths/~1pets/delete").is_some());
+        assert!(body.pointer("/components/schemas/Audit").is_some());
+        assert!(body
+            .pointer("/components/schemas/Pet/properties/cost")
+            .is_some());
+        // x-internal hides elements from every audience
+        assert!(body.pointer("/paths/~1admin").is_none());
+        assert!(body.pointer("/components/schemas/Owner").is_none());
+    }
+
+    #[test]
+    fn test_publish_keyword_named_entries() {
+        let mut schema = Schema::from_json(json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "responses": {
+                            "200": { "description": "Ok" },
+                            "default": { "description": "Debug", "x-internal": true }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "required": ["default"],
+                        "properties": {
+                            "name": { "type": "string", "default": { "x-internal": true } },
+                            "default": { "type": "boolean", "x-internal": true }
+                        }
+                    }
+                }
+            }
+        }));
+
+        Publisher::options()
+            .with_audience("partner".to_string())
+            .process(&mut schema)
+            .unwrap();
+
+        let body = schema.get_body();
+
+        assert!(body
+            .pointer("/paths/~1pets/get/responses/default")
+            .is_none());
+        assert_eq!(
+            body.pointer("/components/schemas/Pet"),
+            Some(&json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "default": { "x-internal": true } }
+                }
+            }))
+        );
+    }
+}
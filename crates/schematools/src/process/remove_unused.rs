@@ -0,0 +1,130 @@
+use super::components::{self, Component};
+use crate::{error::Error, schema::Schema, storage::SchemaStorage, tools};
+
+pub struct Cleaner;
+
+pub struct CleanerOptions {
+    pub keep: Vec<String>,
+    pub report: bool,
+}
+
+impl CleanerOptions {
+    /// Glob patterns of components which are never removed, matched against `kind/name` and `name`
+    pub fn with_keep(&mut self, value: Vec<String>) -> &mut Self {
+        self.keep = value;
+        self
+    }
+
+    /// Only reports unused components, schema is left intact
+    pub fn with_report(&mut self, value: bool) -> &mut Self {
+        self.report = value;
+        self
+    }
+
+    /// Removes components unreachable from paths, webhooks and security, returns removed ones
+    pub fn process(
+        &self,
+        schema: &mut Schema,
+        storage: &SchemaStorage,
+    ) -> Result<Vec<Component>, Error> {
+        let keep = self
+            .keep
+            .iter()
+            .map(|p| tools::glob(p))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let used = components::used_with_storage(schema, storage);
+
+        let is_kept = |component: &Component| {
+            keep.iter()
+                .any(|k| k.is_match(&component.to_string()) || k.is_match(&component.name))
+        };
+
+        let removed = if self.report {
+            components::remove(&mut schema.get_body().clone(), &used, is_kept)
+        } else {
+            components::remove(schema.get_body_mut(), &used, is_kept)
+        };
+
+        for component in removed.iter() {
+            if self.report {
+                log::info!("{}: unused", component.pointer());
+            } else {
+                log::info!("{}: removed", component.pointer());
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+impl Cleaner {
+    pub fn options() -> CleanerOptions {
+        CleanerOptions {
+            keep: vec![],
+            report: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use serde_json::Value;
+
+    fn spec() -> Schema {
+        let url = url::Url::parse(&format!(
+            "file://{}/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            "resources/test/openapi/02-external-components.yaml"
+        ))
+        .unwrap();
+
+        Schema::load_url(url).unwrap()
+    }
+
+    #[test]
+    fn test_remove_unused() {
+        let mut schema = spec();
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        let removed = Cleaner::options()
+            .with_keep(vec!["schemas/Leg*".to_string()])
+            .process(&mut schema, &storage)
+            .unwrap();
+
+        assert_eq!(removed, vec![Component::new("schemas", "Unused")]);
+        assert_eq!(
+            schema
+                .get_body()
+                .pointer("/components/schemas")
+                .and_then(Value::as_object)
+                .map(|s| s.keys().cloned().collect::<Vec<_>>()),
+            Some(vec!["Owner".to_string(), "Legacy".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let mut schema = spec();
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+        let original = schema.get_body().clone();
+
+        let removed = Cleaner::options()
+            .with_report(true)
+            .process(&mut schema, &storage)
+            .unwrap();
+
+        assert_eq!(
+            removed,
+            vec![
+                Component::new("schemas", "Unused"),
+                Component::new("schemas", "Legacy")
+            ]
+        );
+        assert_eq!(schema.get_body(), &original);
+    }
+}
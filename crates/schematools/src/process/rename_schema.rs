@@ -0,0 +1,419 @@
+use serde_json::{Map, Value};
+use url::Url;
+
+use super::components::{Component, KINDS};
+use crate::storage::{ref_to_url, SchemaStorage};
+use crate::{error::Error, schema::Schema};
+
+pub struct Renamer;
+
+pub struct RenamerOptions {
+    pub renames: Vec<(Component, String)>,
+}
+
+impl RenamerOptions {
+    /// Pairs of old and new names, old one can be prefixed with kind (ex. `responses/Error`),
+    /// `schemas` is assumed otherwise
+    pub fn with_renames(&mut self, value: Vec<(String, String)>) -> &mut Self {
+        self.renames = value
+            .into_iter()
+            .map(|(from, to)| (parse_component(&from), to))
+            .collect();
+        self
+    }
+
+    /// Renames components and rewrites references to them in schema and all external files,
+    /// returns external files which were modified
+    pub fn process(
+        &self,
+        schema: &mut Schema,
+        storage: &SchemaStorage,
+    ) -> Result<Vec<Schema>, Error> {
+        let target = without_fragment(schema.get_url());
+
+        let mut urls = storage
+            .schemas
+            .keys()
+            .filter(|url| without_fragment(url) != target && url.scheme() == "file")
+            .cloned()
+            .collect::<Vec<_>>();
+        urls.sort();
+
+        let mut externals = urls
+            .into_iter()
+            .map(|url| Ok((Schema::load_url(url)?, false)))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        for (from, to) in self.renames.iter() {
+            rename_component(schema.get_body_mut(), from, to)?;
+            log::info!("{}: renamed to {}", from.pointer(), to);
+
+            let url = schema.get_url().clone();
+            rewrite(schema.get_body_mut(), &url, &target, from, to);
+
+            for (external, modified) in externals.iter_mut() {
+                let url = external.get_url().clone();
+
+                if rewrite(external.get_body_mut(), &url, &target, from, to) > 0 {
+                    log::info!("{}: references to {} rewritten", url, from);
+                    *modified = true;
+                }
+            }
+        }
+
+        Ok(externals
+            .into_iter()
+            .filter(|(_, modified)| *modified)
+            .map(|(external, _)| external)
+            .collect())
+    }
+}
+
+impl Renamer {
+    pub fn options() -> RenamerOptions {
+        RenamerOptions { renames: vec![] }
+    }
+}
+
+fn parse_component(name: &str) -> Component {
+    match name.split_once('/') {
+        Some((kind, name)) if KINDS.contains(&kind) => Component::new(kind, name),
+        _ => Component::new("schemas", name),
+    }
+}
+
+fn without_fragment(url: &Url) -> Url {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url
+}
+
+/// Replaces key in components section keeping its position
+fn rename_component(root: &mut Value, from: &Component, to: &str) -> Result<(), Error> {
+    let Some(Value::Object(components)) = root.pointer_mut(&format!("/components/{}", from.kind))
+    else {
+        return Err(Error::ComponentRenameInvalid(format!("{from} not found")));
+    };
+
+    if !components.contains_key(&from.name) {
+        return Err(Error::ComponentRenameInvalid(format!("{from} not found")));
+    }
+
+    if from.name != to && components.contains_key(to) {
+        return Err(Error::ComponentRenameInvalid(format!(
+            "{}/{} already exists",
+            from.kind, to
+        )));
+    }
+
+    *components = std::mem::take(components)
+        .into_iter()
+        .map(|(key, value)| {
+            if key == from.name {
+                (to.to_string(), value)
+            } else {
+                (key, value)
+            }
+        })
+        .collect::<Map<_, _>>();
+
+    Ok(())
+}
+
+/// Rewrites references to component within single document, returns number of changes
+pub(crate) fn rewrite_refs(root: &mut Value, url: &Url, from: &Component, to: &str) -> usize {
+    let target = without_fragment(url);
+
+    rewrite(root, url, &target, from, to)
+}
+
+/// Rewrites `$ref`s and discriminator mappings pointing to component, returns number of changes
+fn rewrite(node: &mut Value, base: &Url, target: &Url, from: &Component, to: &str) -> usize {
+    match node {
+        Value::Object(map) => {
+            let mut count = 0;
+
+            if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                if let Some(new) = rewrite_ref(reference, base, target, from, to) {
+                    *reference = new;
+                    count += 1;
+                }
+            }
+
+            if let Some(Value::Object(mapping)) = map
+                .get_mut("discriminator")
+                .and_then(|d| d.get_mut("mapping"))
+            {
+                for value in mapping.values_mut() {
+                    let Value::String(reference) = value else {
+                        continue;
+                    };
+
+                    // bare names in mapping are implicit references to schemas of the same document
+                    let new = if from.kind == "schemas"
+                        && *reference == from.name
+                        && without_fragment(base) == *target
+                    {
+                        Some(to.to_string())
+                    } else {
+                        rewrite_ref(reference, base, target, from, to)
+                    };
+
+                    if let Some(new) = new {
+                        *reference = new;
+                        count += 1;
+                    }
+                }
+            }
+
+            count
+                + map
+                    .iter_mut()
+                    .filter(|(key, _)| *key != "$ref" && *key != "discriminator")
+                    .map(|(_, value)| rewrite(value, base, target, from, to))
+                    .sum::<usize>()
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .map(|item| rewrite(item, base, target, from, to))
+            .sum(),
+        _ => 0,
+    }
+}
+
+/// Returns new reference when given one points to component in target document (or its part),
+/// relative file part is kept as it is
+fn rewrite_ref(
+    reference: &str,
+    base: &Url,
+    target: &Url,
+    from: &Component,
+    to: &str,
+) -> Option<String> {
+    let (file, fragment) = reference.split_once('#')?;
+
+    let url = if file.is_empty() {
+        without_fragment(base)
+    } else {
+        without_fragment(&ref_to_url(base, file)?)
+    };
+
+    if &url != target {
+        return None;
+    }
+
+    let rest = fragment.strip_prefix(&from.pointer())?;
+
+    if !rest.is_empty() && !rest.starts_with('/') {
+        return None;
+    }
+
+    Some(format!(
+        "{}#{}{}",
+        file,
+        Component::new(&from.kind, to).pointer(),
+        rest
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use serde_json::json;
+
+    #[test]
+    fn test_rename() {
+        let mut schema = Schema::from_json(json!({
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "parameters": [{ "$ref": "#/components/parameters/Limit" }],
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Pet" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "Limit": { "name": "limit", "in": "query" }
+                },
+                "schemas": {
+                    "Pet": {
+                        "oneOf": [
+                            { "$ref": "#/components/schemas/Cat" },
+                            { "$ref": "#/components/schemas/Dog" }
+                        ],
+                        "discriminator": {
+                            "propertyName": "kind",
+                            "mapping": {
+                                "cat": "#/components/schemas/Cat",
+                                "dog": "Dog"
+                            }
+                        }
+                    },
+                    "Cat": { "type": "object" },
+                    "Dog": {
+                        "type": "object",
+                        "properties": {
+                            "friend": { "$ref": "#/components/schemas/Cat/properties/name" },
+                            "category": { "$ref": "#/components/schemas/DogCategory" }
+                        }
+                    },
+                    "DogCategory": { "type": "string" }
+                }
+            }
+        }));
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        let externals = Renamer::options()
+            .with_renames(vec![
+                ("Cat".to_string(), "Kitty".to_string()),
+                ("Dog".to_string(), "Puppy".to_string()),
+                ("parameters/Limit".to_string(), "PageLimit".to_string()),
+            ])
+            .process(&mut schema, &storage)
+            .unwrap();
+
+        let expected = json!({
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "parameters": [{ "$ref": "#/components/parameters/PageLimit" }],
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Pet" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "PageLimit": { "name": "limit", "in": "query" }
+                },
+                "schemas": {
+                    "Pet": {
+                        "oneOf": [
+                            { "$ref": "#/components/schemas/Kitty" },
+                            { "$ref": "#/components/schemas/Puppy" }
+                        ],
+                        "discriminator": {
+                            "propertyName": "kind",
+                            "mapping": {
+                                "cat": "#/components/schemas/Kitty",
+                                "dog": "Puppy"
+                            }
+                        }
+                    },
+                    "Kitty": { "type": "object" },
+                    "Puppy": {
+                        "type": "object",
+                        "properties": {
+                            "friend": { "$ref": "#/components/schemas/Kitty/properties/name" },
+                            "category": { "$ref": "#/components/schemas/DogCategory" }
+                        }
+                    },
+                    "DogCategory": { "type": "string" }
+                }
+            }
+        });
+
+        assert!(externals.is_empty());
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_rename_external_refs() {
+        let url = Url::parse(&format!(
+            "file://{}/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            "resources/test/openapi/02-external-components.yaml"
+        ))
+        .unwrap();
+        let mut schema = Schema::load_url(url).unwrap();
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        let externals = Renamer::options()
+            .with_renames(vec![("Owner".to_string(), "PetOwner".to_string())])
+            .process(&mut schema, &storage)
+            .unwrap();
+
+        assert_eq!(externals.len(), 1);
+        assert_eq!(
+            externals[0]
+                .get_body()
+                .pointer("/Pet/properties/owner/$ref"),
+            Some(&json!(
+                "./02-external-components.yaml#/components/schemas/PetOwner"
+            ))
+        );
+        assert!(schema
+            .get_body()
+            .pointer("/components/schemas/PetOwner")
+            .is_some());
+    }
+
+    #[test]
+    fn test_rename_bare_mapping_in_external() {
+        let target = Url::parse("file:///specs/openapi.yaml").unwrap();
+        let mut node = json!({
+            "discriminator": {
+                "propertyName": "kind",
+                "mapping": {
+                    "cat": "Cat",
+                    "dog": "./openapi.yaml#/components/schemas/Cat"
+                }
+            }
+        });
+        let from = Component::new("schemas", "Cat");
+
+        let external = Url::parse("file:///specs/pets.yaml").unwrap();
+        assert_eq!(rewrite(&mut node, &external, &target, &from, "Kitty"), 1);
+
+        // bare name in external file refers to its own schemas
+        assert_eq!(
+            node.pointer("/discriminator/mapping"),
+            Some(&json!({
+                "cat": "Cat",
+                "dog": "./openapi.yaml#/components/schemas/Kitty"
+            }))
+        );
+
+        assert_eq!(rewrite(&mut node, &target, &target, &from, "Kitty"), 1);
+        assert_eq!(
+            node.pointer("/discriminator/mapping/cat"),
+            Some(&json!("Kitty"))
+        );
+    }
+
+    #[test]
+    fn test_rename_invalid() {
+        let mut schema = Schema::from_json(json!({
+            "components": { "schemas": { "Cat": {}, "Dog": {} } }
+        }));
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        for (from, to) in [("Cat", "Dog"), ("Bird", "Parrot")] {
+            let result = Renamer::options()
+                .with_renames(vec![(from.to_string(), to.to_string())])
+                .process(&mut schema, &storage);
+
+            assert!(matches!(result, Err(Error::ComponentRenameInvalid(_))));
+        }
+    }
+}
@@ -0,0 +1,480 @@
+use std::collections::HashSet;
+
+use serde_json::{Map, Value};
+use url::Url;
+
+use super::upgrade_openapi::upgrade_exclusive;
+use crate::storage::{ref_to_url, SchemaStorage};
+use crate::{error::Error, schema::Schema};
+
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+const LEGACY_DRAFTS: [&str; 3] = ["draft-04", "draft-06", "draft-07"];
+
+pub struct Upgrader;
+
+pub struct UpgraderOptions {
+    pub dialect: String,
+}
+
+impl UpgraderOptions {
+    pub fn with_dialect(&mut self, value: String) -> &mut Self {
+        self.dialect = value;
+        self
+    }
+
+    /// Upgrades schema, references into documents which are not upgraded by the same run
+    /// (current drafts, remote files) are left untouched
+    pub fn process(&self, schema: &mut Schema, storage: &SchemaStorage) -> Result<(), Error> {
+        let upgraded = upgraded_documents(schema, storage);
+        let url = schema.get_url().clone();
+
+        self.upgrade_document(schema.get_body_mut(), &url, &upgraded);
+
+        Ok(())
+    }
+
+    /// Upgrades local files referenced by schema, files are reloaded so relative references stay intact
+    pub fn process_external(
+        &self,
+        schema: &Schema,
+        storage: &SchemaStorage,
+    ) -> Result<Vec<Schema>, Error> {
+        let mut urls = storage
+            .schemas
+            .keys()
+            .filter(|url| *url != schema.get_url() && url.scheme() == "file")
+            .cloned()
+            .collect::<Vec<_>>();
+        urls.sort();
+
+        let upgraded = upgraded_documents(schema, storage);
+
+        urls.into_iter()
+            .map(|url| {
+                log::info!("{}: upgrading external file", url);
+
+                let mut external = Schema::load_url(url.clone())?;
+                self.upgrade_document(external.get_body_mut(), &url, &upgraded);
+
+                Ok(external)
+            })
+            .collect()
+    }
+
+    fn upgrade_document(&self, root: &mut Value, url: &Url, upgraded: &HashSet<Url>) {
+        if !is_legacy(root) {
+            log::warn!(
+                "{} is not a legacy draft, skipping",
+                root["$schema"].as_str().unwrap_or_default()
+            );
+            return;
+        }
+
+        let Value::Object(map) = root else {
+            return;
+        };
+
+        // documents without $schema are upgraded but not marked
+        if let Some(Value::String(_)) = map.get("$schema") {
+            map.insert("$schema".to_string(), Value::String(self.dialect.clone()));
+        }
+
+        upgrade_node(root, &|file| {
+            ref_to_url(url, file)
+                .map(|u| upgraded.contains(&without_fragment(&u)))
+                .unwrap_or(false)
+        });
+    }
+}
+
+impl Upgrader {
+    pub fn options() -> UpgraderOptions {
+        UpgraderOptions {
+            dialect: DIALECT.to_string(),
+        }
+    }
+}
+
+/// Documents upgraded by single run, main schema and local files in legacy drafts
+fn upgraded_documents(schema: &Schema, storage: &SchemaStorage) -> HashSet<Url> {
+    let mut result = storage
+        .schemas
+        .iter()
+        .filter(|(url, external)| url.scheme() == "file" && is_legacy(external.get_body()))
+        .map(|(url, _)| without_fragment(url))
+        .collect::<HashSet<_>>();
+
+    if is_legacy(schema.get_body()) {
+        result.insert(without_fragment(schema.get_url()));
+    }
+
+    result
+}
+
+/// Documents without `$schema` are treated as legacy ones
+fn is_legacy(root: &Value) -> bool {
+    root.get("$schema")
+        .and_then(Value::as_str)
+        .map(|dialect| LEGACY_DRAFTS.iter().any(|d| dialect.contains(d)))
+        .unwrap_or(true)
+}
+
+fn without_fragment(url: &Url) -> Url {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url
+}
+
+/// Upgrades single schema, only local references are rewritten
+pub fn upgrade_schema(node: &mut Value) {
+    upgrade_node(node, &|_| false);
+}
+
+/// Upgrades schema, `upgraded` tells if file part of reference points to upgraded document
+fn upgrade_node(node: &mut Value, upgraded: &dyn Fn(&str) -> bool) {
+    let Value::Object(map) = node else {
+        return;
+    };
+
+    for key in ["properties", "patternProperties", "definitions", "$defs"] {
+        if let Some(Value::Object(children)) = map.get_mut(key) {
+            for (_, child) in children.iter_mut() {
+                upgrade_node(child, upgraded);
+            }
+        }
+    }
+
+    if let Some(Value::Object(dependencies)) = map.get_mut("dependencies") {
+        for (_, child) in dependencies.iter_mut() {
+            upgrade_node(child, upgraded);
+        }
+    }
+
+    for key in [
+        "items",
+        "additionalItems",
+        "additionalProperties",
+        "contains",
+        "propertyNames",
+        "not",
+        "if",
+        "then",
+        "else",
+        "allOf",
+        "oneOf",
+        "anyOf",
+    ] {
+        match map.get_mut(key) {
+            Some(Value::Array(children)) => {
+                for child in children.iter_mut() {
+                    upgrade_node(child, upgraded);
+                }
+            }
+            Some(child) => upgrade_node(child, upgraded),
+            None => {}
+        }
+    }
+
+    upgrade_id(map);
+    rename_key(map, "definitions", "$defs");
+    upgrade_exclusive(map, "exclusiveMinimum", "minimum");
+    upgrade_exclusive(map, "exclusiveMaximum", "maximum");
+    upgrade_items(map);
+    upgrade_dependencies(map);
+    upgrade_ref(map, upgraded);
+}
+
+/// Renames key of object keeping its position
+fn rename_key(map: &mut Map<String, Value>, from: &str, to: &str) {
+    if !map.contains_key(from) || map.contains_key(to) {
+        return;
+    }
+
+    *map = std::mem::take(map)
+        .into_iter()
+        .map(|(key, value)| {
+            if key == from {
+                (to.to_string(), value)
+            } else {
+                (key, value)
+            }
+        })
+        .collect();
+}
+
+/// Draft-04 `id`, plain name fragments become `$anchor`
+fn upgrade_id(map: &mut Map<String, Value>) {
+    let Some(Value::String(id)) = map.get("id") else {
+        return;
+    };
+
+    match id.strip_prefix('#') {
+        Some(anchor) if !anchor.is_empty() && !anchor.starts_with('/') => {
+            let anchor = Value::String(anchor.to_string());
+            rename_key(map, "id", "$anchor");
+            map.insert("$anchor".to_string(), anchor);
+        }
+        _ => rename_key(map, "id", "$id"),
+    }
+}
+
+fn upgrade_items(map: &mut Map<String, Value>) {
+    match map.get("items") {
+        Some(Value::Array(_)) => {
+            rename_key(map, "items", "prefixItems");
+
+            if let Some(additional) = map.shift_remove("additionalItems") {
+                map.insert("items".to_string(), additional);
+            }
+        }
+        // additionalItems without array items has no meaning
+        _ => {
+            map.shift_remove("additionalItems");
+        }
+    }
+}
+
+fn upgrade_dependencies(map: &mut Map<String, Value>) {
+    let Some(Value::Object(dependencies)) = map.shift_remove("dependencies") else {
+        return;
+    };
+
+    let (required, schemas): (Map<_, _>, Map<_, _>) = dependencies
+        .into_iter()
+        .partition(|(_, dependency)| dependency.is_array());
+
+    if !required.is_empty() {
+        map.insert("dependentRequired".to_string(), Value::Object(required));
+    }
+    if !schemas.is_empty() {
+        map.insert("dependentSchemas".to_string(), Value::Object(schemas));
+    }
+}
+
+fn upgrade_ref(map: &mut Map<String, Value>, upgraded: &dyn Fn(&str) -> bool) {
+    if let Some(Value::String(reference)) = map.get_mut("$ref") {
+        if let Some(rewritten) = rewrite_ref(reference, upgraded) {
+            *reference = rewritten;
+        }
+    }
+}
+
+/// Rewrites `definitions` segments of reference fragment to `$defs`, references to documents
+/// which are not upgraded still point to `definitions`
+fn rewrite_ref(reference: &str, upgraded: &dyn Fn(&str) -> bool) -> Option<String> {
+    let (url, fragment) = reference.split_once('#')?;
+
+    if !url.is_empty() && !upgraded(url) {
+        return None;
+    }
+
+    let mut previous = "";
+    let segments = fragment
+        .split('/')
+        .map(|segment| {
+            let result = if segment == "definitions" && previous != "properties" {
+                "$defs"
+            } else {
+                segment
+            };
+            previous = segment;
+
+            result
+        })
+        .collect::<Vec<_>>();
+
+    Some(format!("{url}#{}", segments.join("/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use serde_json::json;
+    use test_case::test_case;
+
+    #[test_case(
+        json!({"id": "http://example.com/a.json", "type": "string"}),
+        json!({"$id": "http://example.com/a.json", "type": "string"})
+    )]
+    #[test_case(
+        json!({"id": "#address", "type": "object"}),
+        json!({"$anchor": "address", "type": "object"})
+    )]
+    #[test_case(
+        json!({"type": "number", "minimum": 0, "exclusiveMinimum": true}),
+        json!({"type": "number", "exclusiveMinimum": 0})
+    )]
+    #[test_case(
+        json!({"type": "array", "items": [{"type": "string"}], "additionalItems": false}),
+        json!({"type": "array", "prefixItems": [{"type": "string"}], "items": false})
+    )]
+    #[test_case(
+        json!({"dependencies": {"card": ["billing"], "name": {"required": ["surname"]}}}),
+        json!({"dependentRequired": {"card": ["billing"]}, "dependentSchemas": {"name": {"required": ["surname"]}}})
+    )]
+    #[test_case(
+        json!({"$ref": "other.json#/definitions/Address"}),
+        json!({"$ref": "other.json#/definitions/Address"})
+    )]
+    #[test_case(
+        json!({"$ref": "#/properties/definitions/items"}),
+        json!({"$ref": "#/properties/definitions/items"})
+    )]
+    fn test_upgrade_schema(mut given: Value, expected: Value) {
+        upgrade_schema(&mut given);
+
+        assert_eq!(given.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_upgrade_jsonschema() {
+        let value = json!({
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "id": "http://example.com/event.json",
+            "type": "object",
+            "properties": {
+                "address": { "$ref": "#/definitions/Address" },
+                "id": { "type": "integer" }
+            },
+            "definitions": {
+                "Address": {
+                    "type": "object",
+                    "properties": {
+                        "street": { "type": "string" }
+                    }
+                }
+            }
+        });
+
+        let expected = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$id": "http://example.com/event.json",
+            "type": "object",
+            "properties": {
+                "address": { "$ref": "#/$defs/Address" },
+                "id": { "type": "integer" }
+            },
+            "$defs": {
+                "Address": {
+                    "type": "object",
+                    "properties": {
+                        "street": { "type": "string" }
+                    }
+                }
+            }
+        });
+
+        let mut schema = Schema::from_json(value);
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        Upgrader::options().process(&mut schema, &storage).unwrap();
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_skip_current_draft() {
+        let value = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "definitions": {}
+        });
+
+        let mut schema = Schema::from_json(value.clone());
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        Upgrader::options().process(&mut schema, &storage).unwrap();
+
+        assert_eq!(schema.get_body(), &value);
+    }
+
+    #[test]
+    fn test_upgrade_external_files() {
+        let url = url::Url::parse(&format!(
+            "file://{}/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            "resources/test/json-schemas/06-with-local-reference.json"
+        ))
+        .unwrap();
+
+        let schema = Schema::load_url(url).unwrap();
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        let result = Upgrader::options()
+            .process_external(&schema, &storage)
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].get_body().get("$schema"),
+            Some(&json!("https://json-schema.org/draft/2020-12/schema"))
+        );
+    }
+
+    #[test]
+    fn test_upgrade_external_refs() {
+        let url = url::Url::parse(&format!(
+            "file://{}/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            "resources/test/json-schemas/24-legacy-with-external-refs.json"
+        ))
+        .unwrap();
+
+        let mut schema = Schema::load_url(url).unwrap();
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        Upgrader::options().process(&mut schema, &storage).unwrap();
+
+        let body = schema.get_body();
+
+        assert_eq!(
+            body.pointer("/properties/person/$ref"),
+            Some(&json!("./01-simple.json#/$defs/Name"))
+        );
+        // current draft is skipped so it still has definitions
+        assert_eq!(
+            body.pointer("/properties/tag/$ref"),
+            Some(&json!("./25-current-draft.json#/definitions/Tag"))
+        );
+        assert_eq!(
+            body.pointer("/properties/local/$ref"),
+            Some(&json!("#/$defs/Local"))
+        );
+    }
+
+    #[test]
+    fn test_upgrade_remote_refs() {
+        let mut schema = Schema::from_json(json!({
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "properties": {
+                "remote": { "$ref": "https://example.com/schemas/address.json#/definitions/Address" },
+                "local": { "$ref": "#/definitions/Address" }
+            },
+            "definitions": { "Address": { "type": "object" } }
+        }));
+        // remote files are never upgraded, so no storage is needed to tell it
+        let storage = SchemaStorage {
+            schemas: std::collections::HashMap::new(),
+        };
+
+        Upgrader::options().process(&mut schema, &storage).unwrap();
+
+        let body = schema.get_body();
+
+        assert_eq!(
+            body.pointer("/properties/remote/$ref"),
+            Some(&json!(
+                "https://example.com/schemas/address.json#/definitions/Address"
+            ))
+        );
+        assert_eq!(
+            body.pointer("/properties/local/$ref"),
+            Some(&json!("#/$defs/Address"))
+        );
+    }
+}
@@ -0,0 +1,652 @@
+use serde_json::{json, Map, Value};
+
+use super::diff::escape;
+use crate::{error::Error, schema::Schema, scope::SchemaScope, storage::SchemaStorage, tools};
+
+pub const VERSION: &str = "3.1.0";
+pub const DIALECT: &str = "https://spec.openapis.org/oas/3.1/dialect/base";
+
+/// Locations of schemas outside of path items, second element tells if path ends in media type
+const COMPONENT_SCHEMAS: [(&str, bool); 9] = [
+    ("/any:components/definition:schemas/definition:*", false),
+    ("/any:components/any:parameters/any:*/any:schema", false),
+    (
+        "/any:components/any:parameters/any:*/any:content/any:*/any:schema",
+        true,
+    ),
+    ("/any:components/any:headers/any:*/any:schema", false),
+    (
+        "/any:components/any:requestBodies/any:*/any:content/any:*/any:schema",
+        true,
+    ),
+    (
+        "/any:components/any:responses/any:*/any:content/any:*/any:schema",
+        true,
+    ),
+    (
+        "/any:components/any:responses/any:*/any:headers/any:*/any:schema",
+        false,
+    ),
+    ("/any:definitions/any:*", false),
+    ("/any:$defs/any:*", false),
+];
+
+/// Locations of path items, including webhooks and callbacks of operations
+const PATH_ITEMS: [&str; 6] = [
+    "/path:paths/any:*",
+    "/any:webhooks/any:*",
+    "/any:components/any:pathItems/any:*",
+    "/path:paths/any:*/any:*/any:callbacks/any:*/any:*",
+    "/any:webhooks/any:*/any:*/any:callbacks/any:*/any:*",
+    "/any:components/any:callbacks/any:*/any:*",
+];
+
+/// Locations of schemas within path item
+const PATH_ITEM_SCHEMAS: [(&str, bool); 7] = [
+    ("/any:parameters/any:*/any:schema", false),
+    ("/any:parameters/any:*/any:content/any:*/any:schema", true),
+    ("/any:*/any:parameters/any:*/any:schema", false),
+    (
+        "/any:*/any:parameters/any:*/any:content/any:*/any:schema",
+        true,
+    ),
+    ("/any:*/any:requestBody/any:content/any:*/any:schema", true),
+    (
+        "/any:*/any:responses/any:*/any:content/any:*/any:schema",
+        true,
+    ),
+    (
+        "/any:*/any:responses/any:*/any:headers/any:*/any:schema",
+        false,
+    ),
+];
+
+/// Locations of schemas in openapi document, second element tells if path ends in media type
+pub(crate) fn schema_paths() -> Vec<(String, bool)> {
+    let mut result = COMPONENT_SCHEMAS
+        .iter()
+        .map(|(path, is_content)| (path.to_string(), *is_content))
+        .collect::<Vec<_>>();
+
+    for item in PATH_ITEMS {
+        for (path, is_content) in PATH_ITEM_SCHEMAS {
+            result.push((format!("{item}{path}"), is_content));
+        }
+    }
+
+    result
+}
+
+/// Json pointer of node found by each_node traversal of given path
+pub(crate) fn schema_pointer(path: &str, parts: &[String]) -> String {
+    let mut parts = parts.iter();
+
+    path.trim_matches('/')
+        .split('/')
+        .filter_map(|segment| match segment.split_once(':') {
+            Some((_, "*")) => parts.next().map(|p| escape(p)),
+            Some((_, key)) => Some(escape(key)),
+            None => None,
+        })
+        .fold(String::new(), |pointer, part| format!("{pointer}/{part}"))
+}
+
+pub struct Upgrader;
+
+pub struct UpgraderOptions {
+    pub dialect: Option<String>,
+}
+
+impl UpgraderOptions {
+    pub fn with_dialect(&mut self, value: Option<String>) -> &mut Self {
+        self.dialect = value;
+        self
+    }
+
+    pub fn process(&self, schema: &mut Schema) -> Result<(), Error> {
+        if !needs_upgrade(schema.get_body())? {
+            log::warn!(
+                "openapi {} is already 3.1, skipping",
+                schema.get_body()["openapi"].as_str().unwrap_or_default()
+            );
+            return Ok(());
+        }
+
+        let root = schema.get_body_mut().as_object_mut().unwrap();
+
+        root.insert("openapi".to_string(), Value::String(VERSION.to_string()));
+        root.insert(
+            "jsonSchemaDialect".to_string(),
+            Value::String(self.dialect.clone().unwrap_or_else(|| DIALECT.to_string())),
+        );
+
+        upgrade_document(schema.get_body_mut())
+    }
+
+    /// Upgrades local files referenced by schema, files are reloaded so relative references stay intact,
+    /// nothing is upgraded unless schema itself is openapi 3.0
+    pub fn process_external(
+        &self,
+        schema: &Schema,
+        storage: &SchemaStorage,
+    ) -> Result<Vec<Schema>, Error> {
+        if !needs_upgrade(schema.get_body())? {
+            return Ok(vec![]);
+        }
+
+        let mut urls = storage
+            .schemas
+            .keys()
+            .filter(|url| *url != schema.get_url() && url.scheme() == "file")
+            .cloned()
+            .collect::<Vec<_>>();
+        urls.sort();
+
+        urls.into_iter()
+            .map(|url| {
+                log::info!("{}: upgrading external file", url);
+
+                let mut external = Schema::load_url(url)?;
+                upgrade_document(external.get_body_mut())?;
+
+                Ok(external)
+            })
+            .collect()
+    }
+}
+
+impl Upgrader {
+    pub fn options() -> UpgraderOptions {
+        UpgraderOptions { dialect: None }
+    }
+}
+
+/// Tells if document is openapi 3.0, 3.1 documents need no upgrade and others are invalid
+fn needs_upgrade(root: &Value) -> Result<bool, Error> {
+    match root.get("openapi").and_then(|v| v.as_str()) {
+        Some(version) if version.starts_with("3.0") => Ok(true),
+        Some(version) if version.starts_with("3.1") => Ok(false),
+        _ => Err(Error::InvalidOpenapiSchemaError),
+    }
+}
+
+/// Upgrades every schema found in openapi document, other documents are treated as (sets of) schemas
+fn upgrade_document(root: &mut Value) -> Result<(), Error> {
+    let is_openapi = root
+        .as_object()
+        .map(|o| {
+            o.contains_key("openapi") || o.contains_key("paths") || o.contains_key("components")
+        })
+        .unwrap_or(false);
+
+    if !is_openapi {
+        if is_schema(root) {
+            upgrade_schema(root, None);
+        } else if let Value::Object(map) = root {
+            for (_, value) in map.iter_mut() {
+                if is_schema(value) {
+                    upgrade_schema(value, None);
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
+    let mut scope = SchemaScope::default();
+
+    for (path, is_content) in schema_paths() {
+        tools::each_node_mut(root, &mut scope, &path, |node, parts, scope| {
+            log::trace!("{}: upgrading", scope);
+
+            let media_type = if is_content {
+                parts
+                    .last()
+                    .map(|s| s.as_str())
+                    .filter(|m| is_raw_media_type(m))
+            } else {
+                None
+            };
+
+            upgrade_schema(node, media_type);
+
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+fn is_schema(node: &Value) -> bool {
+    match node {
+        Value::Object(map) => [
+            "type",
+            "properties",
+            "items",
+            "allOf",
+            "oneOf",
+            "anyOf",
+            "enum",
+            "$ref",
+            "nullable",
+        ]
+        .iter()
+        .any(|k| map.contains_key(*k)),
+        _ => false,
+    }
+}
+
+/// Media types which describe raw content, not structured data
+fn is_raw_media_type(media_type: &str) -> bool {
+    !(media_type.contains("json")
+        || media_type.contains("xml")
+        || media_type.starts_with("multipart/")
+        || media_type == "application/x-www-form-urlencoded")
+}
+
+pub fn upgrade_schema(node: &mut Value, media_type: Option<&str>) {
+    if let Value::Object(map) = node {
+        for key in ["properties", "patternProperties", "definitions", "$defs"] {
+            if let Some(Value::Object(children)) = map.get_mut(key) {
+                for (_, child) in children.iter_mut() {
+                    upgrade_schema(child, None);
+                }
+            }
+        }
+
+        for key in ["items", "additionalProperties", "not"] {
+            match map.get_mut(key) {
+                Some(Value::Array(children)) => {
+                    for child in children.iter_mut() {
+                        upgrade_schema(child, None);
+                    }
+                }
+                Some(child) => upgrade_schema(child, None),
+                None => {}
+            }
+        }
+
+        for key in ["allOf", "oneOf", "anyOf"] {
+            if let Some(Value::Array(children)) = map.get_mut(key) {
+                for child in children.iter_mut() {
+                    upgrade_schema(child, None);
+                }
+            }
+        }
+
+        upgrade_exclusive(map, "exclusiveMinimum", "minimum");
+        upgrade_exclusive(map, "exclusiveMaximum", "maximum");
+        upgrade_example(map);
+        upgrade_format(map, media_type);
+        upgrade_nullable(map);
+    }
+}
+
+pub(crate) fn upgrade_exclusive(map: &mut Map<String, Value>, exclusive: &str, bound: &str) {
+    match map.get(exclusive) {
+        Some(Value::Bool(true)) => match map.shift_remove(bound) {
+            Some(value) => {
+                map.insert(exclusive.to_string(), value);
+            }
+            None => {
+                map.shift_remove(exclusive);
+            }
+        },
+        Some(Value::Bool(false)) => {
+            map.shift_remove(exclusive);
+        }
+        _ => {}
+    }
+}
+
+fn upgrade_example(map: &mut Map<String, Value>) {
+    let Some(example) = map.shift_remove("example") else {
+        return;
+    };
+
+    match map.get_mut("examples") {
+        Some(Value::Array(examples)) => {
+            if !examples.contains(&example) {
+                examples.insert(0, example);
+            }
+        }
+        Some(examples) => log::warn!(
+            "example {} dropped, examples {} is not an array",
+            example,
+            examples
+        ),
+        None => {
+            map.insert("examples".to_string(), json!([example]));
+        }
+    }
+}
+
+fn upgrade_format(map: &mut Map<String, Value>, media_type: Option<&str>) {
+    match map.get("format").and_then(|f| f.as_str()) {
+        Some("binary") => {
+            map.shift_remove("format");
+            map.insert(
+                "contentMediaType".to_string(),
+                Value::String(media_type.unwrap_or("application/octet-stream").to_string()),
+            );
+        }
+        Some("byte") => {
+            map.shift_remove("format");
+            map.insert(
+                "contentEncoding".to_string(),
+                Value::String("base64".to_string()),
+            );
+        }
+        _ => {}
+    }
+}
+
+fn upgrade_nullable(map: &mut Map<String, Value>) {
+    if map.shift_remove("nullable") != Some(Value::Bool(true)) {
+        return;
+    }
+
+    if let Some(Value::Array(values)) = map.get_mut("enum") {
+        if !values.contains(&Value::Null) {
+            values.push(Value::Null);
+        }
+    }
+
+    match map.get_mut("type") {
+        Some(Value::String(type_)) => {
+            let type_ = type_.clone();
+            map.insert("type".to_string(), json!([type_, "null"]));
+        }
+        Some(Value::Array(types)) => {
+            if !types.contains(&json!("null")) {
+                types.push(json!("null"));
+            }
+        }
+        _ => {
+            // nullable reference or composition, annotations stay on the outer schema
+            let mut inner = std::mem::take(map);
+
+            for key in [
+                "title",
+                "description",
+                "default",
+                "examples",
+                "deprecated",
+                "readOnly",
+                "writeOnly",
+            ] {
+                if let Some(value) = inner.shift_remove(key) {
+                    map.insert(key.to_string(), value);
+                }
+            }
+
+            // lone composition keeps its keyword, so oneOf stays exclusive
+            let composition = ["anyOf", "oneOf"]
+                .into_iter()
+                .find(|k| inner.len() == 1 && inner.get(*k).is_some_and(Value::is_array));
+
+            let (keyword, mut branches) =
+                match composition.and_then(|k| inner.shift_remove(k).map(|b| (k, b))) {
+                    Some((keyword, Value::Array(branches))) => (keyword, branches),
+                    _ => ("anyOf", vec![Value::Object(inner)]),
+                };
+
+            branches.push(json!({"type": "null"}));
+            map.insert(keyword.to_string(), Value::Array(branches));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use test_case::test_case;
+
+    #[test_case(
+        json!({"type": "string", "nullable": true}),
+        json!({"type": ["string", "null"]})
+    )]
+    #[test_case(
+        json!({"type": "string", "enum": ["a", "b"], "nullable": true}),
+        json!({"type": ["string", "null"], "enum": ["a", "b", null]})
+    )]
+    #[test_case(
+        json!({"$ref": "#/components/schemas/User", "description": "Owner", "nullable": true}),
+        json!({"description": "Owner", "anyOf": [{"$ref": "#/components/schemas/User"}, {"type": "null"}]})
+    )]
+    #[test_case(
+        json!({"oneOf": [{"type": "string"}, {"type": "integer"}], "nullable": true}),
+        json!({"oneOf": [{"type": "string"}, {"type": "integer"}, {"type": "null"}]})
+    )]
+    #[test_case(
+        json!({"anyOf": [{"type": "string"}, {"type": "integer"}], "nullable": true}),
+        json!({"anyOf": [{"type": "string"}, {"type": "integer"}, {"type": "null"}]})
+    )]
+    #[test_case(
+        json!({"oneOf": [{"type": "string"}], "discriminator": {"propertyName": "kind"}, "nullable": true}),
+        json!({"anyOf": [{"oneOf": [{"type": "string"}], "discriminator": {"propertyName": "kind"}}, {"type": "null"}]})
+    )]
+    #[test_case(
+        json!({"type": "integer", "minimum": 0, "exclusiveMinimum": true, "maximum": 10, "exclusiveMaximum": false}),
+        json!({"type": "integer", "exclusiveMinimum": 0, "maximum": 10})
+    )]
+    #[test_case(
+        json!({"type": "string", "example": "john"}),
+        json!({"type": "string", "examples": ["john"]})
+    )]
+    #[test_case(
+        json!({"type": "string", "example": "john", "examples": ["jane"]}),
+        json!({"type": "string", "examples": ["john", "jane"]})
+    )]
+    #[test_case(
+        json!({"type": "string", "format": "byte"}),
+        json!({"type": "string", "contentEncoding": "base64"})
+    )]
+    fn test_upgrade_schema(mut given: Value, expected: Value) {
+        upgrade_schema(&mut given, None);
+
+        assert_eq!(given.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_schema_pointer() {
+        assert_eq!(
+            schema_pointer(
+                "/path:paths/any:*/any:*/any:requestBody/any:content/any:*/any:schema",
+                &[
+                    "/users".to_string(),
+                    "post".to_string(),
+                    "application/json".to_string()
+                ]
+            ),
+            "/paths/~1users/post/requestBody/content/application~1json/schema"
+        );
+    }
+
+    #[test]
+    fn test_upgrade_openapi() {
+        let value = json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/files/{id}": {
+                    "parameters": [
+                        { "name": "id", "in": "path", "schema": { "type": "string", "example": "abc" } }
+                    ],
+                    "put": {
+                        "requestBody": {
+                            "content": {
+                                "image/png": {
+                                    "schema": { "type": "string", "format": "binary" }
+                                },
+                                "multipart/form-data": {
+                                    "schema": {
+                                        "type": "object",
+                                        "properties": {
+                                            "file": { "type": "string", "format": "binary" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "File": {
+                        "type": "object",
+                        "properties": {
+                            "size": { "type": "integer", "nullable": true }
+                        }
+                    }
+                }
+            }
+        });
+
+        let expected = json!({
+            "openapi": "3.1.0",
+            "paths": {
+                "/files/{id}": {
+                    "parameters": [
+                        { "name": "id", "in": "path", "schema": { "type": "string", "examples": ["abc"] } }
+                    ],
+                    "put": {
+                        "requestBody": {
+                            "content": {
+                                "image/png": {
+                                    "schema": { "type": "string", "contentMediaType": "image/png" }
+                                },
+                                "multipart/form-data": {
+                                    "schema": {
+                                        "type": "object",
+                                        "properties": {
+                                            "file": { "type": "string", "contentMediaType": "application/octet-stream" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "File": {
+                        "type": "object",
+                        "properties": {
+                            "size": { "type": ["integer", "null"] }
+                        }
+                    }
+                }
+            },
+            "jsonSchemaDialect": DIALECT
+        });
+
+        let mut schema = Schema::from_json(value);
+
+        Upgrader::options().process(&mut schema).unwrap();
+
+        assert_eq!(schema.get_body().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_upgrade_callbacks_and_webhooks() {
+        let callback = json!({
+            "{$request.body#/url}": {
+                "post": {
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": { "type": "string", "nullable": true, "example": "done" }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let mut schema = Schema::from_json(json!({
+            "openapi": "3.0.3",
+            "paths": {
+                "/subscriptions": {
+                    "post": {
+                        "callbacks": { "onEvent": callback.clone() },
+                        "responses": { "201": { "description": "Created" } }
+                    }
+                }
+            },
+            "webhooks": {
+                "newPet": {
+                    "post": {
+                        "parameters": [
+                            { "name": "limit", "in": "query", "schema": { "type": "integer", "minimum": 0, "exclusiveMinimum": true } }
+                        ]
+                    }
+                }
+            },
+            "components": {
+                "callbacks": { "onEvent": callback }
+            }
+        }));
+
+        Upgrader::options().process(&mut schema).unwrap();
+
+        let upgraded = json!({ "type": ["string", "null"], "examples": ["done"] });
+        let pointer = "/{$request.body#~1url}/post/requestBody/content/application~1json/schema";
+        let body = schema.get_body();
+
+        assert_eq!(
+            body.pointer(&format!(
+                "/paths/~1subscriptions/post/callbacks/onEvent{pointer}"
+            )),
+            Some(&upgraded)
+        );
+        assert_eq!(
+            body.pointer(&format!("/components/callbacks/onEvent{pointer}")),
+            Some(&upgraded)
+        );
+        assert_eq!(
+            body.pointer("/webhooks/newPet/post/parameters/0/schema"),
+            Some(&json!({ "type": "integer", "exclusiveMinimum": 0 }))
+        );
+    }
+
+    #[test]
+    fn test_upgrade_external_files() {
+        let url = url::Url::parse(&format!(
+            "file://{}/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            "resources/test/openapi/02-external-components.yaml"
+        ))
+        .unwrap();
+
+        let mut schema = Schema::load_url(url).unwrap();
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        let result = Upgrader::options()
+            .process_external(&schema, &storage)
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert!(result[0]
+            .get_url()
+            .path()
+            .ends_with("/resources/test/openapi/02-external-schemas.yaml"));
+
+        // externals are left alone when main document is not upgraded
+        schema.get_body_mut()["openapi"] = json!("3.1.0");
+
+        assert!(Upgrader::options()
+            .process_external(&schema, &storage)
+            .unwrap()
+            .is_empty());
+
+        *schema.get_body_mut() = json!({ "type": "object" });
+
+        assert!(Upgrader::options()
+            .process_external(&schema, &storage)
+            .is_err());
+    }
+}
@@ -148,6 +148,40 @@ impl Schema {
     pub fn get_url(&self) -> &Url {
         &self.url
     }
+
+    /// Writes schema back to its file, yaml or json is chosen by extension
+    pub fn save(&self) -> Result<(), Error> {
+        if self.url.scheme() != "file" {
+            return Err(Error::SchemaLoadInvalidScheme {
+                url: self.url.to_string(),
+                scheme: self.url.scheme().to_string(),
+            });
+        }
+
+        let path = self
+            .url
+            .to_file_path()
+            .map_err(|_| Error::SchemaInvalidPath {
+                path: self.url.path().to_string(),
+            })?;
+
+        let is_yaml = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.contains("yaml"))
+            .unwrap_or(false);
+
+        let content = if is_yaml {
+            serde_yaml::to_string(&self.body).map_err(Error::DeserializeYamlError)?
+        } else {
+            serde_json::to_string_pretty(&self.body).map_err(Error::SerdeJsonError)? + "\n"
+        };
+
+        fs::write(&path, content).map_err(|e| Error::SchemaSave {
+            url: self.url.to_string(),
+            reason: e.to_string(),
+        })
+    }
 }
 
 pub fn path_to_url(path: String) -> Result<Url, Error> {
@@ -1,17 +1,72 @@
 use std::fmt;
+use std::str::FromStr;
 
 use regex::Regex;
 use serde::Serialize;
 
 use crate::error::Error;
-
-#[derive(Clone, Debug)]
+use crate::tools::template_pieces;
+
+/// Placeholders available in naming templates
+const TEMPLATE_PLACEHOLDERS: [&str; 8] = [
+    "operation",
+    "path",
+    "method",
+    "status",
+    "entity",
+    "form",
+    "property",
+    "name",
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub enum SchemaNamingStrategy {
+    /// Historical naming, operation schemas are named `{operation}RequestBody` and `{operation}{status}`
+    #[default]
     Default,
+
+    /// Operation schemas are named `{operation}Request` and `{operation}Response{status}`
+    Operation,
+
+    /// Like operation but path and method are used instead of operationId, ex. `PetsPetIdGetResponse200`
+    Path,
+
+    /// User template, ex. `{operation}{form}{property}`, default name is used when it renders empty
+    Template(String),
+}
+
+impl FromStr for SchemaNamingStrategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(Self::Default),
+            "operation" => Ok(Self::Operation),
+            "path" => Ok(Self::Path),
+            template if template.contains('{') => {
+                let pieces = template_pieces(template).ok_or_else(|| {
+                    Error::NamingStrategyInvalid(format!("unclosed placeholder in {template}"))
+                })?;
+
+                for placeholder in pieces.into_iter().filter_map(|(_, p)| p) {
+                    if !TEMPLATE_PLACEHOLDERS.contains(&placeholder.to_lowercase().as_str()) {
+                        return Err(Error::NamingStrategyInvalid(format!(
+                            "unknown placeholder {{{placeholder}}}, available: {}",
+                            TEMPLATE_PLACEHOLDERS.join(", ")
+                        )));
+                    }
+                }
+
+                Ok(Self::Template(template.to_string()))
+            }
+            _ => Err(Error::NamingStrategyInvalid(format!(
+                "{s}, expected default, operation, path or template"
+            ))),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
-#[allow(dead_code)]
 pub struct SchemaScope {
     scope: Vec<SchemaScopeType>,
     naming_strategy: SchemaNamingStrategy,
@@ -39,11 +94,19 @@ enum SchemaScopeType {
 
     // name builder
     Glue(String),
+    Operation {
+        id: String,
+        method: String,
+        path: String,
+    },
+    Request,
+    Response(String),
 }
 
 #[derive(Debug, Clone)]
 pub struct BasicNamer {
     parts: Vec<SchemaScopeType>,
+    naming_strategy: SchemaNamingStrategy,
 }
 
 impl BasicNamer {
@@ -77,7 +140,109 @@ impl BasicNamer {
             return Err(Error::NotImplemented);
         }
 
-        let form = if self.parts.len() < 2 {
+        match &self.naming_strategy {
+            SchemaNamingStrategy::Template(template) => {
+                let parts = self.template_parts(template)?;
+
+                if parts.is_empty() {
+                    self.default_parts()
+                } else {
+                    Ok(parts)
+                }
+            }
+            _ => self.default_parts(),
+        }
+    }
+
+    fn template_parts(&self, template: &str) -> Result<Vec<String>, Error> {
+        let mut result = vec![];
+        let mut rendered = false;
+
+        let pieces = template_pieces(template).ok_or_else(|| {
+            Error::NamingStrategyInvalid(format!("unclosed placeholder in {template}"))
+        })?;
+
+        for (literal, placeholder) in pieces {
+            result.extend(self.split(literal));
+
+            if let Some(placeholder) = placeholder {
+                let value = self.placeholder(&placeholder.to_lowercase())?;
+                rendered |= !value.is_empty();
+                result.extend(value);
+            }
+        }
+
+        Ok(if rendered { result } else { vec![] })
+    }
+
+    fn placeholder(&self, name: &str) -> Result<Vec<String>, Error> {
+        let operation = self.parts.iter().rev().find_map(|s| match s {
+            SchemaScopeType::Operation { id, method, path } => Some((id, method, path)),
+            _ => None,
+        });
+
+        let status = self.parts.iter().rev().find_map(|s| match s {
+            SchemaScopeType::Response(code) => Some(code),
+            _ => None,
+        });
+
+        let last = self.parts.iter().rev().find(|s| {
+            !matches!(
+                s,
+                SchemaScopeType::Form(_)
+                    | SchemaScopeType::Index(_)
+                    | SchemaScopeType::Reference(_)
+            )
+        });
+
+        Ok(match name {
+            "operation" => operation
+                .map(|(id, _, _)| self.split(id))
+                .unwrap_or_default(),
+            "path" => operation
+                .map(|(_, _, path)| self.split_path(path))
+                .unwrap_or_default(),
+            "method" => operation
+                .map(|(_, method, _)| self.split(method))
+                .unwrap_or_default(),
+            "status" => status.map(|s| self.split(s)).unwrap_or_default(),
+            "entity" => self
+                .parts
+                .iter()
+                .rev()
+                .find_map(|s| match s {
+                    SchemaScopeType::Entity(name) => Some(self.split(name)),
+                    _ => None,
+                })
+                .unwrap_or_default(),
+            "property" => match last {
+                Some(SchemaScopeType::Property(p) | SchemaScopeType::Definition(p)) => {
+                    self.split(p)
+                }
+                _ => vec![],
+            },
+            "form" => match self.form() {
+                Some(form) => vec![form],
+                None => self
+                    .parts
+                    .iter()
+                    .rev()
+                    .find_map(|s| match s {
+                        SchemaScopeType::Request => Some(vec!["Request".to_string()]),
+                        SchemaScopeType::Response(code) => {
+                            Some([vec!["Response".to_string()], self.split(code)].concat())
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or_default(),
+            },
+            _ => self.default_parts()?,
+        })
+    }
+
+    /// Suffix of oneOf/anyOf options and allOf parts
+    fn form(&self) -> Option<String> {
+        if self.parts.len() < 2 {
             None
         } else if let Some(SchemaScopeType::Form(form)) = self.parts.get(self.parts.len() - 2) {
             if form == "oneOf" || form == "anyOf" {
@@ -97,7 +262,47 @@ impl BasicNamer {
             }
         } else {
             None
-        };
+        }
+    }
+
+    /// Words of name builder parts, operation related ones depend on naming strategy
+    fn glue_parts(&self, part: &SchemaScopeType) -> Option<Vec<String>> {
+        let strategy = &self.naming_strategy;
+
+        match part {
+            SchemaScopeType::Glue(t) => Some(self.split(t)),
+            SchemaScopeType::Operation { method, path, .. }
+                if *strategy == SchemaNamingStrategy::Path =>
+            {
+                Some([self.split_path(path), self.split(method)].concat())
+            }
+            SchemaScopeType::Operation { id, .. } => Some(self.split(id)),
+            SchemaScopeType::Request if *strategy == SchemaNamingStrategy::Default => {
+                Some(vec!["request".to_string(), "body".to_string()])
+            }
+            SchemaScopeType::Request => Some(vec!["Request".to_string()]),
+            SchemaScopeType::Response(code) if *strategy == SchemaNamingStrategy::Default => {
+                Some(vec![if code == "default" {
+                    "0".to_string()
+                } else {
+                    code.to_string()
+                }])
+            }
+            SchemaScopeType::Response(code) => {
+                Some([vec!["Response".to_string()], self.split(code)].concat())
+            }
+            _ => None,
+        }
+    }
+
+    fn split_path(&self, path: &str) -> Vec<String> {
+        path.split(|c: char| !c.is_ascii_alphanumeric())
+            .flat_map(|s| self.split(s))
+            .collect()
+    }
+
+    fn default_parts(&self) -> Result<Vec<String>, Error> {
+        let form = self.form();
 
         match self
             .parts
@@ -139,17 +344,13 @@ impl BasicNamer {
                 }
             }
             _ => {
-                let glued: Vec<String> = self
+                let glued: Vec<Vec<String>> = self
                     .parts
-                    .clone()
-                    .into_iter()
-                    .filter_map(|s| match s {
-                        SchemaScopeType::Glue(t) => Some(t),
-                        _ => None,
-                    })
+                    .iter()
+                    .filter_map(|s| self.glue_parts(s))
                     .collect();
 
-                let parts: Vec<String> = glued.iter().flat_map(|s| self.split(s)).collect();
+                let parts: Vec<String> = glued.iter().flatten().cloned().collect();
                 if !glued.is_empty() {
                     Ok(parts)
                 } else {
@@ -265,6 +466,26 @@ impl SchemaScope {
         self
     }
 
+    pub fn operation(&mut self, id: &str, method: &str, path: &str) -> &mut Self {
+        self.scope.push(SchemaScopeType::Operation {
+            id: id.to_string(),
+            method: method.to_string(),
+            path: path.to_string(),
+        });
+        self
+    }
+
+    pub fn request(&mut self) -> &mut Self {
+        self.scope.push(SchemaScopeType::Request);
+        self
+    }
+
+    pub fn response(&mut self, status: &str) -> &mut Self {
+        self.scope
+            .push(SchemaScopeType::Response(status.to_string()));
+        self
+    }
+
     pub fn add_spaces(&mut self, spaces: &mut Vec<Space>) -> &mut Self {
         self.spaces.append(spaces);
         self
@@ -292,6 +513,7 @@ impl SchemaScope {
     pub fn namer(&mut self) -> BasicNamer {
         BasicNamer {
             parts: self.scope.clone(),
+            naming_strategy: self.naming_strategy.clone(),
         }
     }
 
@@ -369,7 +591,10 @@ impl fmt::Display for SchemaScope {
 fn scope_to_string(s: SchemaScopeType) -> Option<String> {
     match s {
         SchemaScopeType::Entity(_) => None,
-        SchemaScopeType::Glue(_) => None,
+        SchemaScopeType::Glue(_)
+        | SchemaScopeType::Operation { .. }
+        | SchemaScopeType::Request
+        | SchemaScopeType::Response(_) => None,
         SchemaScopeType::Property(v)
         | SchemaScopeType::Any(v)
         | SchemaScopeType::Form(v)
@@ -54,6 +54,19 @@ where
 
                             Ok(())
                         }
+                        Value::Array(ref mut list) => {
+                            for (i, value) in list.iter_mut().enumerate() {
+                                context.index(i);
+
+                                parts.push(i.to_string());
+                                each_mut(value, context, path, index + 1, parts, f)?;
+                                parts.pop();
+
+                                context.pop();
+                            }
+
+                            Ok(())
+                        }
                         _ => Err(Error::NotImplemented),
                     },
                     real_path => {
@@ -123,6 +136,19 @@ where
 
                             Ok(())
                         }
+                        Value::Array(ref list) => {
+                            for (i, value) in list.iter().enumerate() {
+                                context.index(i);
+
+                                parts.push(i.to_string());
+                                each(value, context, path, index + 1, parts, f)?;
+                                parts.pop();
+
+                                context.pop();
+                            }
+
+                            Ok(())
+                        }
                         _ => Err(Error::NotImplemented),
                     },
                     real_path => {
@@ -256,6 +282,45 @@ pub fn bump_suffix_number(phrase: &str) -> String {
     }
 }
 
+/// Splits template into pairs of literal text and following `{placeholder}`,
+/// returns `None` when some placeholder is not closed
+pub(crate) fn template_pieces(template: &str) -> Option<Vec<(&str, Option<&str>)>> {
+    let mut result = vec![];
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..].find('}')?;
+
+        result.push((&rest[..start], Some(&rest[start + 1..start + end])));
+        rest = &rest[start + end + 1..];
+    }
+
+    result.push((rest, None));
+
+    Some(result)
+}
+
+/// Compiles glob pattern, `*` matches one path segment and `**` any number of them
+pub fn glob(pattern: &str) -> Result<regex::Regex, Error> {
+    let mut expression = String::from("^");
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                expression.push_str(".*");
+            }
+            '*' => expression.push_str("[^/]*"),
+            '?' => expression.push_str("[^/]"),
+            c => expression.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    expression.push('$');
+
+    regex::Regex::new(&expression).map_err(|_| Error::IncorrectFilterError(pattern.to_string()))
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct Filter {
     conditions: Vec<ConditionSet>,
@@ -395,6 +460,53 @@ mod tests {
         assert_eq!(result, "asd544".to_string());
     }
 
+    #[test]
+    fn test_template_pieces() {
+        assert_eq!(
+            template_pieces("{verb}{Resource}By{param}"),
+            Some(vec![
+                ("", Some("verb")),
+                ("", Some("Resource")),
+                ("By", Some("param")),
+                ("", None)
+            ])
+        );
+        assert_eq!(template_pieces("plain"), Some(vec![("plain", None)]));
+        assert_eq!(template_pieces("{verb}{Resource"), None);
+    }
+
+    #[test]
+    fn test_each_node_mut_arrays() {
+        let mut given = serde_json::json!({
+            "parameters": [
+                { "name": "a", "schema": { "type": "string" } },
+                { "name": "b" },
+                { "name": "c", "schema": { "type": "integer" } }
+            ]
+        });
+
+        let mut found = vec![];
+
+        each_node_mut(
+            &mut given,
+            &mut SchemaScope::default(),
+            "/any:parameters/any:*/any:schema",
+            |node, parts, _| {
+                found.push(parts.join("/"));
+                node["title"] = serde_json::json!("Param");
+
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(found, vec!["0", "2"]);
+        assert_eq!(
+            given.pointer("/parameters/2/schema/title").unwrap(),
+            "Param"
+        );
+    }
+
     #[test]
     fn test_fill_parameters() {
         let given = serde_json::json!({
@@ -0,0 +1,871 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::process::merge_allof::{intersect, numeric_bound};
+use crate::{
+    error::Error, resolver::SchemaResolver, schema::Schema, scope::SchemaScope,
+    storage::SchemaStorage, Client,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompatibilityMode {
+    /// Every document valid under previous schema is valid under new one
+    Backward,
+    /// Every document valid under new schema is valid under previous one
+    Forward,
+    /// Backward and forward at the same time
+    Full,
+    /// Full compatibility with all previous versions
+    Transitive,
+}
+
+impl std::str::FromStr for CompatibilityMode {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<CompatibilityMode, Self::Err> {
+        match input {
+            "backward" => Ok(CompatibilityMode::Backward),
+            "forward" => Ok(CompatibilityMode::Forward),
+            "full" => Ok(CompatibilityMode::Full),
+            "transitive" => Ok(CompatibilityMode::Transitive),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Incompatibility {
+    pub previous: String,
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}#{})", self.message, self.previous, self.pointer)
+    }
+}
+
+/// Checks new version of json schema against previous versions, the last one is the most recent,
+/// external files of previous versions are loaded with client
+pub fn check_compatibility(
+    schema: &Schema,
+    storage: &SchemaStorage,
+    previous: &[Schema],
+    mode: CompatibilityMode,
+    client: &Client,
+) -> Result<Vec<Incompatibility>, Error> {
+    let previous = match mode {
+        CompatibilityMode::Transitive => previous,
+        _ => match previous.last() {
+            Some(last) => std::slice::from_ref(last),
+            None => &[],
+        },
+    };
+
+    let mut result = vec![];
+
+    for old in previous {
+        let old_storage = SchemaStorage::new(old, client);
+
+        let old = Side {
+            schema: old,
+            resolver: SchemaResolver::new(old, &old_storage),
+        };
+        let new = Side {
+            schema,
+            resolver: SchemaResolver::new(schema, storage),
+        };
+
+        let directions = match mode {
+            CompatibilityMode::Backward => vec![(&old, &new, "backward")],
+            CompatibilityMode::Forward => vec![(&new, &old, "forward")],
+            CompatibilityMode::Full | CompatibilityMode::Transitive => {
+                vec![(&old, &new, "backward"), (&new, &old, "forward")]
+            }
+        };
+
+        for (sub, sup, direction) in directions {
+            let mut context = Context {
+                sub: &sub.resolver,
+                sup: &sup.resolver,
+                scope: SchemaScope::default(),
+                visited: HashSet::new(),
+                errors: vec![],
+            };
+
+            is_subset(
+                sub.schema.get_body(),
+                sup.schema.get_body(),
+                "",
+                &mut context,
+            );
+
+            result.extend(
+                context
+                    .errors
+                    .into_iter()
+                    .map(|(pointer, message)| Incompatibility {
+                        previous: old.schema.get_url().to_string(),
+                        pointer,
+                        message: format!("{direction}: {message}"),
+                    }),
+            );
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn validate_compatibility(
+    schema: &Schema,
+    storage: &SchemaStorage,
+    previous: &[Schema],
+    mode: CompatibilityMode,
+    client: &Client,
+) -> Result<(), Error> {
+    let incompatibilities = check_compatibility(schema, storage, previous, mode, client)?;
+
+    if incompatibilities.is_empty() {
+        return Ok(());
+    }
+
+    for i in incompatibilities.iter() {
+        log::error!("{}", i);
+    }
+
+    Err(Error::SchemaIncompatible {
+        url: schema.get_url().to_string(),
+        count: incompatibilities.len(),
+    })
+}
+
+struct Side<'a> {
+    schema: &'a Schema,
+    resolver: SchemaResolver<'a>,
+}
+
+struct Context<'a> {
+    sub: &'a SchemaResolver<'a>,
+    sup: &'a SchemaResolver<'a>,
+    scope: SchemaScope,
+    visited: HashSet<(String, String)>,
+    errors: Vec<(String, String)>,
+}
+
+impl Context<'_> {
+    fn error(&mut self, pointer: &str, message: String) {
+        log::debug!("{}: {}", pointer, message);
+        self.errors.push((pointer.to_string(), message));
+    }
+
+    /// Runs check without reporting errors, used to find matching branch of composition
+    fn probe(&mut self, sub: &Value, sup: &Value, pointer: &str) -> bool {
+        let errors = std::mem::take(&mut self.errors);
+        is_subset(sub, sup, pointer, self);
+        let matches = self.errors.is_empty();
+        self.errors = errors;
+
+        matches
+    }
+
+    fn resolve(&mut self, sub: bool, node: &Value) -> Value {
+        let resolver = if sub { self.sub } else { self.sup };
+
+        let resolved = resolver
+            .resolve(node, &mut self.scope, |resolved, _| Ok(resolved.clone()))
+            .unwrap_or_else(|_| node.clone());
+
+        flatten_all_of(resolved, resolver, &mut self.scope)
+    }
+}
+
+fn reference(node: &Value) -> String {
+    node.get("$ref")
+        .and_then(|r| r.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Intersects allOf members into one schema, constraints which cannot be merged into single
+/// keyword stay in allOf and members which cannot be satisfied together accept nothing
+fn flatten_all_of(node: Value, resolver: &SchemaResolver, scope: &mut SchemaScope) -> Value {
+    let Value::Object(mut map) = node else {
+        return node;
+    };
+
+    let members = match map.shift_remove("allOf") {
+        Some(Value::Array(members)) => members,
+        Some(other) => {
+            map.insert("allOf".to_string(), other);
+            return Value::Object(map);
+        }
+        None => return Value::Object(map),
+    };
+
+    let mut result = Value::Object(map);
+    let mut conflicts = vec![];
+
+    for member in members {
+        let member = resolver
+            .resolve(&member, scope, |resolved, _| Ok(resolved.clone()))
+            .unwrap_or(member);
+
+        intersect(
+            &mut result,
+            flatten_all_of(member, resolver, scope),
+            "",
+            &mut conflicts,
+        );
+    }
+
+    if conflicts.is_empty() {
+        result
+    } else {
+        for conflict in conflicts {
+            log::debug!("allOf{}", conflict);
+        }
+
+        Value::Bool(false)
+    }
+}
+
+fn types(schema: &Map<String, Value>) -> Option<Vec<String>> {
+    let mut result = match schema.get("type") {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(a)) => a
+            .iter()
+            .filter_map(|s| s.as_str().map(|s| s.to_string()))
+            .collect(),
+        _ => return None,
+    };
+
+    if schema.get("nullable") == Some(&Value::Bool(true)) && !result.contains(&"null".to_string()) {
+        result.push("null".to_string());
+    }
+
+    Some(result)
+}
+
+fn accepts_type(types: &[String], t: &str) -> bool {
+    types
+        .iter()
+        .any(|a| a == t || (a == "number" && t == "integer"))
+}
+
+fn allowed_values(schema: &Map<String, Value>) -> Option<Vec<Value>> {
+    match (schema.get("enum"), schema.get("const")) {
+        (Some(Value::Array(values)), _) => Some(values.clone()),
+        (_, Some(value)) => Some(vec![value.clone()]),
+        _ => None,
+    }
+}
+
+fn required(schema: &Map<String, Value>) -> Vec<String> {
+    schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| {
+            r.iter()
+                .filter_map(|s| s.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_unconstrained(schema: &Value) -> bool {
+    match schema {
+        Value::Bool(true) => true,
+        Value::Object(map) => map.keys().all(|k| {
+            ["title", "description", "$comment", "examples", "default"].contains(&k.as_str())
+        }),
+        _ => false,
+    }
+}
+
+/// Checks if every document valid under `sub` is valid under `sup`
+fn is_subset(sub: &Value, sup: &Value, pointer: &str, ctx: &mut Context) {
+    let key = (reference(sub), reference(sup));
+    let is_reference = !key.0.is_empty() || !key.1.is_empty();
+
+    if is_reference {
+        if ctx.visited.contains(&key) {
+            return;
+        }
+
+        ctx.visited.insert(key.clone());
+    }
+
+    let sub = ctx.resolve(true, sub);
+    let sup = ctx.resolve(false, sup);
+
+    match (&sub, &sup) {
+        (_, s) if is_unconstrained(s) => {}
+        (Value::Bool(false), _) => {}
+        (_, Value::Bool(false)) => ctx.error(pointer, "schema rejects all documents".to_string()),
+        (Value::Bool(true), _) => ctx.error(
+            pointer,
+            "constraints added to unconstrained schema".to_string(),
+        ),
+        (Value::Object(a), Value::Object(b)) => compare_objects(a, b, pointer, ctx),
+        _ => ctx.error(pointer, "schema is invalid".to_string()),
+    }
+
+    if is_reference {
+        ctx.visited.remove(&key);
+    }
+}
+
+fn compare_objects(
+    a: &Map<String, Value>,
+    b: &Map<String, Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    // compositions of the narrower schema are checked branch by branch
+    for form in ["oneOf", "anyOf"] {
+        if let Some(Value::Array(branches)) = a.get(form) {
+            let mut rest = a.clone();
+            rest.remove(form);
+
+            for (i, branch) in branches.iter().enumerate() {
+                let resolved = ctx.resolve(true, branch);
+                let mut merged = rest.clone();
+
+                if let Value::Object(branch) = resolved {
+                    merged.extend(branch);
+                }
+
+                is_subset(
+                    &Value::Object(merged),
+                    &Value::Object(b.clone()),
+                    &format!("{pointer}/{form}/{i}"),
+                    ctx,
+                );
+            }
+
+            return;
+        }
+    }
+
+    for form in ["oneOf", "anyOf"] {
+        if let Some(Value::Array(branches)) = b.get(form) {
+            let mut rest = b.clone();
+            rest.remove(form);
+
+            let matching = branches.iter().enumerate().any(|(i, branch)| {
+                let resolved = ctx.resolve(false, branch);
+                let mut merged = rest.clone();
+
+                if let Value::Object(branch) = resolved {
+                    merged.extend(branch);
+                }
+
+                ctx.probe(
+                    &Value::Object(a.clone()),
+                    &Value::Object(merged),
+                    &format!("{pointer}/{form}/{i}"),
+                )
+            });
+
+            if !matching {
+                ctx.error(
+                    &format!("{pointer}/{form}"),
+                    format!("schema doesn't match any of {form} schemas"),
+                );
+            }
+
+            return;
+        }
+    }
+
+    // allOf members which could not be merged have to hold on their own
+    if let Some(Value::Array(members)) = b.get("allOf") {
+        let a_members = a.get("allOf").and_then(Value::as_array);
+
+        for (i, member) in members.iter().enumerate() {
+            if !a_members.is_some_and(|m| m.contains(member)) {
+                is_subset(
+                    &Value::Object(a.clone()),
+                    member,
+                    &format!("{pointer}/allOf/{i}"),
+                    ctx,
+                );
+            }
+        }
+    }
+
+    compare_types(a, b, pointer, ctx);
+    compare_values(a, b, pointer, ctx);
+    compare_constraints(a, b, pointer, ctx);
+    compare_properties(a, b, pointer, ctx);
+
+    match (a.get("items"), b.get("items")) {
+        (Some(a), Some(b)) => is_subset(a, b, &format!("{pointer}/items"), ctx),
+        (None, Some(b)) if !is_unconstrained(b) => ctx.error(
+            &format!("{pointer}/items"),
+            "constraints added to array items".to_string(),
+        ),
+        _ => {}
+    }
+}
+
+fn compare_types(a: &Map<String, Value>, b: &Map<String, Value>, pointer: &str, ctx: &mut Context) {
+    let b_types = match types(b) {
+        Some(types) => types,
+        None => return,
+    };
+
+    match types(a) {
+        Some(a_types) => {
+            let missing = a_types
+                .iter()
+                .filter(|t| !accepts_type(&b_types, t))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if !missing.is_empty() {
+                ctx.error(
+                    &format!("{pointer}/type"),
+                    format!(
+                        "type {} is no longer accepted, allowed: {}",
+                        missing.join("|"),
+                        b_types.join("|")
+                    ),
+                );
+            }
+        }
+        None => ctx.error(
+            &format!("{pointer}/type"),
+            format!("type restricted to {}", b_types.join("|")),
+        ),
+    }
+}
+
+fn compare_values(
+    a: &Map<String, Value>,
+    b: &Map<String, Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    let b_values = match allowed_values(b) {
+        Some(values) => values,
+        None => return,
+    };
+
+    match allowed_values(a) {
+        Some(a_values) => {
+            for value in a_values.iter().filter(|v| !b_values.contains(v)) {
+                ctx.error(
+                    &format!("{pointer}/enum"),
+                    format!("enum value {value} is no longer accepted"),
+                );
+            }
+        }
+        None => ctx.error(
+            &format!("{pointer}/enum"),
+            "values restricted by enum".to_string(),
+        ),
+    }
+}
+
+fn compare_constraints(
+    a: &Map<String, Value>,
+    b: &Map<String, Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    // numeric bounds together with their exclusive keywords
+    let max: fn(f64, f64) -> f64 = f64::max;
+
+    for (bound, flag, pick, change) in [
+        ("minimum", "exclusiveMinimum", max, "raised"),
+        ("maximum", "exclusiveMaximum", f64::min, "lowered"),
+    ] {
+        let Some((limit, exclusive)) = numeric_bound(b, bound, flag, pick) else {
+            continue;
+        };
+
+        match numeric_bound(a, bound, flag, pick) {
+            Some((current, current_exclusive))
+                if (current != limit && pick(current, limit) == current)
+                    || (current == limit && (current_exclusive || !exclusive)) => {}
+            current => {
+                let key = if exclusive && b.get(flag).is_some_and(Value::is_number) {
+                    flag
+                } else {
+                    bound
+                };
+
+                ctx.error(
+                    &format!("{pointer}/{key}"),
+                    format!(
+                        "{key} {change} from {} to {limit}",
+                        describe(current.map(|(c, _)| c))
+                    ),
+                );
+            }
+        }
+    }
+
+    // lower bounds
+    for key in ["minLength", "minItems", "minProperties"] {
+        if let Some(bound) = b.get(key).and_then(|v| v.as_f64()) {
+            match a.get(key).and_then(|v| v.as_f64()) {
+                Some(current) if current >= bound => {}
+                current => ctx.error(
+                    &format!("{pointer}/{key}"),
+                    format!("{key} raised from {} to {bound}", describe(current)),
+                ),
+            }
+        }
+    }
+
+    // upper bounds
+    for key in ["maxLength", "maxItems", "maxProperties"] {
+        if let Some(bound) = b.get(key).and_then(|v| v.as_f64()) {
+            match a.get(key).and_then(|v| v.as_f64()) {
+                Some(current) if current <= bound => {}
+                current => ctx.error(
+                    &format!("{pointer}/{key}"),
+                    format!("{key} lowered from {} to {bound}", describe(current)),
+                ),
+            }
+        }
+    }
+
+    for key in ["pattern", "format", "multipleOf"] {
+        if let Some(constraint) = b.get(key) {
+            if a.get(key) != Some(constraint) {
+                ctx.error(
+                    &format!("{pointer}/{key}"),
+                    format!("{key} changed to {constraint}"),
+                );
+            }
+        }
+    }
+
+    if b.get("uniqueItems") == Some(&Value::Bool(true))
+        && a.get("uniqueItems") != Some(&Value::Bool(true))
+    {
+        ctx.error(
+            &format!("{pointer}/uniqueItems"),
+            "unique items required".to_string(),
+        );
+    }
+}
+
+fn describe(value: Option<f64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or("none".to_string())
+}
+
+fn compare_properties(
+    a: &Map<String, Value>,
+    b: &Map<String, Value>,
+    pointer: &str,
+    ctx: &mut Context,
+) {
+    let empty = Map::new();
+    let a_properties = a
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .unwrap_or(&empty);
+    let b_properties = b
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .unwrap_or(&empty);
+    let a_additional = a.get("additionalProperties");
+    let b_additional = b.get("additionalProperties");
+    let a_required = required(a);
+
+    for name in required(b).iter().filter(|r| !a_required.contains(r)) {
+        ctx.error(
+            &format!("{pointer}/required"),
+            format!("property {name} became required"),
+        );
+    }
+
+    for (name, b_property) in b_properties.iter() {
+        let property_pointer = format!("{pointer}/properties/{}", escape(name));
+
+        match a_properties.get(name) {
+            Some(a_property) => is_subset(a_property, b_property, &property_pointer, ctx),
+            None => match a_additional {
+                Some(Value::Bool(false)) => {}
+                Some(Value::Object(additional)) => is_subset(
+                    &Value::Object(additional.clone()),
+                    b_property,
+                    &property_pointer,
+                    ctx,
+                ),
+                _ => {
+                    if !is_unconstrained(b_property) {
+                        ctx.error(
+                            &property_pointer,
+                            format!(
+                                "property {name} added with constraints while additional properties were allowed"
+                            ),
+                        );
+                    }
+                }
+            },
+        }
+    }
+
+    for (name, a_property) in a_properties.iter() {
+        if b_properties.contains_key(name) {
+            continue;
+        }
+
+        let property_pointer = format!("{pointer}/properties/{}", escape(name));
+
+        match b_additional {
+            Some(Value::Bool(false)) => ctx.error(
+                &property_pointer,
+                format!("property {name} removed while additional properties are not allowed"),
+            ),
+            Some(Value::Object(additional)) => is_subset(
+                a_property,
+                &Value::Object(additional.clone()),
+                &property_pointer,
+                ctx,
+            ),
+            _ => {}
+        }
+    }
+
+    let additional_pointer = format!("{pointer}/additionalProperties");
+
+    match (a_additional, b_additional) {
+        (Some(Value::Bool(false)), _) => {}
+        (_, Some(Value::Bool(false))) => ctx.error(
+            &additional_pointer,
+            "additional properties are not allowed".to_string(),
+        ),
+        (Some(Value::Object(a)), Some(Value::Object(b))) => is_subset(
+            &Value::Object(a.clone()),
+            &Value::Object(b.clone()),
+            &additional_pointer,
+            ctx,
+        ),
+        (_, Some(b)) if !is_unconstrained(b) => ctx.error(
+            &additional_pointer,
+            "constraints added to additional properties".to_string(),
+        ),
+        _ => {}
+    }
+}
+
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use test_case::test_case;
+
+    fn check(old: Value, new: Value, mode: CompatibilityMode) -> Vec<String> {
+        let schema = Schema::from_json(new);
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+
+        check_compatibility(&schema, &storage, &[Schema::from_json(old)], mode, &client)
+            .unwrap()
+            .into_iter()
+            .map(|i| format!("{}: {}", i.pointer, i.message))
+            .collect()
+    }
+
+    #[test_case(CompatibilityMode::Backward, 0)]
+    #[test_case(CompatibilityMode::Forward, 1)]
+    #[test_case(CompatibilityMode::Full, 1)]
+    fn test_optional_property_added_to_closed_schema(mode: CompatibilityMode, expected: usize) {
+        let old = json!({
+            "type": "object",
+            "properties": { "id": { "type": "string" } },
+            "additionalProperties": false
+        });
+
+        let new = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string" },
+                "name": { "type": "string" }
+            },
+            "additionalProperties": false
+        });
+
+        assert_eq!(check(old, new, mode).len(), expected);
+    }
+
+    #[test]
+    fn test_backward_incompatible() {
+        let old = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer" },
+                "status": { "type": "string", "enum": ["created", "paid", "cancelled"] },
+                "note": { "type": "string" }
+            }
+        });
+
+        let new = json!({
+            "type": "object",
+            "required": ["id"],
+            "properties": {
+                "id": { "type": "number" },
+                "status": { "type": "string", "enum": ["created", "paid"] },
+                "note": { "type": "string", "maxLength": 100 }
+            }
+        });
+
+        assert_eq!(
+            check(old, new, CompatibilityMode::Backward),
+            vec![
+                "/required: backward: property id became required",
+                "/properties/status/enum: backward: enum value \"cancelled\" is no longer accepted",
+                "/properties/note/maxLength: backward: maxLength lowered from none to 100",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_type_widening() {
+        let old = json!({ "type": "integer" });
+        let new = json!({ "type": ["number", "null"] });
+
+        assert!(check(old.clone(), new.clone(), CompatibilityMode::Backward).is_empty());
+        assert_eq!(
+            check(old, new, CompatibilityMode::Forward),
+            vec!["/type: forward: type number|null is no longer accepted, allowed: integer"]
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        let old = json!({
+            "definitions": {
+                "money": { "type": "object", "properties": { "amount": { "type": "integer" } } }
+            },
+            "type": "object",
+            "properties": {
+                "total": { "$ref": "#/definitions/money" }
+            }
+        });
+
+        let new = json!({
+            "definitions": {
+                "amount": {
+                    "type": "object",
+                    "properties": { "amount": { "type": "string" } }
+                }
+            },
+            "type": "object",
+            "properties": {
+                "total": { "$ref": "#/definitions/amount" }
+            }
+        });
+
+        assert_eq!(
+            check(old, new, CompatibilityMode::Backward),
+            vec!["/properties/total/properties/amount/type: backward: type integer is no longer accepted, allowed: string"]
+        );
+    }
+
+    #[test]
+    fn test_one_of() {
+        let old = json!({ "type": "string" });
+        let new = json!({ "oneOf": [{ "type": "integer" }, { "type": "string" }] });
+
+        assert!(check(old.clone(), new.clone(), CompatibilityMode::Backward).is_empty());
+        assert_eq!(
+            check(old, new, CompatibilityMode::Forward),
+            vec!["/oneOf/0/type: forward: type integer is no longer accepted, allowed: string"]
+        );
+    }
+
+    #[test]
+    fn test_all_of_members_intersected() {
+        let old = json!({
+            "allOf": [
+                { "type": "object", "properties": { "code": { "type": "string" } } },
+                { "properties": { "code": { "maxLength": 10 } } }
+            ]
+        });
+
+        let new = json!({
+            "allOf": [
+                { "type": "object", "properties": { "code": { "type": "string" } } },
+                { "properties": { "code": { "maxLength": 5, "pattern": "^[A-Z]+$" } } },
+                { "properties": { "code": { "pattern": "^[A-Z0-9]+$" } } }
+            ]
+        });
+
+        assert_eq!(
+            check(old.clone(), new.clone(), CompatibilityMode::Backward),
+            vec![
+                "/properties/code/allOf/0/pattern: backward: pattern changed to \"^[A-Z0-9]+$\"",
+                "/properties/code/maxLength: backward: maxLength lowered from 10 to 5",
+                "/properties/code/pattern: backward: pattern changed to \"^[A-Z]+$\"",
+            ]
+        );
+        assert!(check(old, new, CompatibilityMode::Forward).is_empty());
+
+        let unsatisfiable = json!({ "allOf": [{ "type": "string" }, { "type": "integer" }] });
+
+        assert_eq!(
+            check(
+                json!({ "type": "string" }),
+                unsatisfiable,
+                CompatibilityMode::Backward
+            ),
+            vec![": backward: schema rejects all documents"]
+        );
+    }
+
+    #[test_case(json!({ "exclusiveMinimum": 10 }), json!({ "exclusiveMinimum": 5 }), vec![] ; "looser exclusive")]
+    #[test_case(json!({ "exclusiveMinimum": 5 }), json!({ "exclusiveMinimum": 10 }), vec!["/exclusiveMinimum: backward: exclusiveMinimum raised from 5 to 10"] ; "tighter exclusive")]
+    #[test_case(json!({ "minimum": 5 }), json!({ "exclusiveMinimum": 5 }), vec!["/exclusiveMinimum: backward: exclusiveMinimum raised from 5 to 5"] ; "inclusive to exclusive")]
+    #[test_case(json!({ "maximum": 10, "exclusiveMaximum": true }), json!({ "maximum": 10 }), vec![] ; "openapi 3.0 flag dropped")]
+    #[test_case(json!({ "exclusiveMaximum": 10 }), json!({ "maximum": 8 }), vec!["/maximum: backward: maximum lowered from 10 to 8"] ; "exclusive to lower inclusive")]
+    fn test_exclusive_bounds(old: Value, new: Value, expected: Vec<&str>) {
+        assert_eq!(check(old, new, CompatibilityMode::Backward), expected);
+    }
+
+    #[test]
+    fn test_transitive() {
+        let first = Schema::from_json(json!({ "type": "string", "maxLength": 10 }));
+        let second = Schema::from_json(json!({ "type": "string", "maxLength": 20 }));
+        let schema = Schema::from_json(json!({ "type": "string", "maxLength": 20 }));
+
+        let client = Client::new();
+        let storage = SchemaStorage::new(&schema, &client);
+        let previous = [first, second];
+
+        assert!(check_compatibility(
+            &schema,
+            &storage,
+            &previous,
+            CompatibilityMode::Full,
+            &client
+        )
+        .unwrap()
+        .is_empty());
+        assert_eq!(
+            check_compatibility(
+                &schema,
+                &storage,
+                &previous,
+                CompatibilityMode::Transitive,
+                &client
+            )
+            .unwrap()
+            .len(),
+            1
+        );
+    }
+}
@@ -1,3 +1,5 @@
+pub mod compatibility;
+
 use jsonschema::{Draft, JSONSchema};
 use serde_json::{from_slice, Value};
 